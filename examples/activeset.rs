@@ -0,0 +1,104 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+extern crate ndarray;
+use argmin::prelude::*;
+use argmin::solver::qp::ActiveSetQP;
+use ndarray::{array, Array1, Array2};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+// Squared distance `1/2 ||x - target||^2`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct Distance {
+    target: Array1<f64>,
+}
+
+impl ArgminOp for Distance {
+    type Param = Array1<f64>;
+    type Output = f64;
+    type Hessian = Array2<f64>;
+    type Jacobian = ();
+
+    fn apply(&self, x: &Self::Param) -> Result<Self::Output, Error> {
+        let d = x - &self.target;
+        Ok(0.5 * d.dot(&d))
+    }
+
+    fn gradient(&self, x: &Self::Param) -> Result<Self::Param, Error> {
+        Ok(x - &self.target)
+    }
+
+    fn hessian(&self, x: &Self::Param) -> Result<Self::Hessian, Error> {
+        Ok(Array2::eye(x.len()))
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Project a sequence of slowly moving points onto the polytope
+    // `0 <= x_i <= 1, x_1 + x_2 + x_3 <= 1.5`. The active set of one projection is used to warm
+    // start the next one.
+    let rows = vec![array![1.0, 1.0, 1.0]];
+    let rhs = vec![1.5];
+    let lower = array![0.0, 0.0, 0.0];
+    let upper = array![1.0, 1.0, 1.0];
+
+    let mut active_set = vec![];
+    for k in 0..5 {
+        let t = f64::from(k) * 0.1;
+        let operator = Distance {
+            target: array![1.2 - t, 0.4 + t, -0.2 + t],
+        };
+
+        let solver = ActiveSetQP::new(active_set.clone())
+            .inequality(rows.clone(), rhs.clone())?
+            .bounds(&lower, &upper)?;
+
+        let res = Executor::new(operator, solver, Array1::zeros(3))
+            .max_iters(100)
+            .run()?;
+
+        let x = res.state.get_best_param();
+
+        // Constraints which are active at the solution (in the order in which they were added to
+        // the solver: the general constraint, the upper bounds and the lower bounds)
+        let normals = rows
+            .iter()
+            .cloned()
+            .chain((0..3).map(|i| Array2::eye(3).row(i).to_owned()))
+            .chain((0..3).map(|i| -Array2::eye(3).row(i).to_owned()));
+        let bounds = rhs
+            .iter()
+            .cloned()
+            .chain(upper.iter().cloned())
+            .chain(lower.iter().map(|l| -l));
+        active_set = normals
+            .zip(bounds)
+            .enumerate()
+            .filter(|(_, (a, b))| (a.dot(&x) - b).abs() < 1e-8)
+            .map(|(i, _)| i)
+            .collect();
+
+        println!(
+            "target {}: x = {}, active set: {:?}, iterations: {}",
+            k,
+            x,
+            active_set,
+            res.state.get_iter()
+        );
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
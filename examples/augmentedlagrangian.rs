@@ -0,0 +1,112 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+extern crate ndarray;
+use argmin::prelude::*;
+use argmin::solver::augmentedlagrangian::AugmentedLagrangian;
+use argmin::solver::linesearch::MoreThuenteLineSearch;
+use argmin::solver::newton::NewtonCG;
+use argmin::solver::quasinewton::LBFGS;
+use argmin::testfunctions::{rosenbrock_2d, rosenbrock_2d_derivative, rosenbrock_2d_hessian};
+use ndarray::{array, Array, Array1, Array2};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+// Rosenbrock function restricted to the unit disk `x^2 + y^2 - 1 <= 0`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct Rosenbrock {
+    a: f64,
+    b: f64,
+}
+
+impl ArgminOp for Rosenbrock {
+    type Param = Array1<f64>;
+    type Output = f64;
+    type Hessian = Array2<f64>;
+    type Jacobian = ();
+
+    fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(rosenbrock_2d(&p.to_vec(), self.a, self.b))
+    }
+
+    fn gradient(&self, p: &Self::Param) -> Result<Self::Param, Error> {
+        Ok(Array1::from(rosenbrock_2d_derivative(
+            &p.to_vec(),
+            self.a,
+            self.b,
+        )))
+    }
+
+    fn hessian(&self, p: &Self::Param) -> Result<Self::Hessian, Error> {
+        let h = rosenbrock_2d_hessian(&p.to_vec(), self.a, self.b);
+        Ok(Array::from_shape_vec((2, 2), h)?)
+    }
+}
+
+impl ArgminConstraints for Rosenbrock {
+    fn ineq_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+        Ok(vec![p[0].powi(2) + p[1].powi(2) - 1.0])
+    }
+
+    fn ineq_jacobian(&self, p: &Self::Param) -> Result<Vec<Self::Param>, Error> {
+        Ok(vec![array![2.0 * p[0], 2.0 * p[1]]])
+    }
+
+    fn ineq_hessians(&self, _p: &Self::Param) -> Result<Vec<Self::Hessian>, Error> {
+        Ok(vec![Array2::eye(2) * 2.0])
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Define cost function
+    let cost = Rosenbrock { a: 1.0, b: 100.0 };
+
+    // Define initial parameter vector
+    let init_param: Array1<f64> = array![-0.5, 0.5];
+
+    // Solve subproblems with L-BFGS
+    let linesearch = MoreThuenteLineSearch::new();
+    let solver = AugmentedLagrangian::new(LBFGS::new(linesearch, 7)).tol(1e-8)?;
+
+    let res = Executor::new(cost.clone(), solver, init_param.clone())
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(50)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Print result
+    println!("{}", res);
+
+    // Solve subproblems with Newton-CG, which requires the Hessians of the constraints
+    let linesearch = MoreThuenteLineSearch::new();
+    let solver = AugmentedLagrangian::new(NewtonCG::new(linesearch))
+        .with_hessian()
+        .tol(1e-8)?;
+
+    let res = Executor::new(cost, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(50)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Print result
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+extern crate ndarray;
+use argmin::autodiff::{AutoDiffOp, DualNum};
+use argmin::prelude::*;
+use argmin::solver::newton::Newton;
+use ndarray::{array, Array1, Array2};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Only the generic cost function is implemented, derivatives are computed by `AutoDiffOp`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct Rosenbrock {
+    a: f64,
+    b: f64,
+}
+
+impl ArgminOp for Rosenbrock {
+    type Param = Array1<f64>;
+    type Output = f64;
+    type Hessian = Array2<f64>;
+    type Jacobian = ();
+}
+
+impl ArgminDualOp for Rosenbrock {
+    fn apply_dual<T: DualNum>(&self, p: &[T]) -> Result<T, Error> {
+        let t1 = -p[0] + self.a;
+        let t2 = p[1] - p[0] * p[0];
+        Ok(t1 * t1 + t2 * t2 * self.b)
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Define cost function with exact gradient and Hessian
+    let cost = AutoDiffOp::new(Rosenbrock { a: 1.0, b: 100.0 });
+
+    // Define initial parameter vector
+    let init_param: Array1<f64> = array![-1.2, 1.0];
+
+    // Set up solver
+    let solver = Newton::new();
+
+    // Run solver
+    let res = Executor::new(cost, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(20)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Print result
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
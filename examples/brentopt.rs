@@ -0,0 +1,46 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+use argmin::prelude::*;
+use argmin::solver::brent::BrentOpt;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Test function: `(x - a)^2 + b * sin(x)`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct TestFunc {
+    a: f64,
+    b: f64,
+}
+
+impl ArgminOp for TestFunc {
+    // one dimensional problem, no vector needed
+    type Param = f64;
+    type Output = f64;
+    type Hessian = ();
+    type Jacobian = ();
+
+    fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        Ok((p - self.a).powi(2) + self.b * p.sin())
+    }
+}
+
+fn main() {
+    let cost = TestFunc { a: 5.0, b: 2.0 };
+    // No interval needed: the minimum is bracketed automatically starting from `init_param`.
+    let init_param = -3.0;
+    let solver = BrentOpt::new(1e-8).unwrap();
+
+    let res = Executor::new(cost, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(100)
+        .run()
+        .unwrap();
+    println!("Result of Brent minimizer:\n{}", res);
+}
@@ -6,6 +6,8 @@
 // copied, modified, or distributed except according to those terms.
 
 extern crate argmin;
+#[allow(unused_imports)]
+use argmin::preconditioner::JacobiPreconditioner;
 use argmin::prelude::*;
 use argmin::solver::conjugategradient::ConjugateGradient;
 #[cfg(feature = "serde1")]
@@ -39,6 +41,9 @@ fn run() -> Result<(), Error> {
     // Set up the solver
     let solver: ConjugateGradient<_, f64> = ConjugateGradient::new(b)?;
 
+    // Optionally, use the diagonal of `A` as preconditioner
+    // let solver = solver.preconditioner(JacobiPreconditioner::new(&vec![4.0, 3.0])?);
+
     // Run solver
     let res = Executor::new(operator, solver, init_param)
         .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
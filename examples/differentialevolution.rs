@@ -0,0 +1,60 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+use argmin::prelude::*;
+use argmin::solver::differentialevolution::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+use argmin_testfunctions::himmelblau;
+
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Default, Clone)]
+struct Himmelblau {}
+
+impl ArgminOp for Himmelblau {
+    type Param = Vec<f64>;
+    type Output = f64;
+    type Hessian = ();
+    type Jacobian = ();
+
+    fn apply(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(himmelblau(param))
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Define inital parameter vector
+    let init_param: Vec<f64> = vec![0.1, 0.1];
+
+    let cost_function = Himmelblau {};
+
+    let solver = DifferentialEvolution::new((vec![-4.0, -4.0], vec![4.0, 4.0]), 40)?
+        .strategy(DEStrategy::CurrentToBest1Bin)
+        .adaptation(DEAdaptation::JADE { c: 0.1, p: 0.1 })?;
+
+    let res = Executor::new(cost_function, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Every(10))
+        .max_iters(100)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Print Result
+    println!("{}", res);
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+extern crate ndarray;
+use argmin::numdiff::{CentralDiff, Complex64, ComplexStep, CountFiniteDiff, FiniteDiffOp};
+use argmin::prelude::*;
+use argmin::solver::linesearch::MoreThuenteLineSearch;
+use argmin::solver::newton::Newton;
+use argmin::solver::quasinewton::LBFGS;
+use argmin::testfunctions::rosenbrock_2d;
+use ndarray::{array, Array1, Array2};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Only the cost function is implemented, derivatives are computed by `FiniteDiffOp`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct Rosenbrock {
+    a: f64,
+    b: f64,
+}
+
+impl ArgminOp for Rosenbrock {
+    type Param = Array1<f64>;
+    type Output = f64;
+    type Hessian = Array2<f64>;
+    type Jacobian = ();
+
+    fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(rosenbrock_2d(&p.to_vec(), self.a, self.b))
+    }
+}
+
+/// Required for complex-step differences
+impl ArgminComplexOp for Rosenbrock {
+    fn apply_complex(&self, p: &[Complex64]) -> Result<Vec<Complex64>, Error> {
+        let t1 = self.a - p[0];
+        let t2 = p[1] - p[0] * p[0];
+        Ok(vec![t1 * t1 + self.b * t2 * t2])
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Define cost function with central differences
+    let cost = FiniteDiffOp::new(Rosenbrock { a: 1.0, b: 100.0 }).method(CentralDiff);
+
+    // Define initial parameter vector
+    let init_param: Array1<f64> = array![-1.2, 1.0];
+
+    // Set up solver (`CountFiniteDiff` adds the evaluations for the differences to the counts)
+    let solver = CountFiniteDiff::new(LBFGS::new(MoreThuenteLineSearch::new(), 7));
+
+    // Run solver
+    let res = Executor::new(cost, solver, init_param.clone())
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(100)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Print result
+    println!("{}", res);
+    println!(
+        "Evaluations of the cost function (including finite differences): {}",
+        res.state.get_cost_func_count()
+    );
+
+    // Newton's method with gradient and Hessian from complex-step differences
+    let cost = FiniteDiffOp::new(Rosenbrock { a: 1.0, b: 100.0 }).method(ComplexStep);
+    let res = Executor::new(cost, CountFiniteDiff::new(Newton::new()), init_param)
+        .max_iters(20)
+        .run()?;
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
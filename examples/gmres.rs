@@ -0,0 +1,65 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+use argmin::prelude::*;
+use argmin::solver::krylov::GMRES;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct MyProblem {}
+
+impl ArgminOp for MyProblem {
+    type Param = Vec<f64>;
+    type Output = Vec<f64>;
+    type Hessian = ();
+    type Jacobian = ();
+
+    // Non-symmetric matrix
+    fn apply(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+        Ok(vec![
+            4.0 * p[0] + 1.0 * p[1] - 1.0 * p[2],
+            -2.0 * p[0] + 5.0 * p[1] + 1.0 * p[2],
+            1.0 * p[0] - 3.0 * p[1] + 6.0 * p[2],
+        ])
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Define inital parameter vector
+    let init_param: Vec<f64> = vec![0.0, 0.0, 0.0];
+
+    // Define the right hand side `b` of `A * x = b`
+    let b = vec![1.0, 2.0, 3.0];
+
+    // Set up operator
+    let operator = MyProblem {};
+
+    // Set up the solver
+    let solver = GMRES::new(b, 2)?;
+
+    // Run solver
+    let res = Executor::new(operator, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(100)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing to screen again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Print result
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+    }
+}
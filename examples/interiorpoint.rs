@@ -0,0 +1,88 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+extern crate ndarray;
+use argmin::prelude::*;
+use argmin::solver::qp::InteriorPointQP;
+use ndarray::{array, Array1, Array2};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+// Mean-variance portfolio optimization: minimize `1/2 w^T S w - r * mu^T w` where `S` is the
+// covariance matrix of the returns, `mu` the expected returns and `r` the risk tolerance.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct Portfolio {
+    cov: Array2<f64>,
+    mu: Array1<f64>,
+    risk_tolerance: f64,
+}
+
+impl ArgminOp for Portfolio {
+    type Param = Array1<f64>;
+    type Output = f64;
+    type Hessian = Array2<f64>;
+    type Jacobian = ();
+
+    fn apply(&self, w: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(0.5 * w.dot(&self.cov.dot(w)) - self.risk_tolerance * self.mu.dot(w))
+    }
+
+    fn gradient(&self, w: &Self::Param) -> Result<Self::Param, Error> {
+        Ok(self.cov.dot(w) - self.risk_tolerance * &self.mu)
+    }
+
+    fn hessian(&self, _w: &Self::Param) -> Result<Self::Hessian, Error> {
+        Ok(self.cov.clone())
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let operator = Portfolio {
+        cov: array![
+            [0.040, 0.006, 0.002, 0.000],
+            [0.006, 0.090, 0.010, 0.004],
+            [0.002, 0.010, 0.010, 0.001],
+            [0.000, 0.004, 0.001, 0.160]
+        ],
+        mu: array![0.06, 0.10, 0.03, 0.14],
+        risk_tolerance: 0.2,
+    };
+
+    // Weights sum up to one ...
+    let a = Array2::ones((1, 4));
+    let b = array![1.0];
+    // ... and are nonnegative (no short selling)
+    let g = -Array2::eye(4);
+    let h = Array1::zeros(4);
+
+    let solver = InteriorPointQP::new()
+        .equality(a, b)?
+        .inequality(g, h)?
+        .tol(1e-10)?;
+
+    // The initial guess does not need to be feasible
+    let init_param = Array1::zeros(4);
+
+    let res = Executor::new(operator, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(50)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing to screen again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+use argmin::prelude::*;
+use argmin::solver::linprog::RevisedSimplex;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+// Linear objective `c^T x`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct Linear {
+    c: Vec<f64>,
+}
+
+impl ArgminOp for Linear {
+    type Param = Vec<f64>;
+    type Output = f64;
+    type Hessian = ();
+    type Jacobian = ();
+
+    fn apply(&self, x: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(self.c.iter().zip(x.iter()).map(|(c, x)| c * x).sum())
+    }
+
+    fn gradient(&self, _x: &Self::Param) -> Result<Self::Param, Error> {
+        Ok(self.c.clone())
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Diet problem: choose amounts of three foods with costs 0.6, 0.35 and 0.5 per unit such that
+    // the total cost is minimized and minimum requirements for two nutrients are satisfied.
+    let operator = Linear {
+        c: vec![0.6, 0.35, 0.5],
+    };
+
+    // Nutrient contents per unit of food must add up to at least 8 and 10 units, respectively
+    // (written as `-A x <= -b`). At most 6 units of the second food are available.
+    let solver = RevisedSimplex::new()
+        .inequality(
+            vec![
+                vec![-2.0, -1.0, -1.0],
+                vec![-1.0, -2.0, -3.0],
+                vec![0.0, 1.0, 0.0],
+            ],
+            vec![-8.0, -10.0, 6.0],
+        )?
+        // The total amount of food is fixed
+        .equality(vec![vec![1.0, 1.0, 1.0]], vec![6.0])?;
+
+    // The initial parameter vector only determines the number of variables
+    let init_param: Vec<f64> = vec![0.0; 3];
+
+    let res = Executor::new(operator, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(100)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing to screen again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+extern crate ndarray;
+use argmin::prelude::*;
+use argmin::solver::nonlinearsystem::{Broyden, BroydenUpdate, NewtonRaphson};
+use ndarray::{array, Array1, Array2};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+// System of equations:
+// `x^2 + y^2 - 4 = 0`
+// `exp(x) + y - 1 = 0`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct System {}
+
+impl ArgminOp for System {
+    type Param = Array1<f64>;
+    type Output = Array1<f64>;
+    type Hessian = ();
+    type Jacobian = Array2<f64>;
+
+    fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(array![
+            p[0].powi(2) + p[1].powi(2) - 4.0,
+            p[0].exp() + p[1] - 1.0
+        ])
+    }
+
+    fn jacobian(&self, p: &Self::Param) -> Result<Self::Jacobian, Error> {
+        Ok(array![[2.0 * p[0], 2.0 * p[1]], [p[0].exp(), 1.0]])
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Define initial parameter vector
+    let init_param: Array1<f64> = array![1.0, 1.0];
+
+    // Newton-Raphson
+    let res = Executor::new(System {}, NewtonRaphson::new(), init_param.clone())
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(50)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    println!("{}", res);
+
+    // Broyden's method
+    let res = Executor::new(System {}, Broyden::new(BroydenUpdate::Good), init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(50)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
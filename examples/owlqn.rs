@@ -0,0 +1,103 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+use argmin::prelude::*;
+use argmin::solver::quasinewton::OWLQN;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Negative log-likelihood of a logistic regression model
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct LogisticRegression {
+    features: Vec<Vec<f64>>,
+    labels: Vec<f64>,
+}
+
+impl LogisticRegression {
+    /// Synthetic data set where only the first two of `n` features are informative
+    fn new(samples: usize, n: usize) -> Self {
+        let features: Vec<Vec<f64>> = (0..samples)
+            .map(|i| (0..n).map(|j| ((i * (j + 3) + j) as f64).sin()).collect())
+            .collect();
+        let labels = features
+            .iter()
+            .map(|x| {
+                if 2.0 * x[0] - 3.0 * x[1] > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        LogisticRegression { features, labels }
+    }
+}
+
+impl ArgminOp for LogisticRegression {
+    type Param = Vec<f64>;
+    type Output = f64;
+    type Hessian = ();
+    type Jacobian = ();
+
+    fn apply(&self, w: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(self
+            .features
+            .iter()
+            .zip(self.labels.iter())
+            .map(|(x, y)| {
+                let z: f64 = x.dot(w);
+                (1.0 + z.exp()).ln() - y * z
+            })
+            .sum())
+    }
+
+    fn gradient(&self, w: &Self::Param) -> Result<Self::Param, Error> {
+        let mut grad = vec![0.0; w.len()];
+        for (x, y) in self.features.iter().zip(self.labels.iter()) {
+            let z: f64 = x.dot(w);
+            let r = 1.0 / (1.0 + (-z).exp()) - y;
+            grad = grad.scaled_add(&r, x);
+        }
+        Ok(grad)
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Define cost function
+    let cost = LogisticRegression::new(200, 10);
+
+    // Define initial parameter vector
+    let init_param: Vec<f64> = vec![0.0; 10];
+
+    // L1 weight of each coordinate
+    let l1_weights = vec![2.0; 10];
+
+    // Set up solver
+    let solver = OWLQN::new(7, l1_weights)?;
+
+    // Run solver
+    let res = Executor::new(cost, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(100)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Print result
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+extern crate ndarray;
+use argmin::prelude::*;
+use argmin::solver::proximalgradient::{FISTA, ISTA, L1};
+use ndarray::{array, Array1, Array2};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+// Least squares part `0.5 * ||A x - b||^2` of a lasso problem
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct LeastSquares {
+    a: Array2<f64>,
+    b: Array1<f64>,
+}
+
+impl ArgminOp for LeastSquares {
+    type Param = Array1<f64>;
+    type Output = f64;
+    type Hessian = ();
+    type Jacobian = ();
+
+    fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        let r = self.a.dot(p) - &self.b;
+        Ok(0.5 * r.dot(&r))
+    }
+
+    fn gradient(&self, p: &Self::Param) -> Result<Self::Param, Error> {
+        Ok(self
+            .a
+            .view()
+            .reversed_axes()
+            .dot(&(self.a.dot(p) - &self.b)))
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Underdetermined system with a sparse solution `[0, 2, 0, 0, -1, 0]`
+    let a = array![
+        [1.0, 0.5, -0.3, 0.2, 0.0, 0.7],
+        [0.2, 1.0, 0.4, -0.6, 0.3, 0.0],
+        [-0.5, 0.1, 1.0, 0.3, -0.8, 0.2],
+        [0.0, -0.4, 0.6, 1.0, 0.5, -0.3]
+    ];
+    let b = a.dot(&array![0.0, 2.0, 0.0, 0.0, -1.0, 0.0]);
+    let operator = LeastSquares { a, b };
+
+    // define inital parameter vector
+    let init_param: Array1<f64> = Array1::zeros(6);
+
+    // L1 regularization
+    let lambda = 0.01;
+
+    let solver = ISTA::new(L1::new(lambda)?).tol(1e-6)?;
+
+    let res = Executor::new(operator.clone(), solver, init_param.clone())
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Every(100))
+        .max_iters(10000)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing to screen again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    println!("{}", res);
+
+    let solver = FISTA::new(L1::new(lambda)?).tol(1e-6)?.restart(true);
+
+    let res = Executor::new(operator, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Every(100))
+        .max_iters(10000)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing to screen again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
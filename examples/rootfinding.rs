@@ -0,0 +1,66 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+use argmin::prelude::*;
+use argmin::solver::rootfinding::{Bisection, Illinois, NewtonSafe, Ridders};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Test function: `x^3 - 2x - 5`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct TestFunc {}
+
+impl ArgminOp for TestFunc {
+    // one dimensional problem, no vector needed
+    type Param = f64;
+    type Output = f64;
+    type Hessian = ();
+    type Jacobian = ();
+
+    fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(p.powi(3) - 2.0 * p - 5.0)
+    }
+
+    fn gradient(&self, p: &Self::Param) -> Result<Self::Param, Error> {
+        Ok(3.0 * p.powi(2) - 2.0)
+    }
+}
+
+fn run<S: Solver<TestFunc>>(solver: S) -> Result<(), Error> {
+    let res = Executor::new(TestFunc {}, solver, 0.0)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(100)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    // The interval [-1, 0] does not contain the root at 2.0946 and is expanded automatically.
+    let res = Bisection::new(-1.0, 0.0, 1e-11)
+        .and_then(|s| s.expand_bracket(1.6))
+        .and_then(run)
+        .and_then(|_| Illinois::new(-1.0, 0.0, 1e-11))
+        .and_then(|s| s.expand_bracket(1.6))
+        .and_then(run)
+        .and_then(|_| Ridders::new(-1.0, 0.0, 1e-11))
+        .and_then(|s| s.expand_bracket(1.6))
+        .and_then(run)
+        .and_then(|_| NewtonSafe::new(-1.0, 0.0, 1e-11))
+        .and_then(|s| s.expand_bracket(1.6))
+        .and_then(run);
+    if let Err(ref e) = res {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+extern crate ndarray;
+use argmin::prelude::*;
+use argmin::solver::sqp::SQP;
+use ndarray::{array, Array1, Array2};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+// Problem 71 of the Hock-Schittkowski test collection:
+//
+// min x1 x4 (x1 + x2 + x3) + x3
+// subject to x1 x2 x3 x4 >= 25, x1^2 + x2^2 + x3^2 + x4^2 = 40, 1 <= x_i <= 5
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct HS71 {}
+
+impl ArgminOp for HS71 {
+    type Param = Array1<f64>;
+    type Output = f64;
+    type Hessian = Array2<f64>;
+    type Jacobian = ();
+
+    fn apply(&self, x: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(x[0] * x[3] * (x[0] + x[1] + x[2]) + x[2])
+    }
+
+    fn gradient(&self, x: &Self::Param) -> Result<Self::Param, Error> {
+        Ok(array![
+            x[3] * (2.0 * x[0] + x[1] + x[2]),
+            x[0] * x[3],
+            x[0] * x[3] + 1.0,
+            x[0] * (x[0] + x[1] + x[2])
+        ])
+    }
+}
+
+impl ArgminConstraints for HS71 {
+    fn eq_constraints(&self, x: &Self::Param) -> Result<Vec<f64>, Error> {
+        Ok(vec![x.dot(x) - 40.0])
+    }
+
+    fn eq_jacobian(&self, x: &Self::Param) -> Result<Vec<Self::Param>, Error> {
+        Ok(vec![x * 2.0])
+    }
+
+    fn ineq_constraints(&self, x: &Self::Param) -> Result<Vec<f64>, Error> {
+        // 25 - x1 x2 x3 x4 <= 0, followed by the bounds
+        let mut g = vec![25.0 - x.iter().product::<f64>()];
+        g.extend(x.iter().map(|xi| 1.0 - xi));
+        g.extend(x.iter().map(|xi| xi - 5.0));
+        Ok(g)
+    }
+
+    fn ineq_jacobian(&self, x: &Self::Param) -> Result<Vec<Self::Param>, Error> {
+        let prod = array![
+            x[1] * x[2] * x[3],
+            x[0] * x[2] * x[3],
+            x[0] * x[1] * x[3],
+            x[0] * x[1] * x[2]
+        ];
+        let eye: Array2<f64> = Array2::eye(4);
+        let mut jac = vec![-prod];
+        jac.extend(eye.genrows().into_iter().map(|row| -row.to_owned()));
+        jac.extend(eye.genrows().into_iter().map(|row| row.to_owned()));
+        Ok(jac)
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Initial approximation of the Hessian of the Lagrangian
+    let init_hessian: Array2<f64> = Array2::eye(4);
+
+    let solver = SQP::new(init_hessian).tol(1e-8)?;
+
+    let init_param: Array1<f64> = array![1.0, 5.0, 5.0, 1.0];
+
+    let res = Executor::new(HS71 {}, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Always)
+        .max_iters(100)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing to screen again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    println!("{}", res);
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
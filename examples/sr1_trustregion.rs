@@ -10,7 +10,7 @@ extern crate ndarray;
 use argmin::prelude::*;
 use argmin::solver::quasinewton::SR1TrustRegion;
 #[allow(unused_imports)]
-use argmin::solver::trustregion::{CauchyPoint, Dogleg, Steihaug, TrustRegion};
+use argmin::solver::trustregion::{CauchyPoint, Dogleg, Steihaug, TrustRegion, GLTR};
 use argmin::testfunctions::rosenbrock;
 use argmin_core::finitediff::*;
 use ndarray::{array, Array1, Array2};
@@ -63,6 +63,7 @@ fn run() -> Result<(), Error> {
     let subproblem = Steihaug::new().max_iters(20);
     // let subproblem = CauchyPoint::new();
     // let subproblem = Dogleg::new();
+    // let subproblem = GLTR::new().max_iters(20);
 
     // Set up solver
     let solver = SR1TrustRegion::new(subproblem);
@@ -0,0 +1,128 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+extern crate argmin;
+extern crate rand;
+extern crate rand_xorshift;
+use argmin::prelude::*;
+use argmin::solver::stochastic::{Adam, SGD};
+use rand::prelude::*;
+use rand_xorshift::XorShiftRng;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+// Logistic regression: mean negative log-likelihood of labels `y_i` in {0, 1} given features
+// `x_i`; the first parameter is the bias.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+struct LogisticRegression {
+    x: Vec<Vec<f64>>,
+    y: Vec<f64>,
+}
+
+impl LogisticRegression {
+    // Generate a data set from the model with parameters `[0.5, 2.0, -1.0]`
+    fn new(num_samples: usize) -> Self {
+        let mut rng = XorShiftRng::seed_from_u64(0);
+        let w = [0.5, 2.0, -1.0];
+        let mut x = vec![];
+        let mut y = vec![];
+        for _ in 0..num_samples {
+            let xi = vec![1.0, rng.gen_range(-2.0, 2.0), rng.gen_range(-2.0, 2.0)];
+            let p = sigmoid(xi.iter().zip(w.iter()).map(|(a, b)| a * b).sum());
+            y.push(if rng.gen::<f64>() < p { 1.0 } else { 0.0 });
+            x.push(xi);
+        }
+        LogisticRegression { x, y }
+    }
+
+    fn predict(&self, p: &[f64], i: usize) -> f64 {
+        sigmoid(self.x[i].iter().zip(p.iter()).map(|(a, b)| a * b).sum())
+    }
+}
+
+fn sigmoid(t: f64) -> f64 {
+    1.0 / (1.0 + (-t).exp())
+}
+
+impl ArgminOp for LogisticRegression {
+    type Param = Vec<f64>;
+    type Output = f64;
+    type Hessian = ();
+    type Jacobian = ();
+}
+
+impl ArgminMinibatchOp for LogisticRegression {
+    fn num_samples(&self) -> usize {
+        self.y.len()
+    }
+
+    fn batch_apply(&self, p: &Vec<f64>, indices: &[usize]) -> Result<f64, Error> {
+        let nll: f64 = indices
+            .iter()
+            .map(|&i| {
+                let q = self.predict(p, i).max(1e-12).min(1.0 - 1e-12);
+                -(self.y[i] * q.ln() + (1.0 - self.y[i]) * (1.0 - q).ln())
+            })
+            .sum();
+        Ok(nll / indices.len() as f64)
+    }
+
+    fn batch_gradient(&self, p: &Vec<f64>, indices: &[usize]) -> Result<Vec<f64>, Error> {
+        let mut grad = vec![0.0; p.len()];
+        for &i in indices {
+            let r = (self.predict(p, i) - self.y[i]) / indices.len() as f64;
+            grad = grad.scaled_add(&r, &self.x[i]);
+        }
+        Ok(grad)
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let operator = LogisticRegression::new(1000);
+
+    // define inital parameter vector
+    let init_param: Vec<f64> = vec![0.0, 0.0, 0.0];
+
+    // 50 epochs of SGD with Nesterov momentum
+    let solver = SGD::new(0.05)?
+        .momentum(0.9)?
+        .nesterov(true)
+        .batch_size(32)?
+        .seed(42);
+
+    let res = Executor::new(operator.clone(), solver, init_param.clone())
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Every(320))
+        .max_iters(50 * 32)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing to screen again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    println!("{}", res);
+    println!("final parameters: {:?}", res.state.get_param());
+
+    // 50 epochs of Adam
+    let solver = Adam::new(0.01)?.batch_size(32)?.seed(42);
+
+    let res = Executor::new(operator, solver, init_param)
+        .add_observer(ArgminSlogLogger::term(), ObserverMode::Every(320))
+        .max_iters(50 * 32)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing to screen again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    println!("{}", res);
+    println!("final parameters: {:?}", res.state.get_param());
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{} {}", e.as_fail(), e.backtrace());
+        std::process::exit(1);
+    }
+}
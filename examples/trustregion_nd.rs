@@ -9,7 +9,7 @@ extern crate argmin;
 extern crate ndarray;
 use argmin::prelude::*;
 #[allow(unused_imports)]
-use argmin::solver::trustregion::{CauchyPoint, Dogleg, Steihaug, TrustRegion};
+use argmin::solver::trustregion::{CauchyPoint, Dogleg, MoreSorensen, Steihaug, TrustRegion, GLTR};
 use argmin::testfunctions::{rosenbrock_2d, rosenbrock_2d_derivative, rosenbrock_2d_hessian};
 use ndarray::{Array, Array1, Array2};
 #[cfg(feature = "serde1")]
@@ -58,11 +58,16 @@ fn run() -> Result<(), Error> {
 
     // Set up the subproblem
     // let subproblem = Steihaug::new().max_iters(2);
+    // let subproblem = GLTR::new();
     // let subproblem = CauchyPoint::new();
+    // let subproblem = MoreSorensen::new();
     let subproblem = Dogleg::new();
 
     // Set up solver
     let solver = TrustRegion::new(subproblem);
+    // Matrix-free: only uses Hessian-vector products (requires Steihaug or GLTR in matrix-free
+    // mode and an implementation of `ArgminHessianVecProduct`)
+    // let solver = TrustRegion::new(Steihaug::new().matrix_free()).matrix_free();
 
     // Run solver
     let res = Executor::new(cost, solver, init_param)
@@ -0,0 +1,935 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Forward-mode automatic differentiation
+//!
+//! `Dual` numbers `a + b ε` with `ε² = 0` carry the derivative of a computation along with its
+//! value: evaluating a function at `x + ε` yields `f(x) + f'(x) ε`. `HyperDual` numbers
+//! `a + b ε1 + c ε2 + d ε1ε2` with `ε1² = ε2² = 0` additionally carry second derivatives:
+//! evaluating a function at `x + ε1 e_i + ε2 e_j` yields the Hessian entry `H_ij` as the
+//! `ε1ε2` part. Both types implement the arithmetic operators, common elementary functions (via
+//! the `DualNum` trait) and the argmin math traits (`ArgminAdd`, `ArgminMul`, `ArgminDot`, ...).
+//!
+//! A cost function written once for any `T: DualNum` (`f64` is a `DualNum` as well) in
+//! `ArgminDualOp::apply_dual` can be wrapped in an `AutoDiffOp`, which computes exact gradients
+//! (one evaluation with `Dual` numbers per parameter) and Hessians (`n (n + 1) / 2` evaluations
+//! with `HyperDual` numbers) and thereby makes every solver applicable:
+//!
+//! ```rust
+//! # extern crate argmin;
+//! # use argmin::prelude::*;
+//! # use argmin::autodiff::{AutoDiffOp, DualNum};
+//! # use argmin::solver::linesearch::MoreThuenteLineSearch;
+//! # use argmin::solver::quasinewton::LBFGS;
+//! # #[cfg(feature = "serde1")]
+//! # use serde::{Deserialize, Serialize};
+//! #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+//! #[derive(Clone, Default)]
+//! struct Rosenbrock {
+//!     a: f64,
+//!     b: f64,
+//! }
+//!
+//! impl ArgminOp for Rosenbrock {
+//!     type Param = Vec<f64>;
+//!     type Output = f64;
+//!     type Hessian = Vec<Vec<f64>>;
+//!     type Jacobian = ();
+//! }
+//!
+//! impl ArgminDualOp for Rosenbrock {
+//!     fn apply_dual<T: DualNum>(&self, p: &[T]) -> Result<T, Error> {
+//!         Ok((p[0] - self.a).powi(2) + (p[1] - p[0].powi(2)).powi(2) * self.b)
+//!     }
+//! }
+//!
+//! # fn run() -> Result<(), Error> {
+//! let cost = AutoDiffOp::new(Rosenbrock { a: 1.0, b: 100.0 });
+//! # let hessian = cost.hessian(&vec![1.0, 1.0])?;
+//! # assert!((hessian[0][0] - 802.0).abs() < 1e-12);
+//! # assert!((hessian[0][1] + 400.0).abs() < 1e-12);
+//! let solver = LBFGS::new(MoreThuenteLineSearch::new(), 7);
+//! let res = Executor::new(cost, solver, vec![-1.2, 1.0])
+//!     .max_iters(100)
+//!     .run()?;
+//! # assert!(res.state.get_cost() < 1e-12);
+//! # Ok(())
+//! # }
+//! # fn main() {
+//! #     if let Err(ref e) = run() {
+//! #         println!("{} {}", e.as_fail(), e.backtrace());
+//! #         std::process::exit(1);
+//! #     }
+//! # }
+//! ```
+//!
+//! Since `f64` only appears on the right-hand side of the arithmetic operators in generic code,
+//! constants are best written as `x * 2.0` instead of `2.0 * x`, or converted via `T::from(...)`.
+//! Comparisons (for instance in branches) only consider the value of dual numbers (`re()`).
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] Jeffrey A. Fike and Juan J. Alonso (2011). The development of hyper-dual numbers for exact
+//! second-derivative calculations. 49th AIAA Aerospace Sciences Meeting, AIAA 2011-886.
+
+use crate::numdiff::{not_implemented, FiniteDiffMatrix, FiniteDiffVector};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Scalars which cost functions can be evaluated with: `f64`, `Dual` and `HyperDual`
+///
+/// Besides the arithmetic operators and the argmin math traits, this provides the elementary
+/// functions for which the derivatives are propagated.
+pub trait DualNum:
+    Copy
+    + Debug
+    + Send
+    + Sync
+    + PartialOrd
+    + From<f64>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + Add<f64, Output = Self>
+    + Sub<f64, Output = Self>
+    + Mul<f64, Output = Self>
+    + Div<f64, Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + ArgminAdd<Self, Self>
+    + ArgminSub<Self, Self>
+    + ArgminMul<Self, Self>
+    + ArgminDiv<Self, Self>
+    + ArgminDot<Self, Self>
+    + ArgminNorm<Self>
+    + ArgminZero
+    + ArgminZeroLike
+{
+    /// Value (real part)
+    fn re(&self) -> f64;
+
+    /// Absolute value
+    fn abs(self) -> Self;
+
+    /// `1 / x`
+    fn recip(self) -> Self;
+
+    /// Square root
+    fn sqrt(self) -> Self;
+
+    /// Cube root
+    fn cbrt(self) -> Self;
+
+    /// Exponential function
+    fn exp(self) -> Self;
+
+    /// Natural logarithm
+    fn ln(self) -> Self;
+
+    /// Integer power
+    fn powi(self, n: i32) -> Self;
+
+    /// Real power (prefer `powi` for integer exponents)
+    fn powf(self, p: f64) -> Self;
+
+    /// Sine
+    fn sin(self) -> Self;
+
+    /// Cosine
+    fn cos(self) -> Self;
+
+    /// Tangent
+    fn tan(self) -> Self;
+
+    /// Hyperbolic tangent
+    fn tanh(self) -> Self;
+
+    /// Arctangent
+    fn atan(self) -> Self;
+}
+
+/// Values `(f(x), f'(x), f''(x))` of the elementary functions at `x`
+mod elementary {
+    pub fn abs(x: f64) -> (f64, f64, f64) {
+        (x.abs(), x.signum(), 0.0)
+    }
+
+    pub fn recip(x: f64) -> (f64, f64, f64) {
+        let r = x.recip();
+        (r, -r * r, 2.0 * r * r * r)
+    }
+
+    pub fn sqrt(x: f64) -> (f64, f64, f64) {
+        let s = x.sqrt();
+        (s, 0.5 / s, -0.25 / (s * x))
+    }
+
+    pub fn cbrt(x: f64) -> (f64, f64, f64) {
+        let c = x.cbrt();
+        (c, c / (3.0 * x), -2.0 * c / (9.0 * x * x))
+    }
+
+    pub fn exp(x: f64) -> (f64, f64, f64) {
+        let e = x.exp();
+        (e, e, e)
+    }
+
+    pub fn ln(x: f64) -> (f64, f64, f64) {
+        (x.ln(), x.recip(), -x.powi(-2))
+    }
+
+    pub fn powi(x: f64, n: i32) -> (f64, f64, f64) {
+        let nf = f64::from(n);
+        let d1 = if n == 0 { 0.0 } else { nf * x.powi(n - 1) };
+        let d2 = if n == 0 || n == 1 {
+            0.0
+        } else {
+            nf * (nf - 1.0) * x.powi(n - 2)
+        };
+        (x.powi(n), d1, d2)
+    }
+
+    pub fn powf(x: f64, p: f64) -> (f64, f64, f64) {
+        (
+            x.powf(p),
+            p * x.powf(p - 1.0),
+            p * (p - 1.0) * x.powf(p - 2.0),
+        )
+    }
+
+    pub fn sin(x: f64) -> (f64, f64, f64) {
+        let (s, c) = x.sin_cos();
+        (s, c, -s)
+    }
+
+    pub fn cos(x: f64) -> (f64, f64, f64) {
+        let (s, c) = x.sin_cos();
+        (c, -s, -c)
+    }
+
+    pub fn tan(x: f64) -> (f64, f64, f64) {
+        let t = x.tan();
+        let d = 1.0 + t * t;
+        (t, d, 2.0 * t * d)
+    }
+
+    pub fn tanh(x: f64) -> (f64, f64, f64) {
+        let t = x.tanh();
+        let d = 1.0 - t * t;
+        (t, d, -2.0 * t * d)
+    }
+
+    pub fn atan(x: f64) -> (f64, f64, f64) {
+        let d = (1.0 + x * x).recip();
+        (x.atan(), d, -2.0 * x * d * d)
+    }
+}
+
+impl DualNum for f64 {
+    fn re(&self) -> f64 {
+        *self
+    }
+
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+
+    fn recip(self) -> Self {
+        f64::recip(self)
+    }
+
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+
+    fn cbrt(self) -> Self {
+        f64::cbrt(self)
+    }
+
+    fn exp(self) -> Self {
+        f64::exp(self)
+    }
+
+    fn ln(self) -> Self {
+        f64::ln(self)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        f64::powi(self, n)
+    }
+
+    fn powf(self, p: f64) -> Self {
+        f64::powf(self, p)
+    }
+
+    fn sin(self) -> Self {
+        f64::sin(self)
+    }
+
+    fn cos(self) -> Self {
+        f64::cos(self)
+    }
+
+    fn tan(self) -> Self {
+        f64::tan(self)
+    }
+
+    fn tanh(self) -> Self {
+        f64::tanh(self)
+    }
+
+    fn atan(self) -> Self {
+        f64::atan(self)
+    }
+}
+
+/// Dual number `re + eps ε` with `ε² = 0`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Dual {
+    /// value
+    pub re: f64,
+    /// derivative
+    pub eps: f64,
+}
+
+impl Dual {
+    /// Constructor
+    pub fn new(re: f64, eps: f64) -> Self {
+        Dual { re, eps }
+    }
+
+    /// Applies a function with value `f0` and derivative `f1` at `self.re`
+    fn chain(self, (f0, f1, _): (f64, f64, f64)) -> Self {
+        Dual::new(f0, f1 * self.eps)
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+
+    fn add(self, other: Dual) -> Dual {
+        Dual::new(self.re + other.re, self.eps + other.eps)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+
+    fn sub(self, other: Dual) -> Dual {
+        Dual::new(self.re - other.re, self.eps - other.eps)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+
+    fn mul(self, other: Dual) -> Dual {
+        Dual::new(
+            self.re * other.re,
+            self.re * other.eps + self.eps * other.re,
+        )
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+
+    fn neg(self) -> Dual {
+        Dual::new(-self.re, -self.eps)
+    }
+}
+
+/// Hyper-dual number `re + e1 ε1 + e2 ε2 + e12 ε1ε2` with `ε1² = ε2² = 0`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HyperDual {
+    /// value
+    pub re: f64,
+    /// derivative in direction of `ε1`
+    pub e1: f64,
+    /// derivative in direction of `ε2`
+    pub e2: f64,
+    /// second derivative in directions of `ε1` and `ε2`
+    pub e12: f64,
+}
+
+impl HyperDual {
+    /// Constructor
+    pub fn new(re: f64, e1: f64, e2: f64, e12: f64) -> Self {
+        HyperDual { re, e1, e2, e12 }
+    }
+
+    /// Applies a function with value `f0`, first derivative `f1` and second derivative `f2` at
+    /// `self.re`
+    fn chain(self, (f0, f1, f2): (f64, f64, f64)) -> Self {
+        HyperDual::new(
+            f0,
+            f1 * self.e1,
+            f1 * self.e2,
+            f1 * self.e12 + f2 * self.e1 * self.e2,
+        )
+    }
+}
+
+impl Add for HyperDual {
+    type Output = HyperDual;
+
+    fn add(self, other: HyperDual) -> HyperDual {
+        HyperDual::new(
+            self.re + other.re,
+            self.e1 + other.e1,
+            self.e2 + other.e2,
+            self.e12 + other.e12,
+        )
+    }
+}
+
+impl Sub for HyperDual {
+    type Output = HyperDual;
+
+    fn sub(self, other: HyperDual) -> HyperDual {
+        HyperDual::new(
+            self.re - other.re,
+            self.e1 - other.e1,
+            self.e2 - other.e2,
+            self.e12 - other.e12,
+        )
+    }
+}
+
+impl Mul for HyperDual {
+    type Output = HyperDual;
+
+    fn mul(self, other: HyperDual) -> HyperDual {
+        HyperDual::new(
+            self.re * other.re,
+            self.re * other.e1 + self.e1 * other.re,
+            self.re * other.e2 + self.e2 * other.re,
+            self.re * other.e12 + self.e1 * other.e2 + self.e2 * other.e1 + self.e12 * other.re,
+        )
+    }
+}
+
+impl Neg for HyperDual {
+    type Output = HyperDual;
+
+    fn neg(self) -> HyperDual {
+        HyperDual::new(-self.re, -self.e1, -self.e2, -self.e12)
+    }
+}
+
+/// Implements the remaining operators, `DualNum` and the argmin math traits for a dual number
+/// type which implements `Add`, `Sub`, `Mul`, `Neg` and `chain`
+macro_rules! make_dual {
+    ($t:ident) => {
+        impl From<f64> for $t {
+            fn from(re: f64) -> $t {
+                $t {
+                    re,
+                    ..$t::default()
+                }
+            }
+        }
+
+        impl Div for $t {
+            type Output = $t;
+
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn div(self, other: $t) -> $t {
+                self * other.recip()
+            }
+        }
+
+        make_dual!($t, Add, add, AddAssign, add_assign);
+        make_dual!($t, Sub, sub, SubAssign, sub_assign);
+        make_dual!($t, Mul, mul, MulAssign, mul_assign);
+        make_dual!($t, Div, div, DivAssign, div_assign);
+
+        /// Compares the values only
+        impl PartialEq for $t {
+            fn eq(&self, other: &$t) -> bool {
+                self.partial_cmp(other) == Some(Ordering::Equal)
+            }
+        }
+
+        /// Compares the values only
+        impl PartialOrd for $t {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                self.re.partial_cmp(&other.re)
+            }
+        }
+
+        impl DualNum for $t {
+            fn re(&self) -> f64 {
+                self.re
+            }
+
+            fn abs(self) -> Self {
+                self.chain(elementary::abs(self.re))
+            }
+
+            fn recip(self) -> Self {
+                self.chain(elementary::recip(self.re))
+            }
+
+            fn sqrt(self) -> Self {
+                self.chain(elementary::sqrt(self.re))
+            }
+
+            fn cbrt(self) -> Self {
+                self.chain(elementary::cbrt(self.re))
+            }
+
+            fn exp(self) -> Self {
+                self.chain(elementary::exp(self.re))
+            }
+
+            fn ln(self) -> Self {
+                self.chain(elementary::ln(self.re))
+            }
+
+            fn powi(self, n: i32) -> Self {
+                self.chain(elementary::powi(self.re, n))
+            }
+
+            fn powf(self, p: f64) -> Self {
+                self.chain(elementary::powf(self.re, p))
+            }
+
+            fn sin(self) -> Self {
+                self.chain(elementary::sin(self.re))
+            }
+
+            fn cos(self) -> Self {
+                self.chain(elementary::cos(self.re))
+            }
+
+            fn tan(self) -> Self {
+                self.chain(elementary::tan(self.re))
+            }
+
+            fn tanh(self) -> Self {
+                self.chain(elementary::tanh(self.re))
+            }
+
+            fn atan(self) -> Self {
+                self.chain(elementary::atan(self.re))
+            }
+        }
+
+        make_dual!($t, ArgminAdd, add, +);
+        make_dual!($t, ArgminSub, sub, -);
+        make_dual!($t, ArgminMul, mul, *);
+        make_dual!($t, ArgminDiv, div, /);
+
+        impl ArgminDot<$t, $t> for $t {
+            fn dot(&self, other: &$t) -> $t {
+                *self * *other
+            }
+        }
+
+        impl ArgminDot<$t, $t> for f64 {
+            fn dot(&self, other: &$t) -> $t {
+                *self * *other
+            }
+        }
+
+        impl ArgminDot<f64, $t> for $t {
+            fn dot(&self, other: &f64) -> $t {
+                *self * *other
+            }
+        }
+
+        impl ArgminNorm<$t> for $t {
+            fn norm(&self) -> $t {
+                DualNum::abs(*self)
+            }
+        }
+
+        impl ArgminZero for $t {
+            fn zero() -> $t {
+                $t::default()
+            }
+        }
+
+        impl ArgminZeroLike for $t {
+            fn zero_like(&self) -> $t {
+                $t::default()
+            }
+        }
+
+        impl ArgminConj for $t {
+            fn conj(&self) -> $t {
+                *self
+            }
+        }
+
+        impl ArgminTranspose for $t {
+            fn t(self) -> $t {
+                self
+            }
+        }
+
+        impl ArgminEye for $t {
+            fn eye(_n: usize) -> $t {
+                $t::from(1.0)
+            }
+
+            fn eye_like(&self) -> $t {
+                $t::from(1.0)
+            }
+        }
+
+        impl ArgminInv<$t> for $t {
+            fn inv(&self) -> Result<$t, Error> {
+                Ok(self.recip())
+            }
+        }
+
+        impl ArgminMinMax for $t {
+            fn min(x: &$t, y: &$t) -> $t {
+                if y < x {
+                    *y
+                } else {
+                    *x
+                }
+            }
+
+            fn max(x: &$t, y: &$t) -> $t {
+                if y > x {
+                    *y
+                } else {
+                    *x
+                }
+            }
+        }
+    };
+    ($t:ident, $op:ident, $f:ident, $op_assign:ident, $f_assign:ident) => {
+        impl $op<f64> for $t {
+            type Output = $t;
+
+            fn $f(self, other: f64) -> $t {
+                $op::$f(self, $t::from(other))
+            }
+        }
+
+        impl $op<$t> for f64 {
+            type Output = $t;
+
+            fn $f(self, other: $t) -> $t {
+                $op::$f($t::from(self), other)
+            }
+        }
+
+        impl $op_assign for $t {
+            fn $f_assign(&mut self, other: $t) {
+                *self = $op::$f(*self, other);
+            }
+        }
+
+        impl $op_assign<f64> for $t {
+            fn $f_assign(&mut self, other: f64) {
+                *self = $op::$f(*self, other);
+            }
+        }
+    };
+    ($t:ident, $trait:ident, $f:ident, $op:tt) => {
+        impl $trait<$t, $t> for $t {
+            fn $f(&self, other: &$t) -> $t {
+                *self $op *other
+            }
+        }
+
+        impl $trait<f64, $t> for $t {
+            fn $f(&self, other: &f64) -> $t {
+                *self $op *other
+            }
+        }
+
+        impl $trait<$t, $t> for f64 {
+            fn $f(&self, other: &$t) -> $t {
+                *self $op *other
+            }
+        }
+    };
+}
+
+make_dual!(Dual);
+make_dual!(HyperDual);
+
+/// Cost functions which can be evaluated with dual numbers
+///
+/// `apply_dual` has to compute the same value as `ArgminOp::apply`, but for any scalar type
+/// `T: DualNum`. See the [module documentation](index.html) for an example.
+pub trait ArgminDualOp: ArgminOp<Output = f64> {
+    /// Evaluates the cost function at the coordinates `param`
+    fn apply_dual<T: DualNum>(&self, param: &[T]) -> Result<T, Error>;
+}
+
+/// Wrapper computing the gradient and Hessian of an `ArgminDualOp` by forward-mode automatic
+/// differentiation
+///
+/// `apply` and `jacobian` are forwarded to the wrapped operator; if its `apply` is not
+/// implemented, `apply_dual` is evaluated with `f64`. The derivatives of the wrapped operator are
+/// never called: `gradient` evaluates `apply_dual` once per parameter with `Dual` numbers and
+/// `hessian` evaluates it `n (n + 1) / 2` times with `HyperDual` numbers.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default)]
+pub struct AutoDiffOp<O> {
+    /// wrapped operator
+    op: O,
+}
+
+impl<O> AutoDiffOp<O> {
+    /// Constructor
+    pub fn new(op: O) -> Self {
+        AutoDiffOp { op }
+    }
+
+    /// Wrapped operator
+    pub fn inner(&self) -> &O {
+        &self.op
+    }
+}
+
+impl<O> ArgminOp for AutoDiffOp<O>
+where
+    O: ArgminDualOp,
+    O::Param: FiniteDiffVector,
+    O::Hessian: FiniteDiffMatrix,
+{
+    type Param = O::Param;
+    type Output = f64;
+    type Hessian = O::Hessian;
+    type Jacobian = O::Jacobian;
+
+    fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        match self.op.apply(p) {
+            Err(ref e) if not_implemented(e) => self.op.apply_dual(&p.to_f64_vec()),
+            cost => cost,
+        }
+    }
+
+    fn gradient(&self, p: &Self::Param) -> Result<Self::Param, Error> {
+        let x = p.to_f64_vec();
+        let grad = (0..x.len())
+            .map(|i| {
+                let xd: Vec<Dual> = x
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &xj)| Dual::new(xj, if i == j { 1.0 } else { 0.0 }))
+                    .collect();
+                Ok(self.op.apply_dual(&xd)?.eps)
+            })
+            .collect::<Result<Vec<f64>, Error>>()?;
+        Ok(Self::Param::from_f64_vec(grad))
+    }
+
+    #[allow(clippy::needless_range_loop)]
+    fn hessian(&self, p: &Self::Param) -> Result<Self::Hessian, Error> {
+        let x = p.to_f64_vec();
+        let n = x.len();
+        let mut hessian = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in i..n {
+                let xd: Vec<HyperDual> = x
+                    .iter()
+                    .enumerate()
+                    .map(|(k, &xk)| {
+                        let e1 = if k == i { 1.0 } else { 0.0 };
+                        let e2 = if k == j { 1.0 } else { 0.0 };
+                        HyperDual::new(xk, e1, e2, 0.0)
+                    })
+                    .collect();
+                let hij = self.op.apply_dual(&xd)?.e12;
+                hessian[i][j] = hij;
+                hessian[j][i] = hij;
+            }
+        }
+        Ok(Self::Hessian::from_rows(hessian))
+    }
+
+    fn jacobian(&self, p: &Self::Param) -> Result<Self::Jacobian, Error> {
+        self.op.jacobian(p)
+    }
+
+    fn modify(&self, p: &Self::Param, t: f64) -> Result<Self::Param, Error> {
+        self.op.modify(p, t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::quasinewton::LBFGS;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(dual, Dual);
+    test_trait_impl!(hyperdual, HyperDual);
+    test_trait_impl!(autodiff_op, AutoDiffOp<MinimalNoOperator>);
+
+    /// `f(x) = exp(x_0) x_1^2 + sin(x_0 x_1)`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Func {}
+
+    impl ArgminOp for Func {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+    }
+
+    impl ArgminDualOp for Func {
+        fn apply_dual<T: DualNum>(&self, p: &[T]) -> Result<T, Error> {
+            Ok(p[0].exp() * p[1].powi(2) + (p[0] * p[1]).sin())
+        }
+    }
+
+    fn assert_close(a: f64, b: f64, tol: f64) {
+        assert!((a - b).abs() < tol, "{} vs. {}", a, b);
+    }
+
+    #[test]
+    fn test_gradient_and_hessian() {
+        let p: Vec<f64> = vec![0.3, -1.2];
+        let op = AutoDiffOp::new(Func {});
+        let (s, c) = (p[0] * p[1]).sin_cos();
+        let e = p[0].exp();
+
+        assert_close(op.apply(&p).unwrap(), e * p[1].powi(2) + s, 1e-15);
+
+        let grad = op.gradient(&p).unwrap();
+        assert_close(grad[0], e * p[1].powi(2) + p[1] * c, 1e-14);
+        assert_close(grad[1], 2.0 * e * p[1] + p[0] * c, 1e-14);
+
+        let hessian = op.hessian(&p).unwrap();
+        let h01 = 2.0 * e * p[1] + c - p[0] * p[1] * s;
+        assert_close(hessian[0][0], e * p[1].powi(2) - p[1].powi(2) * s, 1e-14);
+        assert_close(hessian[0][1], h01, 1e-14);
+        assert_close(hessian[1][0], h01, 1e-14);
+        assert_close(hessian[1][1], 2.0 * e - p[0].powi(2) * s, 1e-14);
+    }
+
+    #[test]
+    fn test_elementary_functions() {
+        type F = fn(HyperDual) -> HyperDual;
+        // function, first and second derivative at 0.7
+        let x: f64 = 0.7;
+        let cases: Vec<(F, f64, f64)> = vec![
+            (|y| y.abs(), 1.0, 0.0),
+            (|y| y.recip(), -x.powi(-2), 2.0 * x.powi(-3)),
+            (|y| y.sqrt(), 0.5 / x.sqrt(), -0.25 * x.powf(-1.5)),
+            (
+                |y| y.cbrt(),
+                x.powf(-2.0 / 3.0) / 3.0,
+                -2.0 / 9.0 * x.powf(-5.0 / 3.0),
+            ),
+            (|y| y.exp(), x.exp(), x.exp()),
+            (|y| y.ln(), 1.0 / x, -1.0 / (x * x)),
+            (|y| y.powi(3), 3.0 * x * x, 6.0 * x),
+            (|y| y.powf(2.5), 2.5 * x.powf(1.5), 3.75 * x.sqrt()),
+            (|y| y.sin(), x.cos(), -x.sin()),
+            (|y| y.cos(), -x.sin(), -x.cos()),
+            (
+                |y| y.tan(),
+                x.cos().powi(-2),
+                2.0 * x.tan() * x.cos().powi(-2),
+            ),
+            (
+                |y| y.tanh(),
+                x.cosh().powi(-2),
+                -2.0 * x.tanh() * x.cosh().powi(-2),
+            ),
+            (
+                |y| y.atan(),
+                1.0 / (1.0 + x * x),
+                -2.0 * x / (1.0 + x * x).powi(2),
+            ),
+            (|y| y / (y * y + 1.0), 0.51 / 1.49f64.powi(2), {
+                let d = 1.0 + x * x;
+                (2.0 * x.powi(3) - 6.0 * x) / d.powi(3)
+            }),
+        ];
+        for (f, d1, d2) in cases {
+            let y = f(HyperDual::new(x, 1.0, 1.0, 0.0));
+            assert_close(y.e1, d1, 1e-12);
+            assert_close(y.e2, d1, 1e-12);
+            assert_close(y.e12, d2, 1e-12);
+            let z = f(HyperDual::from(x));
+            assert_close(z.re, y.re, 1e-15);
+        }
+        // zero
+        let y = HyperDual::new(0.0, 1.0, 1.0, 0.0).powi(1);
+        assert_close(y.e1, 1.0, 1e-15);
+        assert_close(y.e12, 0.0, 1e-15);
+    }
+
+    #[test]
+    fn test_math_traits() {
+        let a = Dual::new(2.0, 1.0);
+        let b = Dual::new(-1.5, 0.5);
+        let c = a.scaled_add(&3.0, &b);
+        assert_close(c.re, -2.5, 1e-15);
+        assert_close(c.eps, 2.5, 1e-15);
+        let d = ArgminDot::dot(&a, &b);
+        assert_close(d.eps, -0.5, 1e-15);
+        let n = ArgminNorm::norm(&b);
+        assert_close(n.re, 1.5, 1e-15);
+        assert_close(n.eps, -0.5, 1e-15);
+        assert_close(<Dual as ArgminMinMax>::min(&a, &b).re, -1.5, 1e-15);
+        assert_close(a.inv().unwrap().eps, -0.25, 1e-15);
+        assert!(a > b);
+        assert!(Dual::new(1.0, 2.0) == Dual::from(1.0));
+    }
+
+    /// Rosenbrock function
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Rosenbrock {}
+
+    impl ArgminOp for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+    }
+
+    impl ArgminDualOp for Rosenbrock {
+        fn apply_dual<T: DualNum>(&self, p: &[T]) -> Result<T, Error> {
+            Ok((-p[0] + 1.0).powi(2) + (p[1] - p[0] * p[0]).powi(2) * 100.0)
+        }
+    }
+
+    #[test]
+    fn test_solver() {
+        let res = Executor::new(
+            AutoDiffOp::new(Rosenbrock {}),
+            LBFGS::new(MoreThuenteLineSearch::new(), 5),
+            vec![-1.2, 1.0],
+        )
+        .max_iters(100)
+        .run()
+        .unwrap();
+        let param = res.state.get_best_param();
+        assert!((param[0] - 1.0).abs() < 1e-6);
+        assert!((param[1] - 1.0).abs() < 1e-6);
+    }
+}
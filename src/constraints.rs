@@ -0,0 +1,90 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Constraints
+//!
+//! Extension of `ArgminOp` for problems of the form
+//!
+//! ```text
+//! min f(x)  subject to  h_i(x) = 0,  i = 1, ..., m
+//!                       g_j(x) <= 0, j = 1, ..., p
+//! ```
+//!
+//! The cost function `f` and its derivatives are provided via `ArgminOp`, the constraint
+//! functions via `ArgminConstraints`. All methods come with default implementations, therefore
+//! only the kinds of constraints which are actually present need to be implemented.
+
+use crate::prelude::*;
+
+/// Equality and inequality constraints of an optimization problem.
+///
+/// Constraint values are returned as `Vec<f64>` with one entry per constraint. The Jacobians are
+/// returned row-wise as `Vec<Self::Param>`, where the i-th entry is the gradient of the i-th
+/// constraint. Inequality constraints are of the form `g(x) <= 0`.
+pub trait ArgminConstraints: ArgminOp {
+    /// Values of the equality constraints `h(x)` (default: no equality constraints)
+    fn eq_constraints(&self, _param: &Self::Param) -> Result<Vec<f64>, Error> {
+        Ok(vec![])
+    }
+
+    /// Jacobian of the equality constraints (default: no equality constraints)
+    fn eq_jacobian(&self, _param: &Self::Param) -> Result<Vec<Self::Param>, Error> {
+        Ok(vec![])
+    }
+
+    /// Hessians of the equality constraints, one per constraint. Only needed by second order
+    /// methods.
+    fn eq_hessians(&self, _param: &Self::Param) -> Result<Vec<Self::Hessian>, Error> {
+        Err(ArgminError::NotImplemented {
+            text: "Method `eq_hessians` of ArgminConstraints trait not implemented!".to_string(),
+        }
+        .into())
+    }
+
+    /// Values of the inequality constraints `g(x)` (default: no inequality constraints)
+    fn ineq_constraints(&self, _param: &Self::Param) -> Result<Vec<f64>, Error> {
+        Ok(vec![])
+    }
+
+    /// Jacobian of the inequality constraints (default: no inequality constraints)
+    fn ineq_jacobian(&self, _param: &Self::Param) -> Result<Vec<Self::Param>, Error> {
+        Ok(vec![])
+    }
+
+    /// Hessians of the inequality constraints, one per constraint. Only needed by second order
+    /// methods.
+    fn ineq_hessians(&self, _param: &Self::Param) -> Result<Vec<Self::Hessian>, Error> {
+        Err(ArgminError::NotImplemented {
+            text: "Method `ineq_hessians` of ArgminConstraints trait not implemented!".to_string(),
+        }
+        .into())
+    }
+}
+
+/// Checks that a Jacobian or a list of Hessians has one entry per constraint.
+pub(crate) fn check_rows<P>(name: &str, values: &[f64], rows: &[P]) -> Result<(), Error> {
+    if values.len() != rows.len() {
+        return Err(ArgminError::InvalidParameter {
+            text: format!(
+                "{}: expected one entry per constraint ({}), got {}.",
+                name,
+                values.len(),
+                rows.len()
+            ),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Largest violation of the constraints `h(x) = 0` and `g(x) <= 0`
+pub(crate) fn infeasibility(h: &[f64], g: &[f64]) -> f64 {
+    h.iter()
+        .map(|hi| hi.abs())
+        .chain(g.iter().map(|gj| gj.max(0.0)))
+        .fold(0.0, f64::max)
+}
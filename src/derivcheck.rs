@@ -0,0 +1,626 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Derivative checks
+//!
+//! Wrong analytic derivatives usually do not cause errors, but let solvers stall or converge to
+//! wrong points. `DerivativeChecker` compares the `gradient`, `jacobian` and `hessian` of an
+//! operator (as far as they are implemented) with central finite differences at given points and
+//! returns a `DerivativeCheckReport` with the relative error of each component:
+//!
+//! ```rust
+//! # extern crate argmin;
+//! # use argmin::prelude::*;
+//! # use argmin::derivcheck::DerivativeChecker;
+//! # #[cfg(feature = "serde1")]
+//! # use serde::{Deserialize, Serialize};
+//! #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+//! #[derive(Clone, Default)]
+//! struct Quadratic {}
+//!
+//! impl ArgminOp for Quadratic {
+//!     type Param = Vec<f64>;
+//!     type Output = f64;
+//!     type Hessian = ();
+//!     type Jacobian = ();
+//!
+//!     fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+//!         Ok(p[0].powi(2) + 3.0 * p[1].powi(2))
+//!     }
+//!
+//!     fn gradient(&self, p: &Self::Param) -> Result<Self::Param, Error> {
+//!         // wrong: should be 6 * p[1]
+//!         Ok(vec![2.0 * p[0], 3.0 * p[1]])
+//!     }
+//! }
+//!
+//! # fn run() -> Result<(), Error> {
+//! let report = DerivativeChecker::new().check(&Quadratic {}, &[vec![1.0, 2.0]])?;
+//! assert!(!report.is_ok());
+//! // gradient[1] at point 0: analytic 6, finite differences 12, relative error 5.0e-1 (mismatch)
+//! println!("{}", report);
+//! # assert_eq!(report.mismatches().len(), 1);
+//! # Ok(())
+//! # }
+//! # fn main() {
+//! #     if let Err(ref e) = run() {
+//! #         println!("{} {}", e.as_fail(), e.backtrace());
+//! #         std::process::exit(1);
+//! #     }
+//! # }
+//! ```
+//!
+//! The relative error of a component with analytic value `a` and finite difference approximation
+//! `n` is `|a - n| / max(|a|, |n|, 1)`, i.e. components of magnitude smaller than one are
+//! compared via their absolute error. Components whose error exceeds the tolerance (`tol(...)`)
+//! are flagged as mismatches. The Hessian is compared with differences of the gradient if the
+//! operator implements the gradient and with second order differences of the cost function
+//! otherwise.
+//!
+//! To check the derivatives at the initial parameter vector before the first iteration of a
+//! solver, wrap the solver in `CheckDerivatives`. By default, the `Executor` then aborts with an
+//! error listing the mismatches.
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::numdiff::{
+    not_implemented, CentralDiff, FiniteDiffMatrix, FiniteDiffOp, FiniteDiffVector,
+};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Checked derivative
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DerivativeKind {
+    /// `ArgminOp::gradient`
+    Gradient,
+    /// `ArgminOp::jacobian`
+    Jacobian,
+    /// `ArgminOp::hessian`
+    Hessian,
+}
+
+/// Comparison of a single component of a derivative with its finite difference approximation
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DerivativeCheckEntry {
+    /// Checked derivative
+    pub kind: DerivativeKind,
+    /// Index of the point in the list of checked points
+    pub point: usize,
+    /// Row and column of the component (the gradient only has rows)
+    pub index: (usize, usize),
+    /// Value of the analytic derivative
+    pub analytic: f64,
+    /// Finite difference approximation
+    pub numeric: f64,
+    /// Relative error
+    pub rel_error: f64,
+    /// Whether the relative error exceeds the tolerance
+    pub mismatch: bool,
+}
+
+impl fmt::Display for DerivativeCheckEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            DerivativeKind::Gradient => write!(f, "gradient[{}]", self.index.0)?,
+            DerivativeKind::Jacobian => write!(f, "jacobian[{}, {}]", self.index.0, self.index.1)?,
+            DerivativeKind::Hessian => write!(f, "hessian[{}, {}]", self.index.0, self.index.1)?,
+        }
+        write!(
+            f,
+            " at point {}: analytic {}, finite differences {}, relative error {:.1e}",
+            self.point, self.analytic, self.numeric, self.rel_error
+        )?;
+        if self.mismatch {
+            write!(f, " (mismatch)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of a derivative check
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DerivativeCheckReport {
+    /// all checked components
+    entries: Vec<DerivativeCheckEntry>,
+}
+
+impl DerivativeCheckReport {
+    /// All checked components
+    pub fn entries(&self) -> &[DerivativeCheckEntry] {
+        &self.entries
+    }
+
+    /// Components whose relative error exceeds the tolerance
+    pub fn mismatches(&self) -> Vec<&DerivativeCheckEntry> {
+        self.entries.iter().filter(|e| e.mismatch).collect()
+    }
+
+    /// Whether all checked components agree with their finite difference approximations
+    pub fn is_ok(&self) -> bool {
+        self.entries.iter().all(|e| !e.mismatch)
+    }
+
+    /// Largest relative error of all checked components
+    pub fn max_rel_error(&self) -> f64 {
+        self.entries
+            .iter()
+            .map(|e| e.rel_error)
+            .fold(0.0, |acc, e| if e > acc || e.is_nan() { e } else { acc })
+    }
+}
+
+impl fmt::Display for DerivativeCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in self.entries.iter() {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the derivatives of an operator with finite differences
+///
+/// See the [module documentation](index.html) for details.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct DerivativeChecker {
+    /// tolerance of the relative error
+    tol: f64,
+    /// relative step size of first order differences
+    step: Option<f64>,
+    /// relative step size of second order differences
+    hessian_step: Option<f64>,
+}
+
+impl Default for DerivativeChecker {
+    fn default() -> Self {
+        DerivativeChecker::new()
+    }
+}
+
+impl DerivativeChecker {
+    /// Constructor
+    pub fn new() -> Self {
+        DerivativeChecker {
+            tol: 1e-5,
+            step: None,
+            hessian_step: None,
+        }
+    }
+
+    /// Set tolerance of the relative error (default: 1e-5)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "DerivativeChecker: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Set relative step size of first order differences (see `FiniteDiffOp::step`)
+    pub fn step(mut self, step: f64) -> Result<Self, Error> {
+        if step <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "DerivativeChecker: step must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.step = Some(step);
+        Ok(self)
+    }
+
+    /// Set relative step size of second order differences (see `FiniteDiffOp::hessian_step`)
+    pub fn hessian_step(mut self, step: f64) -> Result<Self, Error> {
+        if step <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "DerivativeChecker: hessian_step must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.hessian_step = Some(step);
+        Ok(self)
+    }
+
+    /// Checks the derivatives of `op` at all `params`
+    pub fn check<O>(&self, op: &O, params: &[O::Param]) -> Result<DerivativeCheckReport, Error>
+    where
+        O: ArgminOp,
+        O::Param: FiniteDiffVector,
+        O::Output: FiniteDiffVector,
+        O::Hessian: FiniteDiffMatrix,
+        O::Jacobian: FiniteDiffMatrix,
+    {
+        self.check_counted(&mut OpWrapper::new(op), params)
+    }
+
+    /// Checks the derivatives of `op` at all `params`. All evaluations are counted in `op`.
+    fn check_counted<O>(
+        &self,
+        op: &mut OpWrapper<O>,
+        params: &[O::Param],
+    ) -> Result<DerivativeCheckReport, Error>
+    where
+        O: ArgminOp,
+        O::Param: FiniteDiffVector,
+        O::Output: FiniteDiffVector,
+        O::Hessian: FiniteDiffMatrix,
+        O::Jacobian: FiniteDiffMatrix,
+    {
+        let mut fd = FiniteDiffOp::new(op.op.clone().unwrap()).method(CentralDiff);
+        if let Some(step) = self.step {
+            fd = fd.step(step)?;
+        }
+        if let Some(step) = self.hessian_step {
+            fd = fd.hessian_step(step)?;
+        }
+        let mut report = DerivativeCheckReport::default();
+        for (point, param) in params.iter().enumerate() {
+            let x = param.to_f64_vec();
+
+            let has_gradient = match op.gradient(param) {
+                Err(ref e) if not_implemented(e) => false,
+                grad => {
+                    let analytic = grad?.to_f64_vec();
+                    let numeric = fd.numeric_gradient(&x)?;
+                    self.compare(
+                        &mut report,
+                        DerivativeKind::Gradient,
+                        point,
+                        &[analytic],
+                        &[numeric],
+                    )?;
+                    true
+                }
+            };
+
+            match op.jacobian(param) {
+                Err(ref e) if not_implemented(e) => {}
+                jacobian => {
+                    let analytic = jacobian?.to_rows();
+                    let numeric = fd.numeric_jacobian(&x)?;
+                    self.compare(
+                        &mut report,
+                        DerivativeKind::Jacobian,
+                        point,
+                        &analytic,
+                        &numeric,
+                    )?;
+                }
+            }
+
+            match op.hessian(param) {
+                Err(ref e) if not_implemented(e) => {}
+                hessian => {
+                    let analytic = hessian?.to_rows();
+                    fd = fd.analytic_gradient(has_gradient);
+                    let numeric = fd.numeric_hessian(&x)?;
+                    self.compare(
+                        &mut report,
+                        DerivativeKind::Hessian,
+                        point,
+                        &analytic,
+                        &numeric,
+                    )?;
+                }
+            }
+        }
+        let (cost_count, grad_count) = fd.take_counts();
+        op.cost_func_count += cost_count;
+        op.grad_func_count += grad_count;
+        Ok(report)
+    }
+
+    /// Adds the comparison of the rows `analytic` and `numeric` to `report`
+    fn compare(
+        &self,
+        report: &mut DerivativeCheckReport,
+        kind: DerivativeKind,
+        point: usize,
+        analytic: &[Vec<f64>],
+        numeric: &[Vec<f64>],
+    ) -> Result<(), Error> {
+        let same_shape = analytic.len() == numeric.len()
+            && analytic
+                .iter()
+                .zip(numeric.iter())
+                .all(|(a, n)| a.len() == n.len());
+        if !same_shape {
+            return Err(ArgminError::ConditionViolated {
+                text: format!(
+                    "DerivativeChecker: {:?} at point {} has wrong dimensions.",
+                    kind, point
+                ),
+            }
+            .into());
+        }
+        for (i, (a_row, n_row)) in analytic.iter().zip(numeric.iter()).enumerate() {
+            for (j, (&a, &n)) in a_row.iter().zip(n_row.iter()).enumerate() {
+                let rel_error = (a - n).abs() / a.abs().max(n.abs()).max(1.0);
+                report.entries.push(DerivativeCheckEntry {
+                    kind,
+                    point,
+                    index: if kind == DerivativeKind::Gradient {
+                        (j, 0)
+                    } else {
+                        (i, j)
+                    },
+                    analytic: a,
+                    numeric: n,
+                    rel_error,
+                    mismatch: rel_error.is_nan() || rel_error > self.tol,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks the derivatives of the operator at the initial parameter vector before running the
+/// wrapped solver
+///
+/// The check is performed in `init` and therefore by the `Executor` before the first iteration.
+/// By default, mismatches cause an error. Otherwise, the largest relative error and the number of
+/// mismatches are reported as `derivative_check_max_rel_error` and
+/// `derivative_check_mismatches` to the observers. The function evaluations of the check are
+/// included in the evaluation counts.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct CheckDerivatives<S> {
+    /// wrapped solver
+    solver: S,
+    /// derivative checker
+    checker: DerivativeChecker,
+    /// return an error on mismatches
+    abort_on_mismatch: bool,
+}
+
+impl<S> CheckDerivatives<S> {
+    /// Constructor
+    pub fn new(solver: S) -> Self {
+        CheckDerivatives {
+            solver,
+            checker: DerivativeChecker::new(),
+            abort_on_mismatch: true,
+        }
+    }
+
+    /// Set derivative checker (tolerance and step sizes)
+    pub fn checker(mut self, checker: DerivativeChecker) -> Self {
+        self.checker = checker;
+        self
+    }
+
+    /// Return an error if the check finds mismatches (default: true)
+    pub fn abort_on_mismatch(mut self, abort: bool) -> Self {
+        self.abort_on_mismatch = abort;
+        self
+    }
+}
+
+impl<O, S> Solver<O> for CheckDerivatives<S>
+where
+    O: ArgminOp,
+    O::Param: FiniteDiffVector,
+    O::Output: FiniteDiffVector,
+    O::Hessian: FiniteDiffMatrix,
+    O::Jacobian: FiniteDiffMatrix,
+    S: Solver<O>,
+{
+    const NAME: &'static str = S::NAME;
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let report = self.checker.check_counted(op, &[state.get_param()])?;
+        let mismatches = report.mismatches();
+        if self.abort_on_mismatch && !mismatches.is_empty() {
+            let lines: Vec<String> = mismatches.iter().map(|e| e.to_string()).collect();
+            return Err(ArgminError::ConditionViolated {
+                text: format!("Derivative check failed:\n{}", lines.join("\n")),
+            }
+            .into());
+        }
+        let mut kv = make_kv!(
+            "derivative_check_max_rel_error" => report.max_rel_error();
+            "derivative_check_mismatches" => mismatches.len();
+        );
+        Ok(Some(match self.solver.init(op, state)? {
+            Some(data) => {
+                let kv = data.get_kv().merge(&mut kv);
+                data.kv(kv)
+            }
+            None => ArgminIterData::new().kv(kv),
+        }))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        self.solver.next_iter(op, state)
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        self.solver.terminate(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::quasinewton::LBFGS;
+    use crate::test_trait_impl;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    test_trait_impl!(derivative_checker, DerivativeChecker);
+
+    test_trait_impl!(
+        check_derivatives,
+        CheckDerivatives<LBFGS<MoreThuenteLineSearch<Vec<f64>>, Vec<f64>>>
+    );
+
+    /// `f(x) = x_0^4 + x_0 x_1 + exp(x_1)`, optionally with an error in the gradient. Counts the
+    /// evaluations of the cost function and the gradient.
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Func {
+        grad_error: f64,
+        evaluations: Arc<(AtomicU64, AtomicU64)>,
+    }
+
+    impl Func {
+        fn new(grad_error: f64) -> Self {
+            Func {
+                grad_error,
+                evaluations: Arc::new((AtomicU64::new(0), AtomicU64::new(0))),
+            }
+        }
+    }
+
+    impl ArgminOp for Func {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            self.evaluations.0.fetch_add(1, Ordering::Relaxed);
+            Ok(p[0].powi(4) + p[0] * p[1] + p[1].exp())
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            self.evaluations.1.fetch_add(1, Ordering::Relaxed);
+            Ok(vec![
+                4.0 * p[0].powi(3) + p[1],
+                p[0] + p[1].exp() + self.grad_error,
+            ])
+        }
+
+        fn hessian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![12.0 * p[0].powi(2), 1.0], vec![1.0, p[1].exp()]])
+        }
+    }
+
+    /// Residuals `r(x) = (x_0 x_1, x_1^2)` with a wrong Jacobian
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Residuals {}
+
+    impl ArgminOp for Residuals {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Hessian = ();
+        type Jacobian = Vec<Vec<f64>>;
+
+        fn apply(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![p[0] * p[1], p[1].powi(2)])
+        }
+
+        fn jacobian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![p[1], p[0]], vec![0.0, p[1]]])
+        }
+    }
+
+    fn checker() -> DerivativeChecker {
+        DerivativeChecker::new().tol(1e-4).unwrap()
+    }
+
+    #[test]
+    fn test_correct_derivatives() {
+        let points = vec![vec![0.5, -1.0], vec![-2.0, 0.3]];
+        let report = checker().check(&Func::new(0.0), &points).unwrap();
+        assert!(report.is_ok());
+        // 2 gradient and 4 Hessian components per point
+        assert_eq!(report.entries().len(), 12);
+        assert!(report.max_rel_error() < 1e-4);
+    }
+
+    #[test]
+    fn test_wrong_gradient() {
+        let report = checker()
+            .check(&Func::new(1e-3), &[vec![0.5, -1.0]])
+            .unwrap();
+        let mismatches = report.mismatches();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].kind, DerivativeKind::Gradient);
+        assert_eq!(mismatches[0].index, (1, 0));
+        assert!(report.to_string().contains("gradient[1] at point 0"));
+    }
+
+    #[test]
+    fn test_wrong_jacobian() {
+        let report = checker().check(&Residuals {}, &[vec![1.5, 2.0]]).unwrap();
+        let mismatches = report.mismatches();
+        assert_eq!(report.entries().len(), 4);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].kind, DerivativeKind::Jacobian);
+        assert_eq!(mismatches[0].index, (1, 1));
+        assert!((mismatches[0].numeric - 4.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_executor() {
+        let solver = || CheckDerivatives::new(LBFGS::new(MoreThuenteLineSearch::new(), 5));
+        let res = Executor::new(
+            Func::new(1e-3),
+            solver().checker(checker()),
+            vec![0.5, -1.0],
+        )
+        .max_iters(10)
+        .run();
+        assert!(res.is_err());
+
+        // all evaluations of the check and of `LBFGS::init` are counted
+        let func = Func::new(0.0);
+        let res = Executor::new(func.clone(), solver().checker(checker()), vec![0.5, -1.0])
+            .max_iters(0)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_cost_func_count(),
+            func.evaluations.0.load(Ordering::Relaxed)
+        );
+        assert_eq!(
+            res.state.get_grad_func_count(),
+            func.evaluations.1.load(Ordering::Relaxed)
+        );
+        assert!(res.state.get_grad_func_count() > 2);
+
+        let res = Executor::new(
+            Func::new(1e-3),
+            solver().checker(checker()).abort_on_mismatch(false),
+            vec![0.5, -1.0],
+        )
+        .max_iters(10)
+        .run();
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_parameters() {
+        assert!(DerivativeChecker::new().tol(0.0).is_err());
+        assert!(DerivativeChecker::new().step(-1.0).is_err());
+        assert!(DerivativeChecker::new().hessian_step(0.0).is_err());
+    }
+}
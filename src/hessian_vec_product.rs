@@ -0,0 +1,258 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Hessian-vector products
+//!
+//! Solvers which only need products of the Hessian with vectors (`NewtonCG`, `Steihaug`, `GLTR`
+//! and `TrustRegion`) compute them from the dense Hessian (`ArgminOp::hessian`) by default. For
+//! problems with many parameters, forming the Hessian is infeasible. These solvers can therefore
+//! be switched to matrix-free mode via `matrix_free()`, in which case `ArgminOp::hessian` is never
+//! called and the products are computed by the `ArgminHessianVecProduct` extension of `ArgminOp`
+//! instead. Only operators used in matrix-free mode need to implement it.
+//!
+//! Operators which can compute the product exactly (for instance via automatic differentiation or
+//! from the structure of the problem) should override `hessian_vec_product`. Otherwise, an empty
+//! implementation suffices: the default returns `ArgminError::NotImplemented`, in which case the
+//! solvers approximate the product with central differences of the gradient. Each approximation
+//! costs two gradient evaluations, which are counted as such:
+//!
+//! ```text
+//! H(x) v ≈ (∇f(x + h v) - ∇f(x - h v)) / (2 h),  h = ε^{1/3} (1 + ||x||) / ||v||
+//! ```
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::numdiff::not_implemented;
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Products of the Hessian of the cost function with vectors
+pub trait ArgminHessianVecProduct: ArgminOp {
+    /// Computes `H(param) * v` (default: `ArgminError::NotImplemented`, solvers fall back to
+    /// finite differences of the gradient)
+    fn hessian_vec_product(
+        &self,
+        _param: &Self::Param,
+        _v: &Self::Param,
+    ) -> Result<Self::Param, Error> {
+        Err(ArgminError::NotImplemented {
+            text: "Method `hessian_vec_product` of ArgminHessianVecProduct trait not implemented!"
+                .to_string(),
+        }
+        .into())
+    }
+}
+
+impl<O: ArgminHessianVecProduct> ArgminHessianVecProduct for OpWrapper<O> {
+    fn hessian_vec_product(
+        &self,
+        param: &Self::Param,
+        v: &Self::Param,
+    ) -> Result<Self::Param, Error> {
+        self.op.as_ref().unwrap().hessian_vec_product(param, v)
+    }
+}
+
+/// Hessian-vector products computed from the dense Hessian (default mode of the solvers)
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct DenseHessian;
+
+/// Matrix-free Hessian-vector products via `ArgminHessianVecProduct`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct MatrixFree;
+
+/// Source of the Hessian-vector products of a solver (`DenseHessian` or `MatrixFree`)
+pub trait HessianVecProducts<O: ArgminOp> {
+    /// Returns the Hessian at `param` which is used for the products: `hessian` (for instance
+    /// taken from the state) if available, otherwise it is evaluated. Always `None` in
+    /// matrix-free mode.
+    fn hessian(
+        op: &mut OpWrapper<O>,
+        param: &O::Param,
+        hessian: Option<O::Hessian>,
+    ) -> Result<Option<O::Hessian>, Error>;
+
+    /// Computes `H(param) * v`, where `hessian` was obtained from `hessian`
+    fn product(
+        op: &mut OpWrapper<O>,
+        param: &O::Param,
+        hessian: Option<&O::Hessian>,
+        v: &O::Param,
+    ) -> Result<O::Param, Error>;
+}
+
+impl<O> HessianVecProducts<O> for DenseHessian
+where
+    O: ArgminOp,
+    O::Hessian: ArgminDot<O::Param, O::Param>,
+{
+    fn hessian(
+        op: &mut OpWrapper<O>,
+        param: &O::Param,
+        hessian: Option<O::Hessian>,
+    ) -> Result<Option<O::Hessian>, Error> {
+        match hessian {
+            Some(hessian) => Ok(Some(hessian)),
+            None => Ok(Some(op.hessian(param)?)),
+        }
+    }
+
+    fn product(
+        op: &mut OpWrapper<O>,
+        param: &O::Param,
+        hessian: Option<&O::Hessian>,
+        v: &O::Param,
+    ) -> Result<O::Param, Error> {
+        match hessian {
+            Some(hessian) => Ok(hessian.dot(v)),
+            None => Ok(op.hessian(param)?.dot(v)),
+        }
+    }
+}
+
+impl<O> HessianVecProducts<O> for MatrixFree
+where
+    O: ArgminHessianVecProduct,
+    O::Param: ArgminScaledAdd<O::Param, f64, O::Param>
+        + ArgminSub<O::Param, O::Param>
+        + ArgminMul<f64, O::Param>
+        + ArgminNorm<f64>,
+{
+    fn hessian(
+        _op: &mut OpWrapper<O>,
+        _param: &O::Param,
+        _hessian: Option<O::Hessian>,
+    ) -> Result<Option<O::Hessian>, Error> {
+        Ok(None)
+    }
+
+    fn product(
+        op: &mut OpWrapper<O>,
+        param: &O::Param,
+        _hessian: Option<&O::Hessian>,
+        v: &O::Param,
+    ) -> Result<O::Param, Error> {
+        counted_hessian_vec_product(op, param, v)
+    }
+}
+
+/// Hessian-vector product via an `OpWrapper`
+///
+/// If the operator does not implement `hessian_vec_product`, the product is approximated by
+/// central differences of the gradient. The two gradient evaluations are counted by the
+/// `OpWrapper`.
+pub(crate) fn counted_hessian_vec_product<O>(
+    op: &mut OpWrapper<O>,
+    param: &O::Param,
+    v: &O::Param,
+) -> Result<O::Param, Error>
+where
+    O: ArgminHessianVecProduct,
+    O::Param: ArgminScaledAdd<O::Param, f64, O::Param>
+        + ArgminSub<O::Param, O::Param>
+        + ArgminMul<f64, O::Param>
+        + ArgminNorm<f64>,
+{
+    match op.op.as_ref().unwrap().hessian_vec_product(param, v) {
+        Err(ref e) if not_implemented(e) => {}
+        hv => return hv,
+    }
+    let v_norm = v.norm();
+    if v_norm == 0.0 {
+        return Ok(v.clone());
+    }
+    let h = std::f64::EPSILON.cbrt() * (1.0 + param.norm()) / v_norm;
+    let grad_fwd = op.gradient(&param.scaled_add(&h, v))?;
+    let grad_bwd = op.gradient(&param.scaled_add(&(-h), v))?;
+    Ok(grad_fwd.sub(&grad_bwd).mul(&(0.5 / h)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serde1")]
+    use serde::{Deserialize, Serialize};
+
+    /// `f(x) = x_0^2 x_1 + x_1^3`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Cubic {}
+
+    impl ArgminOp for Cubic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![2.0 * p[0] * p[1], p[0].powi(2) + 3.0 * p[1].powi(2)])
+        }
+    }
+
+    impl ArgminHessianVecProduct for Cubic {}
+
+    #[test]
+    fn test_finite_differences() {
+        let mut op = OpWrapper::new(&Cubic {});
+        let x = vec![1.5, -2.0];
+        let v = vec![0.3, 0.7];
+        // H = [[2 x_1, 2 x_0], [2 x_0, 6 x_1]]
+        let expected = [
+            2.0 * x[1] * v[0] + 2.0 * x[0] * v[1],
+            2.0 * x[0] * v[0] + 6.0 * x[1] * v[1],
+        ];
+        let hv = counted_hessian_vec_product(&mut op, &x, &v).unwrap();
+        for (a, b) in hv.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-8);
+        }
+        assert_eq!(op.grad_func_count, 2);
+        assert_eq!(op.hessian_func_count, 0);
+        assert_eq!(
+            counted_hessian_vec_product(&mut op, &x, &vec![0.0, 0.0]).unwrap(),
+            vec![0.0, 0.0]
+        );
+        assert_eq!(op.grad_func_count, 2);
+        assert!(Cubic {}.hessian_vec_product(&x, &v).is_err());
+    }
+
+    /// `Cubic` with exact Hessian-vector products
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct ExactCubic {}
+
+    impl ArgminOp for ExactCubic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+    }
+
+    impl ArgminHessianVecProduct for ExactCubic {
+        fn hessian_vec_product(&self, p: &Vec<f64>, v: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![
+                2.0 * p[1] * v[0] + 2.0 * p[0] * v[1],
+                2.0 * p[0] * v[0] + 6.0 * p[1] * v[1],
+            ])
+        }
+    }
+
+    #[test]
+    fn test_exact() {
+        let mut op = OpWrapper::new(&ExactCubic {});
+        let x = vec![1.5, -2.0];
+        let hv = counted_hessian_vec_product(&mut op, &x, &vec![1.0, 0.0]).unwrap();
+        assert_eq!(hv, vec![-4.0, 3.0]);
+        assert_eq!(op.grad_func_count, 0);
+        assert_eq!(op.hessian_vec_product(&x, &vec![1.0, 0.0]).unwrap(), hv);
+    }
+}
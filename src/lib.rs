@@ -51,25 +51,63 @@
 //! - [Trust region method](solver/trustregion/trustregion_method/struct.TrustRegion.html)
 //!   - [Cauchy point method](solver/trustregion/cauchypoint/struct.CauchyPoint.html)
 //!   - [Dogleg method](solver/trustregion/dogleg/struct.Dogleg.html)
+//!   - [Generalized Lanczos trust region (GLTR) method](solver/trustregion/gltr/struct.GLTR.html)
+//!   - [Moré-Sorensen method](solver/trustregion/moresorensen/struct.MoreSorensen.html)
 //!   - [Steihaug method](solver/trustregion/steihaug/struct.Steihaug.html)
 //! - [Steepest descent](solver/gradientdescent/steepestdescent/struct.SteepestDescent.html)
 //! - [Conjugate gradient method](solver/conjugategradient/cg/struct.ConjugateGradient.html)
 //! - [Nonlinear conjugate gradient method](solver/conjugategradient/nonlinear_cg/struct.NonlinearConjugateGradient.html)
+//! - [Krylov subspace methods for linear systems](solver/krylov/index.html)
+//!   - [GMRES(m)](solver/krylov/gmres/struct.GMRES.html)
+//!   - [BiCGSTAB](solver/krylov/bicgstab/struct.BiCGSTAB.html)
+//!   - [MINRES](solver/krylov/minres/struct.MINRES.html)
 //! - [Newton methods](solver/newton/index.html)
 //!   - [Newton's method](solver/newton/newton_method/struct.Newton.html)
 //!   - [Newton-CG](solver/newton/newton_cg/struct.NewtonCG.html)
 //! - [Quasi-Newton methods](solver/quasinewton/index.html)
 //!   - [BFGS](solver/quasinewton/bfgs/struct.BFGS.html)
 //!   - [L-BFGS](solver/quasinewton/lbfgs/struct.LBFGS.html)
+//!   - [L-BFGS-B](solver/quasinewton/lbfgsb/struct.LBFGSB.html)
+//!   - [OWL-QN](solver/quasinewton/owlqn/struct.OWLQN.html)
 //!   - [DFP](solver/quasinewton/dfp/struct.DFP.html)
 //!   - [SR1](solver/quasinewton/sr1/struct.SR1.html)
 //!   - [SR1-TrustRegion](solver/quasinewton/sr1_trustregion/struct.SR1TrustRegion.html)
+//!   - [Generic quasi-Newton method with BFGS, DFP, SR1, Broyden class and damped BFGS updates](solver/quasinewton/quasinewton_method/struct.QuasiNewton.html)
 //! - [Gauss-Newton method](solver/gaussnewton/gaussnewton/struct.GaussNewton.html)
 //! - [Gauss-Newton method with linesearch](solver/gaussnewton/gaussnewton_linesearch/struct.GaussNewtonLS.html)
+//! - [Levenberg-Marquardt method](solver/gaussnewton/levenberg_marquardt/struct.LevenbergMarquardt.html)
+//! - [Nonlinear systems of equations](solver/nonlinearsystem/index.html)
+//!   - [Newton-Raphson method](solver/nonlinearsystem/newton_raphson/struct.NewtonRaphson.html)
+//!   - [Broyden's method](solver/nonlinearsystem/broyden/struct.Broyden.html)
+//! - [Augmented Lagrangian method](solver/augmentedlagrangian/struct.AugmentedLagrangian.html)
+//! - [Sequential quadratic programming (SQP)](solver/sqp/struct.SQP.html)
+//! - [Linear programming](solver/linprog/index.html)
+//!   - [Revised simplex method](solver/linprog/simplex/struct.RevisedSimplex.html)
+//! - [Quadratic programming](solver/qp/index.html)
+//!   - [Active-set method](solver/qp/activeset/struct.ActiveSetQP.html)
+//!   - [Mehrotra interior-point method](solver/qp/interiorpoint/struct.InteriorPointQP.html) (requires `ndarrayl`)
 //! - [Landweber iteration](solver/landweber/struct.Landweber.html)
+//! - [Proximal gradient methods](solver/proximalgradient/index.html)
+//!   - [ISTA](solver/proximalgradient/ista/struct.ISTA.html)
+//!   - [FISTA](solver/proximalgradient/fista/struct.FISTA.html)
 //! - [Nelder-Mead method](solver/neldermead/struct.NelderMead.html)
+//! - [Brent's method for minimization](solver/brent/brentopt/struct.BrentOpt.html)
+//! - [Golden-section search](solver/goldensectionsearch/struct.GoldenSectionSearch.html)
+//! - [Root finding methods](solver/rootfinding/index.html)
+//!   - [Brent's method](solver/brent/struct.Brent.html)
+//!   - [Bisection](solver/rootfinding/bisection/struct.Bisection.html)
+//!   - [Illinois method](solver/rootfinding/illinois/struct.Illinois.html)
+//!   - [Ridders' method](solver/rootfinding/ridders/struct.Ridders.html)
+//!   - [Safeguarded Newton method](solver/rootfinding/rtsafe/struct.NewtonSafe.html)
 //! - [Simulated Annealing](solver/simulatedannealing/struct.SimulatedAnnealing.html)
 //! - [Particle Swarm Optimization](solver/particleswarm/struct.ParticleSwarm.html)
+//! - [Stochastic first order methods](solver/stochastic/index.html)
+//!   - [SGD with (Nesterov) momentum](solver/stochastic/sgd/struct.SGD.html)
+//!   - [AdaGrad](solver/stochastic/adagrad/struct.AdaGrad.html)
+//!   - [RMSProp](solver/stochastic/rmsprop/struct.RMSProp.html)
+//!   - [Adam](solver/stochastic/adam/struct.Adam.html)
+//! - [CMA-ES](solver/cmaes/struct.CMAES.html)
+//! - [Differential Evolution](solver/differentialevolution/struct.DifferentialEvolution.html)
 //!
 //! # Usage
 //!
@@ -93,8 +131,9 @@
 //! `wasm32-unknown-unkown` seems to be possible.
 //!
 //! - `ctrlc`: Uses the `ctrlc` crate to properly stop the optimization (and return the current best
-//!    result) after pressing Ctrl+C.
-//! - `ndarrayl`: Support for `ndarray`, `ndarray-linalg` and `ndarray-rand`.
+//!   result) after pressing Ctrl+C.
+//! - `ndarrayl`: Support for `ndarray`, `ndarray-linalg` and `ndarray-rand`. Also enables solvers
+//!   which are implemented on top of `ndarray` (e.g. the interior-point method for quadratic programs).
 //!
 //! ## Running the tests
 //!
@@ -122,6 +161,26 @@
 //! - `jacobian(&self, p: &Self::Param) -> Result<Self::Jacobian, Error>`: Computes the Jacobian
 //!   at `p`.
 //!
+//! Equality and inequality constraints can be added by implementing the `ArgminConstraints` trait
+//! (see the [constraints module](constraints/index.html)).
+//! Cost functions which are sums over samples can implement the `ArgminMinibatchOp` trait to be
+//! used with the stochastic solvers (see the [minibatch module](minibatch/index.html)).
+//! Operators which only implement `apply` can be wrapped in a `FiniteDiffOp`, which fills in
+//! `gradient`, `hessian` and `jacobian` by forward, central or complex-step differences. The
+//! solver then has to be wrapped in `CountFiniteDiff`, which adds the evaluations needed for the
+//! differences to the function evaluation counts (see the [numdiff module](numdiff/index.html)).
+//! Solvers which only need products of the Hessian with vectors (`NewtonCG`, `Steihaug`, `GLTR`
+//! and `TrustRegion`) can be switched to matrix-free mode via `matrix_free()`. They then use the
+//! `ArgminHessianVecProduct` trait instead of `hessian` and fall back to finite differences of
+//! the gradient if it is not implemented (see the
+//! [hessian_vec_product module](hessian_vec_product/index.html)).
+//! Implemented derivatives can be compared with finite differences using the `DerivativeChecker`,
+//! and wrapping a solver in `CheckDerivatives` lets the `Executor` perform this check before the
+//! first iteration (see the [derivcheck module](derivcheck/index.html)).
+//! Cost functions written generically over the `DualNum` scalar trait can be wrapped in an
+//! `AutoDiffOp`, which computes exact gradients and Hessians using dual and hyper-dual numbers
+//! (see the [autodiff module](autodiff/index.html)).
+//!
 //! The following code snippet shows an example of how to use the Rosenbrock test functions from
 //! `argmin-testfunctions` in argmin:
 //!
@@ -537,6 +596,9 @@
 
 extern crate argmin_core;
 extern crate argmin_testfunctions;
+#[cfg(feature = "ndarrayl")]
+extern crate ndarray;
+extern crate num_complex;
 extern crate rand;
 
 /// Definition of all relevant traits and types
@@ -545,10 +607,34 @@ pub mod prelude;
 /// Solvers
 pub mod solver;
 
+/// Constraints
+pub mod constraints;
+
+/// Minibatches
+pub mod minibatch;
+
+/// Preconditioners
+pub mod preconditioner;
+
+/// Hessian-vector products
+pub mod hessian_vec_product;
+
+/// Finite differences
+pub mod numdiff;
+
+/// Derivative checks
+pub mod derivcheck;
+
+/// Forward-mode automatic differentiation
+pub mod autodiff;
+
 /// Macros
 #[macro_use]
 mod macros;
 
+/// Dense linear algebra helpers
+mod linalg;
+
 use argmin_core::*;
 
 /// Testfunctions
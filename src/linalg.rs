@@ -0,0 +1,205 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Dense linear algebra helpers
+//!
+//! Small helpers for solvers which operate directly on `Vec<f64>` and need to solve the (usually
+//! tiny) dense linear systems arising in their inner workings. These are not meant to replace the
+//! `ArgminInv` and `ArgminDot` implementations of `argmin_core`.
+
+use crate::prelude::*;
+
+/// Solves `a * x = b` via Gaussian elimination with partial pivoting.
+pub(crate) fn solve(a: &[Vec<f64>], b: &[f64]) -> Result<Vec<f64>, Error> {
+    let n = b.len();
+    let mut a: Vec<Vec<f64>> = a.to_vec();
+    let mut b: Vec<f64> = b.to_vec();
+    for k in 0..n {
+        let (piv, max) = (k..n)
+            .map(|i| (i, a[i][k].abs()))
+            .fold((k, -1.0), |acc, x| if x.1 > acc.1 { x } else { acc });
+        if max <= std::f64::EPSILON * 1e-3 || max.is_nan() {
+            return Err(ArgminError::ConditionViolated {
+                text: "linalg: matrix is singular.".to_string(),
+            }
+            .into());
+        }
+        a.swap(k, piv);
+        b.swap(k, piv);
+        let (upper, lower) = a.split_at_mut(k + 1);
+        let pivot_row = &upper[k];
+        for (i, row) in lower.iter_mut().enumerate() {
+            let factor = row[k] / pivot_row[k];
+            for (rj, pj) in row.iter_mut().zip(pivot_row.iter()).skip(k) {
+                *rj -= factor * pj;
+            }
+            b[k + 1 + i] -= factor * b[k];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let s: f64 = ((i + 1)..n).map(|j| a[i][j] * x[j]).sum();
+        x[i] = (b[i] - s) / a[i][i];
+    }
+    Ok(x)
+}
+
+/// Eigendecomposition of a symmetric matrix via cyclic Jacobi rotations.
+///
+/// Returns the eigenvalues and a matrix whose columns are the corresponding (orthonormal)
+/// eigenvectors.
+pub(crate) fn symmetric_eigen(a: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut a: Vec<Vec<f64>> = a.to_vec();
+    let mut v: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for _ in 0..100 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j].powi(2))
+            .sum();
+        let diag: f64 = (0..n).map(|i| a[i][i].powi(2)).sum();
+        if off <= std::f64::EPSILON.powi(2) * diag || off < std::f64::MIN_POSITIVE {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[p][q].abs() < std::f64::MIN_POSITIVE {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta.powi(2) + 1.0).sqrt());
+                let c = 1.0 / (t.powi(2) + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (row_p, row_q) = (a[p].clone(), a[q].clone());
+                a[p] = row_p
+                    .iter()
+                    .zip(row_q.iter())
+                    .map(|(apk, aqk)| c * apk - s * aqk)
+                    .collect();
+                a[q] = row_p
+                    .iter()
+                    .zip(row_q.iter())
+                    .map(|(apk, aqk)| s * apk + c * aqk)
+                    .collect();
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+/// Cholesky factorization `a = l * l^T` of a symmetric positive definite matrix.
+///
+/// Returns the lower triangular factor `l`, or `None` if `a` is not (numerically) positive
+/// definite.
+pub(crate) fn cholesky(a: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = a.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for j in 0..n {
+        let d = a[j][j] - l[j][..j].iter().map(|v: &f64| v.powi(2)).sum::<f64>();
+        if d <= 0.0 || d.is_nan() {
+            return None;
+        }
+        l[j][j] = d.sqrt();
+        for i in (j + 1)..n {
+            let s: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            l[i][j] = (a[i][j] - s) / l[j][j];
+        }
+    }
+    Some(l)
+}
+
+/// Solves `l * y = b` for a lower triangular matrix `l`.
+pub(crate) fn forward_substitution(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let mut y = vec![0.0; b.len()];
+    for i in 0..b.len() {
+        let s: f64 = (0..i).map(|k| l[i][k] * y[k]).sum();
+        y[i] = (b[i] - s) / l[i][i];
+    }
+    y
+}
+
+/// Solves `l^T * x = y` for a lower triangular matrix `l`.
+pub(crate) fn backward_substitution(l: &[Vec<f64>], y: &[f64]) -> Vec<f64> {
+    let n = y.len();
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let s: f64 = ((i + 1)..n).map(|k| l[k][i] * x[k]).sum();
+        x[i] = (y[i] - s) / l[i][i];
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_solve() {
+        let a = vec![
+            vec![0.0, 2.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+            vec![3.0, 0.0, 1.0],
+        ];
+        let x = solve(&a, &[5.0, 3.0, 4.0]).unwrap();
+        assert_relative_eq!(x[0], 1.0, epsilon = 1e-12);
+        assert_relative_eq!(x[1], 2.0, epsilon = 1e-12);
+        assert_relative_eq!(x[2], 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_solve_singular() {
+        let a = vec![vec![1.0, 2.0], vec![2.0, 4.0]];
+        assert!(solve(&a, &[1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn test_cholesky() {
+        let a = vec![
+            vec![4.0, 2.0, 0.4],
+            vec![2.0, 5.0, 1.0],
+            vec![0.4, 1.0, 3.0],
+        ];
+        let l = cholesky(&a).unwrap();
+        let y = forward_substitution(&l, &[1.0, 2.0, 3.0]);
+        let x = backward_substitution(&l, &y);
+        let expected = solve(&a, &[1.0, 2.0, 3.0]).unwrap();
+        for (xi, ei) in x.iter().zip(expected.iter()) {
+            assert_relative_eq!(xi, ei, epsilon = 1e-12);
+        }
+        assert!(cholesky(&[vec![1.0, 2.0], vec![2.0, 1.0]]).is_none());
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let a = vec![
+            vec![4.0, 1.0, 0.5],
+            vec![1.0, 3.0, 0.2],
+            vec![0.5, 0.2, 1.0],
+        ];
+        let (d, v) = symmetric_eigen(&a);
+        // A * v_j = d_j * v_j for all eigenpairs
+        for j in 0..3 {
+            for i in 0..3 {
+                let av: f64 = (0..3).map(|k| a[i][k] * v[k][j]).sum();
+                assert_relative_eq!(av, d[j] * v[i][j], epsilon = 1e-10);
+            }
+        }
+    }
+}
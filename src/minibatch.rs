@@ -0,0 +1,207 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Minibatches
+//!
+//! Extension of `ArgminOp` for cost functions which are sums (or means) over a set of samples,
+//! such as the loss of a model on a training data set:
+//!
+//! ```text
+//! f(x) = 1/N sum_{i=0}^{N-1} f_i(x)
+//! ```
+//!
+//! Stochastic solvers only evaluate the cost and the gradient on subsets (minibatches) of the
+//! samples. The minibatches are drawn by a `MinibatchSampler`, which shuffles the samples at the
+//! beginning of each epoch using a seedable random number generator. Evaluations on a minibatch
+//! via an `OpWrapper` are counted as one cost function or gradient evaluation.
+
+use crate::prelude::*;
+use rand::prelude::*;
+use rand_xorshift::XorShiftRng;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Cost functions which can be evaluated on a subset of samples
+pub trait ArgminMinibatchOp: ArgminOp {
+    /// Total number of samples
+    fn num_samples(&self) -> usize;
+
+    /// Cost on the samples with the given indices
+    fn batch_apply(&self, _param: &Self::Param, _indices: &[usize]) -> Result<Self::Output, Error> {
+        Err(ArgminError::NotImplemented {
+            text: "Method `batch_apply` of ArgminMinibatchOp trait not implemented!".to_string(),
+        }
+        .into())
+    }
+
+    /// Gradient on the samples with the given indices
+    fn batch_gradient(
+        &self,
+        _param: &Self::Param,
+        _indices: &[usize],
+    ) -> Result<Self::Param, Error> {
+        Err(ArgminError::NotImplemented {
+            text: "Method `batch_gradient` of ArgminMinibatchOp trait not implemented!".to_string(),
+        }
+        .into())
+    }
+}
+
+/// Counted minibatch evaluations of the operator of an `OpWrapper`
+pub(crate) trait ArgminMinibatchOpWrapper<O: ArgminMinibatchOp> {
+    /// Total number of samples
+    fn num_samples(&self) -> usize;
+
+    /// Cost on the samples with the given indices (counted as one cost function evaluation)
+    fn batch_apply(&mut self, param: &O::Param, indices: &[usize]) -> Result<O::Output, Error>;
+
+    /// Gradient on the samples with the given indices (counted as one gradient evaluation)
+    fn batch_gradient(&mut self, param: &O::Param, indices: &[usize]) -> Result<O::Param, Error>;
+}
+
+impl<O: ArgminMinibatchOp> ArgminMinibatchOpWrapper<O> for OpWrapper<O> {
+    fn num_samples(&self) -> usize {
+        self.op.as_ref().unwrap().num_samples()
+    }
+
+    fn batch_apply(&mut self, param: &O::Param, indices: &[usize]) -> Result<O::Output, Error> {
+        self.cost_func_count += 1;
+        self.op.as_ref().unwrap().batch_apply(param, indices)
+    }
+
+    fn batch_gradient(&mut self, param: &O::Param, indices: &[usize]) -> Result<O::Param, Error> {
+        self.grad_func_count += 1;
+        self.op.as_ref().unwrap().batch_gradient(param, indices)
+    }
+}
+
+/// Draws minibatches of sample indices.
+///
+/// At the beginning of every epoch the indices `0..num_samples` are shuffled and then handed out
+/// in consecutive chunks of `batch_size` indices. The last batch of an epoch may be smaller.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct MinibatchSampler {
+    /// number of samples per batch
+    batch_size: usize,
+    /// random number generator used for shuffling
+    rng: XorShiftRng,
+    /// permutation of the current epoch
+    perm: Vec<usize>,
+    /// position in `perm`
+    pos: usize,
+    /// number of started epochs
+    epoch: u64,
+}
+
+impl MinibatchSampler {
+    /// Constructor
+    pub fn new(batch_size: usize) -> Result<Self, Error> {
+        if batch_size == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "MinibatchSampler: batch_size must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(MinibatchSampler {
+            batch_size,
+            rng: XorShiftRng::from_entropy(),
+            perm: vec![],
+            pos: 0,
+            epoch: 0,
+        })
+    }
+
+    /// Seed the random number generator
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Set number of samples per batch (keeps the state of the random number generator)
+    pub fn set_batch_size(&mut self, batch_size: usize) -> Result<(), Error> {
+        if batch_size == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "MinibatchSampler: batch_size must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.batch_size = batch_size;
+        Ok(())
+    }
+
+    /// Number of samples per batch
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Number of started epochs
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Indices of the next minibatch out of `num_samples` samples
+    pub fn next_batch(&mut self, num_samples: usize) -> Result<Vec<usize>, Error> {
+        if num_samples == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "MinibatchSampler: there must be at least one sample.".to_string(),
+            }
+            .into());
+        }
+        if self.pos >= self.perm.len() || self.perm.len() != num_samples {
+            self.perm = (0..num_samples).collect();
+            self.perm.shuffle(&mut self.rng);
+            self.pos = 0;
+            self.epoch += 1;
+        }
+        let end = (self.pos + self.batch_size).min(num_samples);
+        let batch = self.perm[self.pos..end].to_vec();
+        self.pos = end;
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampler_epochs() {
+        let mut sampler = MinibatchSampler::new(3).unwrap().seed(42);
+        let mut seen = vec![];
+        for _ in 0..4 {
+            seen.extend(sampler.next_batch(10).unwrap());
+        }
+        assert_eq!(sampler.epoch(), 1);
+        seen.sort();
+        assert_eq!(seen, (0..10).collect::<Vec<usize>>());
+        assert_eq!(sampler.next_batch(10).unwrap().len(), 3);
+        assert_eq!(sampler.epoch(), 2);
+    }
+
+    #[test]
+    fn test_sampler_seed() {
+        let mut a = MinibatchSampler::new(4).unwrap().seed(7);
+        let mut b = MinibatchSampler::new(4).unwrap().seed(7);
+        for _ in 0..5 {
+            assert_eq!(a.next_batch(9).unwrap(), b.next_batch(9).unwrap());
+        }
+        assert!(MinibatchSampler::new(0).is_err());
+    }
+
+    #[test]
+    fn test_sampler_set_batch_size() {
+        let mut a = MinibatchSampler::new(1).unwrap().seed(7);
+        a.set_batch_size(4).unwrap();
+        let mut b = MinibatchSampler::new(4).unwrap().seed(7);
+        for _ in 0..5 {
+            assert_eq!(a.next_batch(9).unwrap(), b.next_batch(9).unwrap());
+        }
+        assert!(a.set_batch_size(0).is_err());
+        assert_eq!(a.batch_size(), 4);
+    }
+}
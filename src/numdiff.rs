@@ -0,0 +1,1181 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Finite differences
+//!
+//! `FiniteDiffOp` wraps an operator and fills in the methods `gradient`, `hessian` and `jacobian`
+//! with finite difference approximations. `hessian` and `jacobian` of the wrapped operator are
+//! used if they are implemented (i.e. if they do not return `ArgminError::NotImplemented`). The
+//! gradient is approximated by differences of the cost function unless `analytic_gradient(true)`
+//! is set, in which case the gradient of the wrapped operator is used and the Hessian is computed
+//! from differences of the gradient. This makes every gradient-based solver applicable to
+//! operators which only implement `apply`:
+//!
+//! ```rust
+//! # extern crate argmin;
+//! # use argmin::prelude::*;
+//! # use argmin::numdiff::{CentralDiff, CountFiniteDiff, FiniteDiffOp};
+//! # use argmin::solver::linesearch::MoreThuenteLineSearch;
+//! # use argmin::solver::quasinewton::LBFGS;
+//! # use argmin::testfunctions::rosenbrock_2d;
+//! # #[cfg(feature = "serde1")]
+//! # use serde::{Deserialize, Serialize};
+//! #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+//! #[derive(Clone, Default)]
+//! struct Rosenbrock {}
+//!
+//! impl ArgminOp for Rosenbrock {
+//!     type Param = Vec<f64>;
+//!     type Output = f64;
+//!     type Hessian = ();
+//!     type Jacobian = ();
+//!
+//!     fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+//!         Ok(rosenbrock_2d(p, 1.0, 100.0))
+//!     }
+//! }
+//!
+//! # fn run() -> Result<(), Error> {
+//! let cost = FiniteDiffOp::new(Rosenbrock {}).method(CentralDiff);
+//! let solver = CountFiniteDiff::new(LBFGS::new(MoreThuenteLineSearch::new(), 7));
+//! let res = Executor::new(cost, solver, vec![-1.2, 1.0])
+//!     .max_iters(100)
+//!     .run()?;
+//!
+//! // evaluations of `Rosenbrock::apply`, including those required for the differences
+//! let evaluations = res.state.get_cost_func_count();
+//! # assert!(evaluations > res.state.get_grad_func_count());
+//! # assert!(res.state.get_cost() < 1e-8);
+//! # Ok(())
+//! # }
+//! # fn main() {
+//! #     if let Err(ref e) = run() {
+//! #         println!("{} {}", e.as_fail(), e.backtrace());
+//! #         std::process::exit(1);
+//! #     }
+//! # }
+//! ```
+//!
+//! The difference scheme is chosen via `method(...)`:
+//!
+//! * `ForwardDiff` (default): forward differences, one evaluation per parameter.
+//! * `CentralDiff`: central differences, two evaluations per parameter, more accurate.
+//! * `ComplexStep`: complex-step differences, which are exact up to machine precision and
+//!   suffer from no cancellation. Requires the operator to implement `ArgminComplexOp`.
+//!
+//! Forward and central differences are computed by `argmin_core::finitediff`. The step size for
+//! coordinate `i` is `h * max(1, |x_i|)`, where the relative step sizes `h` of first and second
+//! order differences can be set via `step(...)` and `hessian_step(...)`.
+//!
+//! ## Function evaluation counts
+//!
+//! As usual, `OpWrapper` counts the calls of the solver to `apply`, `gradient`, `hessian` and
+//! `jacobian` of the wrapper. The evaluations of the wrapped operator (and of its gradient) which
+//! are needed for the differences are added to the cost function (and gradient) evaluation counts
+//! of the `OpWrapper` by `CountFiniteDiff`, which the solver must be wrapped in, as in the
+//! example above. Without it, `gradient`, `hessian` and `jacobian` of the wrapper fail instead of
+//! returning differences whose evaluations are missing from the counts.
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] Joaquim R. R. A. Martins, Peter Sturdza and Juan J. Alonso (2003). The complex-step
+//! derivative approximation. ACM Transactions on Mathematical Software 29(3), 245-262.
+
+use crate::prelude::*;
+use argmin_core::finitediff::FiniteDiff;
+pub use num_complex::Complex64;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Step size of the differences in `argmin_core::finitediff` (square root of the machine
+/// precision)
+const FINITEDIFF_STEP: f64 = 1.490_116_119_384_765_6e-8;
+
+/// Vectors (parameters and outputs of operators) which can be differentiated by finite
+/// differences
+pub trait FiniteDiffVector: Sized {
+    /// Coordinates of the vector
+    fn to_f64_vec(&self) -> Vec<f64>;
+
+    /// Vector with the given coordinates
+    fn from_f64_vec(v: Vec<f64>) -> Self;
+}
+
+impl FiniteDiffVector for f64 {
+    fn to_f64_vec(&self) -> Vec<f64> {
+        vec![*self]
+    }
+
+    fn from_f64_vec(v: Vec<f64>) -> Self {
+        v[0]
+    }
+}
+
+impl FiniteDiffVector for Vec<f64> {
+    fn to_f64_vec(&self) -> Vec<f64> {
+        self.clone()
+    }
+
+    fn from_f64_vec(v: Vec<f64>) -> Self {
+        v
+    }
+}
+
+#[cfg(feature = "ndarrayl")]
+impl FiniteDiffVector for ndarray::Array1<f64> {
+    fn to_f64_vec(&self) -> Vec<f64> {
+        self.to_vec()
+    }
+
+    fn from_f64_vec(v: Vec<f64>) -> Self {
+        ndarray::Array1::from(v)
+    }
+}
+
+/// Matrices (Hessians and Jacobians) which can be assembled from finite differences
+pub trait FiniteDiffMatrix: Sized {
+    /// Matrix with the given rows
+    fn from_rows(rows: Vec<Vec<f64>>) -> Self;
+
+    /// Rows of the matrix
+    fn to_rows(&self) -> Vec<Vec<f64>>;
+}
+
+/// For operators without Hessian or Jacobian
+impl FiniteDiffMatrix for () {
+    fn from_rows(_rows: Vec<Vec<f64>>) -> Self {}
+
+    fn to_rows(&self) -> Vec<Vec<f64>> {
+        vec![]
+    }
+}
+
+impl FiniteDiffMatrix for Vec<Vec<f64>> {
+    fn from_rows(rows: Vec<Vec<f64>>) -> Self {
+        rows
+    }
+
+    fn to_rows(&self) -> Vec<Vec<f64>> {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "ndarrayl")]
+impl FiniteDiffMatrix for ndarray::Array2<f64> {
+    fn from_rows(rows: Vec<Vec<f64>>) -> Self {
+        let shape = (rows.len(), rows.first().map_or(0, Vec::len));
+        ndarray::Array2::from_shape_vec(shape, rows.concat()).unwrap()
+    }
+
+    fn to_rows(&self) -> Vec<Vec<f64>> {
+        self.outer_iter().map(|row| row.to_vec()).collect()
+    }
+}
+
+/// Operators which can be evaluated for complex parameters (required by `ComplexStep`)
+pub trait ArgminComplexOp: ArgminOp {
+    /// Evaluates the operator for complex parameters and returns all outputs (a single value for
+    /// cost functions). This must be the analytic continuation of `apply`, i.e. the same
+    /// computation carried out in complex arithmetic (without `abs`, `conj` or comparisons of
+    /// complex numbers).
+    fn apply_complex(&self, param: &[Complex64]) -> Result<Vec<Complex64>, Error>;
+}
+
+/// Gradient as function of the coordinates of the parameter vector
+pub type GradientFn<'a> = dyn Fn(&[f64]) -> Result<Vec<f64>, Error> + 'a;
+
+/// Finite difference scheme of a `FiniteDiffOp`
+pub trait FiniteDiffMethod<O: ArgminOp>: Clone + SerializeAlias {
+    /// Default relative step size of first order differences
+    fn step(&self) -> f64;
+
+    /// Default relative step size of second order differences. `from_gradient` indicates whether
+    /// the Hessian is computed from gradients or from cost function values.
+    fn hessian_step(&self, from_gradient: bool) -> f64;
+
+    /// Gradient of the (scalar) cost function at `x` for the step sizes `h`
+    fn gradient(&self, op: &FiniteDiffOp<O, Self>, x: &[f64], h: &[f64])
+        -> Result<Vec<f64>, Error>;
+
+    /// Jacobian (one row per output) of the operator at `x` for the step sizes `h`
+    fn jacobian(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error>;
+
+    /// Hessian of the (scalar) cost function at `x` computed from cost function values with the
+    /// step sizes `h`
+    fn hessian(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error>;
+
+    /// Hessian at `x` computed from differences of the gradient `grad` with the step sizes `h`
+    /// (default: central differences)
+    fn gradient_differences(
+        &self,
+        grad: &GradientFn,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error> {
+        let scaled = Scaled::new(x, h);
+        let hessian = scaled
+            .origin()
+            .central_hessian(&|y| scaled.eval(|x| scaled.scale(grad(x)?), y));
+        scaled.finish(scaled.unscale_hessian(hessian))
+    }
+}
+
+/// Forward differences
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ForwardDiff;
+
+/// Central differences
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CentralDiff;
+
+/// Complex-step differences (requires `ArgminComplexOp`)
+///
+/// The Hessian is computed from central differences of the complex-step gradient.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ComplexStep;
+
+impl<O> FiniteDiffMethod<O> for ForwardDiff
+where
+    O: ArgminOp,
+    O::Param: FiniteDiffVector,
+    O::Output: FiniteDiffVector,
+{
+    fn step(&self) -> f64 {
+        std::f64::EPSILON.sqrt()
+    }
+
+    fn hessian_step(&self, from_gradient: bool) -> f64 {
+        if from_gradient {
+            std::f64::EPSILON.sqrt()
+        } else {
+            std::f64::EPSILON.cbrt()
+        }
+    }
+
+    fn gradient(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<f64>, Error> {
+        let scaled = Scaled::new(x, h);
+        let grad = scaled
+            .origin()
+            .forward_diff(&|y| scaled.eval_scalar(|x| op.eval_scalar(x), y));
+        scaled.finish(scaled.unscale_gradient(grad))
+    }
+
+    fn jacobian(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error> {
+        let scaled = Scaled::new(x, h);
+        let jacobian = scaled
+            .origin()
+            .forward_jacobian(&|y| scaled.eval(|x| op.eval(x), y));
+        scaled.finish(scaled.unscale_jacobian(jacobian))
+    }
+
+    fn hessian(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error> {
+        let scaled = Scaled::new(x, h);
+        let hessian = scaled
+            .origin()
+            .forward_hessian_nograd(&|y| scaled.eval_scalar(|x| op.eval_scalar(x), y));
+        scaled.finish(scaled.unscale_hessian(hessian))
+    }
+
+    fn gradient_differences(
+        &self,
+        grad: &GradientFn,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error> {
+        let scaled = Scaled::new(x, h);
+        let hessian = scaled
+            .origin()
+            .forward_hessian(&|y| scaled.eval(|x| scaled.scale(grad(x)?), y));
+        scaled.finish(scaled.unscale_hessian(hessian))
+    }
+}
+
+impl<O> FiniteDiffMethod<O> for CentralDiff
+where
+    O: ArgminOp,
+    O::Param: FiniteDiffVector,
+    O::Output: FiniteDiffVector,
+{
+    fn step(&self) -> f64 {
+        std::f64::EPSILON.cbrt()
+    }
+
+    fn hessian_step(&self, from_gradient: bool) -> f64 {
+        if from_gradient {
+            std::f64::EPSILON.cbrt()
+        } else {
+            std::f64::EPSILON.powf(0.25)
+        }
+    }
+
+    fn gradient(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<f64>, Error> {
+        let scaled = Scaled::new(x, h);
+        let grad = scaled
+            .origin()
+            .central_diff(&|y| scaled.eval_scalar(|x| op.eval_scalar(x), y));
+        scaled.finish(scaled.unscale_gradient(grad))
+    }
+
+    fn jacobian(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error> {
+        let scaled = Scaled::new(x, h);
+        let jacobian = scaled
+            .origin()
+            .central_jacobian(&|y| scaled.eval(|x| op.eval(x), y));
+        scaled.finish(scaled.unscale_jacobian(jacobian))
+    }
+
+    fn hessian(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error> {
+        // central differences of the central difference gradient (in scaled coordinates)
+        let scaled = Scaled::new(x, h);
+        let cost = |y: &Vec<f64>| scaled.eval_scalar(|x| op.eval_scalar(x), y);
+        let hessian = scaled.origin().central_hessian(&|y| y.central_diff(&cost));
+        scaled.finish(scaled.unscale_hessian(hessian))
+    }
+}
+
+impl<O> FiniteDiffMethod<O> for ComplexStep
+where
+    O: ArgminComplexOp,
+    O::Param: FiniteDiffVector,
+    O::Output: FiniteDiffVector,
+{
+    fn step(&self) -> f64 {
+        1e-20
+    }
+
+    fn hessian_step(&self, _from_gradient: bool) -> f64 {
+        std::f64::EPSILON.cbrt()
+    }
+
+    fn gradient(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<f64>, Error> {
+        complex_step_columns(op, x, h)?
+            .into_iter()
+            .map(scalar)
+            .collect()
+    }
+
+    fn jacobian(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error> {
+        let columns = complex_step_columns(op, x, h)?;
+        Ok((0..columns.first().map_or(0, Vec::len))
+            .map(|i| columns.iter().map(|c| c[i]).collect())
+            .collect())
+    }
+
+    fn hessian(
+        &self,
+        op: &FiniteDiffOp<O, Self>,
+        x: &[f64],
+        h: &[f64],
+    ) -> Result<Vec<Vec<f64>>, Error> {
+        let grad = |y: &[f64]| self.gradient(op, y, &op.first_order_steps(y));
+        FiniteDiffMethod::<O>::gradient_differences(self, &grad, x, h)
+    }
+}
+
+/// Complex-step derivatives of all outputs of `op` at `x` with respect to each parameter (one
+/// column per parameter) for the step sizes `h`
+fn complex_step_columns<O, M>(
+    op: &FiniteDiffOp<O, M>,
+    x: &[f64],
+    h: &[f64],
+) -> Result<Vec<Vec<f64>>, Error>
+where
+    O: ArgminComplexOp,
+{
+    (0..x.len())
+        .map(|j| {
+            let mut xc: Vec<Complex64> = x.iter().map(|&xi| Complex64::new(xi, 0.0)).collect();
+            xc[j].im = h[j];
+            Ok(op.eval_complex(&xc)?.iter().map(|f| f.im / h[j]).collect())
+        })
+        .collect()
+}
+
+/// Evaluations of the wrapped operator and its gradient which were made for finite differences
+/// and have not yet been added to the counts of an `OpWrapper`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, Default)]
+struct PendingCounts {
+    /// evaluations of `apply` (or `apply_complex`)
+    cost: AtomicU64,
+    /// evaluations of `gradient`
+    grad: AtomicU64,
+    /// set by `CountFiniteDiff`, which adds the pending evaluations to the counts
+    counted: AtomicBool,
+}
+
+/// Finite difference wrapper of an operator
+///
+/// Fills in `gradient`, `hessian` and `jacobian` of operators which do not implement them with
+/// finite difference approximations. See the [module documentation](index.html) for details.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/finitediff.rs)
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+///
+/// [1] Joaquim R. R. A. Martins, Peter Sturdza and Juan J. Alonso (2003). The complex-step
+/// derivative approximation. ACM Transactions on Mathematical Software 29(3), 245-262.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default)]
+pub struct FiniteDiffOp<O, M = ForwardDiff> {
+    /// wrapped operator
+    op: O,
+    /// difference scheme
+    method: M,
+    /// relative step size of first order differences
+    step: Option<f64>,
+    /// relative step size of second order differences
+    hessian_step: Option<f64>,
+    /// use the gradient of the wrapped operator
+    analytic_gradient: bool,
+    /// evaluations for differences which have not been counted yet (shared between clones)
+    pending: Arc<PendingCounts>,
+}
+
+impl<O> FiniteDiffOp<O, ForwardDiff> {
+    /// Constructor (forward differences)
+    pub fn new(op: O) -> Self {
+        FiniteDiffOp {
+            op,
+            method: ForwardDiff,
+            step: None,
+            hessian_step: None,
+            analytic_gradient: false,
+            pending: Arc::new(PendingCounts::default()),
+        }
+    }
+}
+
+impl<O, M> FiniteDiffOp<O, M> {
+    /// Set difference scheme (`ForwardDiff`, `CentralDiff` or `ComplexStep`)
+    pub fn method<M2>(self, method: M2) -> FiniteDiffOp<O, M2> {
+        FiniteDiffOp {
+            op: self.op,
+            method,
+            step: self.step,
+            hessian_step: self.hessian_step,
+            analytic_gradient: self.analytic_gradient,
+            pending: self.pending,
+        }
+    }
+
+    /// Set relative step size of first order differences (default depends on the method)
+    pub fn step(mut self, step: f64) -> Result<Self, Error> {
+        if step <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "FiniteDiffOp: step must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.step = Some(step);
+        Ok(self)
+    }
+
+    /// Set relative step size of second order differences (default depends on the method)
+    pub fn hessian_step(mut self, step: f64) -> Result<Self, Error> {
+        if step <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "FiniteDiffOp: hessian_step must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.hessian_step = Some(step);
+        Ok(self)
+    }
+
+    /// Use the gradient of the wrapped operator instead of finite differences and compute the
+    /// Hessian from differences of the gradient (default: false)
+    pub fn analytic_gradient(mut self, analytic_gradient: bool) -> Self {
+        self.analytic_gradient = analytic_gradient;
+        self
+    }
+
+    /// Wrapped operator
+    pub fn inner(&self) -> &O {
+        &self.op
+    }
+
+    /// Returns and resets the numbers of evaluations of the wrapped operator and its gradient
+    /// which were made for differences (by this wrapper and all of its clones)
+    pub(crate) fn take_counts(&self) -> (u64, u64) {
+        (
+            self.pending.cost.swap(0, Ordering::Relaxed),
+            self.pending.grad.swap(0, Ordering::Relaxed),
+        )
+    }
+
+    /// Marks the evaluations for differences as counted (by this wrapper and all of its clones)
+    pub(crate) fn set_counted(&self) {
+        self.pending.counted.store(true, Ordering::Relaxed);
+    }
+
+    /// Fails unless the evaluations for differences are counted. Without `CountFiniteDiff`, the
+    /// function evaluation counts of the `OpWrapper` would silently be too low.
+    fn check_counted(&self) -> Result<(), Error> {
+        if !self.pending.counted.load(Ordering::Relaxed) {
+            return Err(ArgminError::NotInitialized {
+                text: "FiniteDiffOp: the solver must be wrapped in `CountFiniteDiff`.".to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl<O, M> FiniteDiffOp<O, M>
+where
+    O: ArgminOp,
+    O::Param: FiniteDiffVector,
+    O::Output: FiniteDiffVector,
+    M: FiniteDiffMethod<O>,
+{
+    /// Evaluates the wrapped operator at the coordinates `x`
+    pub fn eval(&self, x: &[f64]) -> Result<Vec<f64>, Error> {
+        self.pending.cost.fetch_add(1, Ordering::Relaxed);
+        Ok(self
+            .op
+            .apply(&O::Param::from_f64_vec(x.to_vec()))?
+            .to_f64_vec())
+    }
+
+    /// Evaluates the wrapped (scalar) cost function at the coordinates `x`
+    pub fn eval_scalar(&self, x: &[f64]) -> Result<f64, Error> {
+        scalar(self.eval(x)?)
+    }
+
+    /// Evaluates the gradient of the wrapped operator at the coordinates `x`
+    pub fn eval_gradient(&self, x: &[f64]) -> Result<Vec<f64>, Error> {
+        self.pending.grad.fetch_add(1, Ordering::Relaxed);
+        Ok(self
+            .op
+            .gradient(&O::Param::from_f64_vec(x.to_vec()))?
+            .to_f64_vec())
+    }
+
+    /// Step sizes of first order differences at `x`
+    pub fn first_order_steps(&self, x: &[f64]) -> Vec<f64> {
+        steps(x, self.step.unwrap_or_else(|| self.method.step()))
+    }
+
+    /// Step sizes of second order differences at `x`
+    pub fn second_order_steps(&self, x: &[f64], from_gradient: bool) -> Vec<f64> {
+        let step = self
+            .hessian_step
+            .unwrap_or_else(|| self.method.hessian_step(from_gradient));
+        steps(x, step)
+    }
+
+    /// Finite difference approximation of the gradient at `x`
+    pub(crate) fn numeric_gradient(&self, x: &[f64]) -> Result<Vec<f64>, Error> {
+        self.method.gradient(self, x, &self.first_order_steps(x))
+    }
+
+    /// Finite difference approximation of the Jacobian at `x`
+    pub(crate) fn numeric_jacobian(&self, x: &[f64]) -> Result<Vec<Vec<f64>>, Error> {
+        self.method.jacobian(self, x, &self.first_order_steps(x))
+    }
+
+    /// Finite difference approximation of the Hessian at `x` (from differences of the gradient
+    /// of the wrapped operator if `analytic_gradient` is set)
+    pub(crate) fn numeric_hessian(&self, x: &[f64]) -> Result<Vec<Vec<f64>>, Error> {
+        let h = self.second_order_steps(x, self.analytic_gradient);
+        if self.analytic_gradient {
+            self.method
+                .gradient_differences(&|y: &[f64]| self.eval_gradient(y), x, &h)
+        } else {
+            self.method.hessian(self, x, &h)
+        }
+    }
+}
+
+impl<O: ArgminComplexOp, M> FiniteDiffOp<O, M> {
+    /// Evaluates the wrapped operator at the complex coordinates `x`
+    pub fn eval_complex(&self, x: &[Complex64]) -> Result<Vec<Complex64>, Error> {
+        self.pending.cost.fetch_add(1, Ordering::Relaxed);
+        self.op.apply_complex(x)
+    }
+}
+
+impl<O, M> ArgminOp for FiniteDiffOp<O, M>
+where
+    O: ArgminOp,
+    O::Param: FiniteDiffVector,
+    O::Output: FiniteDiffVector,
+    O::Hessian: FiniteDiffMatrix,
+    O::Jacobian: FiniteDiffMatrix,
+    M: FiniteDiffMethod<O> + Send + Sync,
+{
+    type Param = O::Param;
+    type Output = O::Output;
+    type Hessian = O::Hessian;
+    type Jacobian = O::Jacobian;
+
+    fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        self.op.apply(p)
+    }
+
+    fn gradient(&self, p: &Self::Param) -> Result<Self::Param, Error> {
+        if self.analytic_gradient {
+            return self.op.gradient(p);
+        }
+        self.check_counted()?;
+        Ok(Self::Param::from_f64_vec(
+            self.numeric_gradient(&p.to_f64_vec())?,
+        ))
+    }
+
+    fn hessian(&self, p: &Self::Param) -> Result<Self::Hessian, Error> {
+        match self.op.hessian(p) {
+            Err(ref e) if not_implemented(e) => {}
+            hessian => return hessian,
+        }
+        self.check_counted()?;
+        Ok(Self::Hessian::from_rows(
+            self.numeric_hessian(&p.to_f64_vec())?,
+        ))
+    }
+
+    fn jacobian(&self, p: &Self::Param) -> Result<Self::Jacobian, Error> {
+        match self.op.jacobian(p) {
+            Err(ref e) if not_implemented(e) => {}
+            jacobian => return jacobian,
+        }
+        self.check_counted()?;
+        Ok(Self::Jacobian::from_rows(
+            self.numeric_jacobian(&p.to_f64_vec())?,
+        ))
+    }
+
+    fn modify(&self, p: &Self::Param, t: f64) -> Result<Self::Param, Error> {
+        self.op.modify(p, t)
+    }
+}
+
+/// Adds the evaluations made by a `FiniteDiffOp` for finite differences to the function
+/// evaluation counts
+///
+/// After `init` and every iteration of the wrapped solver, the evaluations of the wrapped
+/// operator and of its gradient which were needed for the differences are added to the cost
+/// function and gradient evaluation counts of the `OpWrapper`. This includes evaluations made by
+/// line searches or other inner solvers.
+///
+/// Solvers which need derivatives of a `FiniteDiffOp` must be wrapped in `CountFiniteDiff`,
+/// otherwise the evaluation of the differences fails.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct CountFiniteDiff<S> {
+    /// wrapped solver
+    solver: S,
+}
+
+impl<S> CountFiniteDiff<S> {
+    /// Constructor
+    pub fn new(solver: S) -> Self {
+        CountFiniteDiff { solver }
+    }
+
+    /// Adds the pending evaluations of the operator to the counts of `op`
+    fn count<O, M>(op: &mut OpWrapper<FiniteDiffOp<O, M>>)
+    where
+        FiniteDiffOp<O, M>: ArgminOp,
+    {
+        if let Some(ref fd) = op.op {
+            let (cost, grad) = fd.take_counts();
+            op.cost_func_count += cost;
+            op.grad_func_count += grad;
+        }
+    }
+}
+
+impl<O, M, S> Solver<FiniteDiffOp<O, M>> for CountFiniteDiff<S>
+where
+    FiniteDiffOp<O, M>: ArgminOp,
+    S: Solver<FiniteDiffOp<O, M>>,
+{
+    const NAME: &'static str = S::NAME;
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<FiniteDiffOp<O, M>>,
+        state: &IterState<FiniteDiffOp<O, M>>,
+    ) -> Result<Option<ArgminIterData<FiniteDiffOp<O, M>>>, Error> {
+        if let Some(ref fd) = op.op {
+            fd.set_counted();
+        }
+        let data = self.solver.init(op, state);
+        Self::count(op);
+        data
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<FiniteDiffOp<O, M>>,
+        state: &IterState<FiniteDiffOp<O, M>>,
+    ) -> Result<ArgminIterData<FiniteDiffOp<O, M>>, Error> {
+        let data = self.solver.next_iter(op, state);
+        Self::count(op);
+        data
+    }
+
+    fn terminate(&mut self, state: &IterState<FiniteDiffOp<O, M>>) -> TerminationReason {
+        self.solver.terminate(state)
+    }
+}
+
+/// Scaled coordinates `y` of the points `x + diag(h) y / FINITEDIFF_STEP`
+///
+/// `argmin_core::finitediff` uses a fixed step size. Differences with respect to `y` at `y = 0`
+/// therefore correspond to differences with the step sizes `h` with respect to `x`. Since the
+/// functions passed to `argmin_core::finitediff` cannot fail, the first error of an evaluation is
+/// stored and returned by `finish`.
+struct Scaled<'a> {
+    /// point at which the derivatives are computed
+    x: &'a [f64],
+    /// scaling factors `h / FINITEDIFF_STEP`
+    s: Vec<f64>,
+    /// first error of an evaluation
+    error: RefCell<Option<Error>>,
+    /// number of outputs of the last successful evaluation
+    dim: Cell<usize>,
+}
+
+impl<'a> Scaled<'a> {
+    /// Constructor
+    fn new(x: &'a [f64], h: &[f64]) -> Self {
+        Scaled {
+            x,
+            s: h.iter().map(|hi| hi / FINITEDIFF_STEP).collect(),
+            error: RefCell::new(None),
+            dim: Cell::new(0),
+        }
+    }
+
+    /// Scaled coordinates of `x`
+    fn origin(&self) -> Vec<f64> {
+        vec![0.0; self.x.len()]
+    }
+
+    /// Evaluates `f` at the point with the scaled coordinates `y`. After an error, `f` is not
+    /// evaluated anymore and NaNs are returned instead.
+    fn eval<F>(&self, f: F, y: &[f64]) -> Vec<f64>
+    where
+        F: Fn(&[f64]) -> Result<Vec<f64>, Error>,
+    {
+        if self.error.borrow().is_none() {
+            let x: Vec<f64> = self
+                .x
+                .iter()
+                .zip(self.s.iter().zip(y.iter()))
+                .map(|(xi, (si, yi))| xi + si * yi)
+                .collect();
+            match f(&x) {
+                Ok(v) => {
+                    self.dim.set(v.len());
+                    return v;
+                }
+                Err(e) => *self.error.borrow_mut() = Some(e),
+            }
+        }
+        vec![std::f64::NAN; self.dim.get()]
+    }
+
+    /// Evaluates the scalar function `f` at the point with the scaled coordinates `y`
+    fn eval_scalar<F>(&self, f: F, y: &[f64]) -> f64
+    where
+        F: Fn(&[f64]) -> Result<f64, Error>,
+    {
+        self.eval(|x| Ok(vec![f(x)?]), y)
+            .first()
+            .cloned()
+            .unwrap_or(std::f64::NAN)
+    }
+
+    /// Gradient `g` with respect to `x` as gradient with respect to `y`
+    fn scale(&self, g: Vec<f64>) -> Result<Vec<f64>, Error> {
+        Ok(g.iter()
+            .zip(self.s.iter())
+            .map(|(gi, si)| gi * si)
+            .collect())
+    }
+
+    /// Gradient with respect to `x` from the gradient `g` with respect to `y`
+    fn unscale_gradient(&self, g: Vec<f64>) -> Vec<f64> {
+        g.iter()
+            .zip(self.s.iter())
+            .map(|(gi, si)| gi / si)
+            .collect()
+    }
+
+    /// Jacobian with respect to `x` from the Jacobian `j` with respect to `y`
+    fn unscale_jacobian(&self, j: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+        j.into_iter()
+            .map(|row| self.unscale_gradient(row))
+            .collect()
+    }
+
+    /// Symmetric Hessian with respect to `x` from the Hessian `h` with respect to `y`
+    fn unscale_hessian(&self, h: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+        let h: Vec<Vec<f64>> = h
+            .into_iter()
+            .zip(self.s.iter())
+            .map(|(row, si)| self.unscale_gradient(row).iter().map(|x| x / si).collect())
+            .collect();
+        symmetrize(h)
+    }
+
+    /// Returns the first error of an evaluation or `value` if there was none
+    fn finish<T>(&self, value: T) -> Result<T, Error> {
+        match self.error.borrow_mut().take() {
+            Some(e) => Err(e),
+            None => Ok(value),
+        }
+    }
+}
+
+/// Whether `e` is `ArgminError::NotImplemented`
+pub(crate) fn not_implemented(e: &Error) -> bool {
+    matches!(
+        e.downcast_ref::<ArgminError>(),
+        Some(ArgminError::NotImplemented { .. })
+    )
+}
+
+/// Single value of the output of a scalar cost function
+pub(crate) fn scalar(v: Vec<f64>) -> Result<f64, Error> {
+    if v.len() != 1 {
+        return Err(ArgminError::ConditionViolated {
+            text: "FiniteDiffOp: gradient and Hessian require a scalar cost function.".to_string(),
+        }
+        .into());
+    }
+    Ok(v[0])
+}
+
+/// Step sizes `step * max(1, |x_i|)`
+fn steps(x: &[f64], step: f64) -> Vec<f64> {
+    x.iter().map(|xi| step * xi.abs().max(1.0)).collect()
+}
+
+/// Symmetric matrix `(A + A^T) / 2`
+fn symmetrize(a: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let n = a.len();
+    (0..n)
+        .map(|i| (0..n).map(|j| 0.5 * (a[i][j] + a[j][i])).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::quasinewton::LBFGS;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(finitediff_op, FiniteDiffOp<MinimalNoOperator>);
+
+    test_trait_impl!(
+        count_finite_diff,
+        CountFiniteDiff<LBFGS<MoreThuenteLineSearch<Vec<f64>>, Vec<f64>>>
+    );
+
+    /// `f(x) = exp(x_0) x_1^2 + sin(x_0 x_1)`, counts its evaluations
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Func {
+        evaluations: Arc<AtomicU64>,
+    }
+
+    impl ArgminOp for Func {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            self.evaluations.fetch_add(1, Ordering::Relaxed);
+            Ok(p[0].exp() * p[1].powi(2) + (p[0] * p[1]).sin())
+        }
+    }
+
+    impl ArgminComplexOp for Func {
+        fn apply_complex(&self, p: &[Complex64]) -> Result<Vec<Complex64>, Error> {
+            self.evaluations.fetch_add(1, Ordering::Relaxed);
+            Ok(vec![p[0].exp() * p[1] * p[1] + (p[0] * p[1]).sin()])
+        }
+    }
+
+    fn gradient(p: &[f64]) -> Vec<f64> {
+        let c = (p[0] * p[1]).cos();
+        vec![
+            p[0].exp() * p[1].powi(2) + p[1] * c,
+            2.0 * p[0].exp() * p[1] + p[0] * c,
+        ]
+    }
+
+    fn hessian(p: &[f64]) -> Vec<Vec<f64>> {
+        let (s, c) = (p[0] * p[1]).sin_cos();
+        let h01 = 2.0 * p[0].exp() * p[1] + c - p[0] * p[1] * s;
+        vec![
+            vec![p[0].exp() * p[1].powi(2) - p[1].powi(2) * s, h01],
+            vec![h01, 2.0 * p[0].exp() - p[0].powi(2) * s],
+        ]
+    }
+
+    /// Residuals `r(x) = (x_0 x_1, x_0 + x_1^2, exp(x_0))`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Residuals {}
+
+    impl ArgminOp for Residuals {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Hessian = ();
+        type Jacobian = Vec<Vec<f64>>;
+
+        fn apply(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![p[0] * p[1], p[0] + p[1].powi(2), p[0].exp()])
+        }
+    }
+
+    /// `f(x) = exp(x_0) x_1^2 + sin(x_0 x_1)` with gradient
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct WithGradient {}
+
+    impl ArgminOp for WithGradient {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Func::default().apply(p)
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(gradient(p))
+        }
+    }
+
+    fn assert_close(a: &[f64], b: &[f64], tol: f64) {
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() < tol, "{} vs. {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_gradient() {
+        let x = vec![0.3, -1.2];
+        let g = gradient(&x);
+        let op = FiniteDiffOp::new(Func::default());
+        op.set_counted();
+        assert_close(&op.gradient(&x).unwrap(), &g, 1e-6);
+        assert_eq!(op.take_counts(), (3, 0));
+        let op = op.method(CentralDiff);
+        assert_close(&op.gradient(&x).unwrap(), &g, 1e-9);
+        let op = op.method(ComplexStep);
+        op.take_counts();
+        assert_close(&op.gradient(&x).unwrap(), &g, 1e-14);
+        assert_eq!(op.take_counts(), (2, 0));
+    }
+
+    #[test]
+    fn test_hessian() {
+        let x = vec![0.3, -1.2];
+        let h = hessian(&x);
+        let op = FiniteDiffOp::new(Func::default());
+        op.set_counted();
+        for (a, b) in op.hessian(&x).unwrap().iter().zip(h.iter()) {
+            assert_close(a, b, 1e-4);
+        }
+        let op = op.method(CentralDiff);
+        for (a, b) in op.hessian(&x).unwrap().iter().zip(h.iter()) {
+            assert_close(a, b, 1e-3);
+        }
+        let op = op.method(ComplexStep);
+        for (a, b) in op.hessian(&x).unwrap().iter().zip(h.iter()) {
+            assert_close(a, b, 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_jacobian() {
+        let x = vec![0.3, -1.2];
+        let op = FiniteDiffOp::new(Residuals {}).method(CentralDiff);
+        op.set_counted();
+        let jacobian = op.jacobian(&x).unwrap();
+        let expected = vec![
+            vec![x[1], x[0]],
+            vec![1.0, 2.0 * x[1]],
+            vec![x[0].exp(), 0.0],
+        ];
+        assert_eq!(jacobian.len(), 3);
+        for (a, b) in jacobian.iter().zip(expected.iter()) {
+            assert_close(a, b, 1e-4);
+        }
+        // differences of non-scalar operators are no gradient
+        assert!(op.gradient(&x).is_err());
+    }
+
+    #[test]
+    fn test_step() {
+        // forward differences of `f(x) = exp(x_0) x_1^2 + sin(x_0 x_1)` with respect to `x_1`
+        // with step size `h`
+        let x = vec![0.3, -1.2];
+        let h = 1e-2 * 1.2;
+        let op = FiniteDiffOp::new(Func::default()).step(1e-2).unwrap();
+        op.set_counted();
+        let expected = (op.apply(&vec![x[0], x[1] + h]).unwrap() - op.apply(&x).unwrap()) / h;
+        assert!((op.gradient(&x).unwrap()[1] - expected).abs() < 1e-6);
+        assert!((expected - gradient(&x)[1]).abs() > 1e-3);
+    }
+
+    #[test]
+    fn test_analytic_gradient() {
+        let x = vec![0.3, -1.2];
+
+        // the gradient of the operator is only used if requested
+        let op = FiniteDiffOp::new(WithGradient {});
+        op.set_counted();
+        assert_ne!(op.gradient(&x).unwrap(), gradient(&x));
+        assert_close(&op.gradient(&x).unwrap(), &gradient(&x), 1e-6);
+        assert_eq!(op.take_counts(), (6, 0));
+
+        let op = op.method(CentralDiff).analytic_gradient(true);
+        assert_eq!(op.gradient(&x).unwrap(), gradient(&x));
+        for (a, b) in op.hessian(&x).unwrap().iter().zip(hessian(&x).iter()) {
+            assert_close(a, b, 1e-4);
+        }
+        let (cost_count, grad_count) = op.take_counts();
+        assert_eq!(cost_count, 0);
+        assert!(grad_count > 0);
+    }
+
+    /// Gradient descent with fixed step length
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone)]
+    struct GradientStep {}
+
+    impl<O: ArgminOp<Param = Vec<f64>, Output = f64>> Solver<O> for GradientStep {
+        fn next_iter(
+            &mut self,
+            op: &mut OpWrapper<O>,
+            state: &IterState<O>,
+        ) -> Result<ArgminIterData<O>, Error> {
+            let param = state.get_param();
+            let grad = op.gradient(&param)?;
+            let param = param.scaled_add(&(-0.1), &grad);
+            let cost = op.apply(&param)?;
+            Ok(ArgminIterData::new().param(param).cost(cost))
+        }
+    }
+
+    #[test]
+    fn test_counts() {
+        // with `CountFiniteDiff`, all evaluations of the operator are counted by the `OpWrapper`
+        let func = Func::default();
+        let op = FiniteDiffOp::new(func.clone()).method(CentralDiff);
+        let res = Executor::new(op, CountFiniteDiff::new(GradientStep {}), vec![0.3, -1.2])
+            .max_iters(5)
+            .run()
+            .unwrap();
+        assert_eq!(res.state.get_grad_func_count(), 5);
+        assert_eq!(res.state.get_cost_func_count(), 25);
+        assert_eq!(func.evaluations.load(Ordering::Relaxed), 25);
+    }
+
+    #[test]
+    fn test_uncounted() {
+        // without `CountFiniteDiff`, the counts would be too low, therefore the solver fails
+        let func = Func::default();
+        let op = FiniteDiffOp::new(func.clone()).method(CentralDiff);
+        let res = Executor::new(op, GradientStep {}, vec![0.3, -1.2])
+            .max_iters(5)
+            .run();
+        assert!(res.is_err());
+        assert_eq!(func.evaluations.load(Ordering::Relaxed), 0);
+
+        let op = FiniteDiffOp::new(Func::default());
+        let solver = LBFGS::new(MoreThuenteLineSearch::new(), 7);
+        assert!(Executor::new(op, solver, vec![0.3, -1.2])
+            .max_iters(5)
+            .run()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parameters() {
+        assert!(FiniteDiffOp::new(Func::default()).step(0.0).is_err());
+        assert!(FiniteDiffOp::new(Func::default())
+            .hessian_step(-1.0)
+            .is_err());
+        let op = FiniteDiffOp::new(Func::default()).step(1e-4).unwrap();
+        assert_eq!(op.first_order_steps(&[2.0, -0.5]), vec![2e-4, 1e-4]);
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Preconditioners
+//!
+//! A preconditioner `M` is a symmetric positive definite approximation of the matrix `A` of a
+//! linear system (or of the Hessian in a Newton or trust region subproblem) whose inverse can be
+//! applied cheaply. Conjugate gradient based solvers converge faster on `M^{-1} A` if it is better
+//! conditioned than `A`.
+//!
+//! Preconditioners are used by `ConjugateGradient`, `NewtonCG` and `Steihaug`. Besides
+//! implementing `Preconditioner` for a custom type, the following implementations are available:
+//!
+//! * `IdentityPreconditioner`: no preconditioning (default)
+//! * `JacobiPreconditioner`: scaling with the inverse of the diagonal of `A`
+//! * `OpPreconditioner`: wraps a user supplied operator whose `apply` method computes `M^{-1} r`
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// Application of the inverse of a preconditioner
+pub trait Preconditioner<P> {
+    /// Computes `z = M^{-1} r`
+    fn precondition(&self, r: &P) -> Result<P, Error>;
+}
+
+/// No preconditioning (`M = I`)
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, PartialEq, PartialOrd, Default)]
+pub struct IdentityPreconditioner {}
+
+impl IdentityPreconditioner {
+    /// Constructor
+    pub fn new() -> Self {
+        IdentityPreconditioner {}
+    }
+}
+
+impl<P: Clone> Preconditioner<P> for IdentityPreconditioner {
+    fn precondition(&self, r: &P) -> Result<P, Error> {
+        Ok(r.clone())
+    }
+}
+
+/// Jacobi (diagonal) preconditioner `M = diag(A)`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct JacobiPreconditioner<P> {
+    /// inverse of the diagonal
+    inv_diag: P,
+}
+
+impl<P> JacobiPreconditioner<P>
+where
+    P: FromIterator<f64>,
+    for<'a> &'a P: IntoIterator<Item = &'a f64>,
+{
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// `diag`: diagonal of `A`, all entries must be positive
+    pub fn new(diag: &P) -> Result<Self, Error> {
+        if diag.into_iter().any(|d| *d <= 0.0 || d.is_nan()) {
+            return Err(ArgminError::InvalidParameter {
+                text: "JacobiPreconditioner: all diagonal entries must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(JacobiPreconditioner {
+            inv_diag: diag.into_iter().map(|d| 1.0 / d).collect(),
+        })
+    }
+}
+
+impl<P> Preconditioner<P> for JacobiPreconditioner<P>
+where
+    P: ArgminMul<P, P>,
+{
+    fn precondition(&self, r: &P) -> Result<P, Error> {
+        Ok(r.mul(&self.inv_diag))
+    }
+}
+
+/// User supplied preconditioner: the `apply` method of the wrapped operator computes `M^{-1} r`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct OpPreconditioner<O> {
+    /// operator
+    op: O,
+}
+
+impl<O> OpPreconditioner<O> {
+    /// Constructor
+    pub fn new(op: O) -> Self {
+        OpPreconditioner { op }
+    }
+}
+
+impl<O> Preconditioner<O::Param> for OpPreconditioner<O>
+where
+    O: ArgminOp<Output = <O as ArgminOp>::Param>,
+{
+    fn precondition(&self, r: &O::Param) -> Result<O::Param, Error> {
+        self.op.apply(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(identity_preconditioner, IdentityPreconditioner);
+
+    test_trait_impl!(jacobi_preconditioner, JacobiPreconditioner<Vec<f64>>);
+
+    test_trait_impl!(
+        op_preconditioner,
+        OpPreconditioner<NoOperator<Vec<f64>, Vec<f64>, (), ()>>
+    );
+
+    #[test]
+    fn test_jacobi() {
+        let precond = JacobiPreconditioner::new(&vec![2.0, 4.0]).unwrap();
+        let z = precond.precondition(&vec![1.0, 1.0]).unwrap();
+        assert!((z[0] - 0.5).abs() < std::f64::EPSILON);
+        assert!((z[1] - 0.25).abs() < std::f64::EPSILON);
+        assert!(JacobiPreconditioner::new(&vec![2.0, 0.0]).is_err());
+    }
+}
@@ -9,4 +9,11 @@
 //!
 //! Put `argmin::prelude::*` on top of your code to get all relevant traits into scope.
 
+pub use crate::autodiff::ArgminDualOp;
+pub use crate::constraints::ArgminConstraints;
+pub use crate::hessian_vec_product::ArgminHessianVecProduct;
+pub use crate::minibatch::ArgminMinibatchOp;
+pub use crate::numdiff::ArgminComplexOp;
+pub use crate::preconditioner::Preconditioner;
+pub use crate::solver::trustregion::ArgminTrustRegionSubproblem;
 pub use argmin_core::*;
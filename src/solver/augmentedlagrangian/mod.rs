@@ -0,0 +1,655 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Augmented Lagrangian method
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] Ernesto G. Birgin and José Mario Martínez (2014). Practical Augmented Lagrangian Methods
+//! for Constrained Optimization. SIAM. ISBN 978-1-611973-35-8.
+
+use crate::constraints::{check_rows, infeasibility};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// Augmented Lagrangian method
+///
+/// Solves problems with equality constraints `h(x) = 0` and inequality constraints `g(x) <= 0`
+/// (see `ArgminConstraints`) by approximately minimizing a sequence of augmented Lagrangians
+///
+/// ```text
+/// L(x) = f(x) + sum_i (lambda_i h_i(x) + mu/2 h_i(x)^2)
+///             + 1/(2 mu) sum_j (max(0, nu_j + mu g_j(x))^2 - nu_j^2)
+/// ```
+///
+/// with an unconstrained inner solver such as `LBFGS`, `BFGS` or `NewtonCG`. After each inner
+/// solve the multipliers are updated by `lambda_i += mu h_i(x)` and
+/// `nu_j = max(0, nu_j + mu g_j(x))`. The penalty `mu` is increased if the violation of the
+/// constraints (and of complementarity) did not decrease sufficiently.
+///
+/// The cost is only reported for iterates which violate the constraints by at most `tol`, all
+/// other iterates have infinite cost. This way the best parameter vector is always a feasible
+/// one as soon as one has been found.
+///
+/// By default, the Hessian of the augmented Lagrangian is not available to the inner solver and
+/// no requirements are placed on `ArgminOp::Hessian`. Inner solvers which need it (such as
+/// `NewtonCG`) require `with_hessian()`, which assembles it from `ArgminOp::hessian` and the
+/// Hessians of the constraints (`eq_hessians` and `ineq_hessians`). This requires the Hessian
+/// type to support addition, scalar multiplication and the outer product `row.dot(row)` of
+/// parameter vectors.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/augmentedlagrangian.rs)
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+///
+/// [1] Ernesto G. Birgin and José Mario Martínez (2014). Practical Augmented Lagrangian Methods
+/// for Constrained Optimization. SIAM. ISBN 978-1-611973-35-8.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct AugmentedLagrangian<S, H = WithoutHessian> {
+    /// solver for the unconstrained subproblems
+    inner: S,
+    /// maximum number of iterations of the inner solver
+    max_inner_iters: u64,
+    /// multipliers of the equality constraints
+    lambda_eq: Vec<f64>,
+    /// multipliers of the inequality constraints
+    lambda_ineq: Vec<f64>,
+    /// penalty parameter
+    mu: f64,
+    /// factor by which the penalty parameter is increased
+    mu_factor: f64,
+    /// required reduction of the violation for the penalty parameter to be kept
+    reduction: f64,
+    /// tolerance of the constraint violation
+    tol: f64,
+    /// tolerance of the norm of the gradient of the Lagrangian
+    gtol: f64,
+    /// violation of the previous iteration
+    prev_violation: f64,
+    /// availability of the Hessian of the augmented Lagrangian
+    hessian: std::marker::PhantomData<H>,
+}
+
+impl<S> AugmentedLagrangian<S, WithoutHessian> {
+    /// Constructor
+    pub fn new(inner: S) -> Self {
+        AugmentedLagrangian {
+            inner,
+            max_inner_iters: 1000,
+            lambda_eq: vec![],
+            lambda_ineq: vec![],
+            mu: 10.0,
+            mu_factor: 10.0,
+            reduction: 0.25,
+            tol: 1e-6,
+            gtol: 1e-6,
+            prev_violation: f64::INFINITY,
+            hessian: std::marker::PhantomData,
+        }
+    }
+
+    /// Provide the Hessian of the augmented Lagrangian to the inner solver
+    pub fn with_hessian(self) -> AugmentedLagrangian<S, WithHessian> {
+        AugmentedLagrangian {
+            inner: self.inner,
+            max_inner_iters: self.max_inner_iters,
+            lambda_eq: self.lambda_eq,
+            lambda_ineq: self.lambda_ineq,
+            mu: self.mu,
+            mu_factor: self.mu_factor,
+            reduction: self.reduction,
+            tol: self.tol,
+            gtol: self.gtol,
+            prev_violation: self.prev_violation,
+            hessian: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S, H> AugmentedLagrangian<S, H> {
+    /// Set initial multipliers of the equality and the inequality constraints (default: zero).
+    /// Multipliers of inequality constraints must be non-negative.
+    pub fn multipliers(
+        mut self,
+        lambda_eq: Vec<f64>,
+        lambda_ineq: Vec<f64>,
+    ) -> Result<Self, Error> {
+        if lambda_ineq.iter().any(|&nu| nu < 0.0) {
+            return Err(ArgminError::InvalidParameter {
+                text: "AugmentedLagrangian: multipliers of inequality constraints must be >= 0."
+                    .to_string(),
+            }
+            .into());
+        }
+        self.lambda_eq = lambda_eq;
+        self.lambda_ineq = lambda_ineq;
+        Ok(self)
+    }
+
+    /// Set initial penalty parameter (default: 10.0)
+    pub fn penalty(mut self, mu: f64) -> Result<Self, Error> {
+        if mu <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "AugmentedLagrangian: penalty must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.mu = mu;
+        Ok(self)
+    }
+
+    /// Set factor by which the penalty parameter is increased (default: 10.0)
+    pub fn penalty_factor(mut self, factor: f64) -> Result<Self, Error> {
+        if factor <= 1.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "AugmentedLagrangian: penalty factor must be > 1.".to_string(),
+            }
+            .into());
+        }
+        self.mu_factor = factor;
+        Ok(self)
+    }
+
+    /// Set tolerance of the constraint violation (default: 1e-6)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "AugmentedLagrangian: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Set tolerance of the norm of the gradient of the Lagrangian (default: 1e-6)
+    pub fn gtol(mut self, gtol: f64) -> Result<Self, Error> {
+        if gtol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "AugmentedLagrangian: gtol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.gtol = gtol;
+        Ok(self)
+    }
+
+    /// Set maximum number of iterations of the inner solver (default: 1000)
+    pub fn max_inner_iters(mut self, iters: u64) -> Self {
+        self.max_inner_iters = iters;
+        self
+    }
+
+    /// Violation of the constraints and of complementarity
+    fn violation(&self, h: &[f64], g: &[f64]) -> f64 {
+        h.iter()
+            .map(|hi| hi.abs())
+            .chain(
+                g.iter()
+                    .zip(self.lambda_ineq.iter())
+                    .map(|(gj, nu)| gj.max(-nu / self.mu).abs()),
+            )
+            .fold(0.0, f64::max)
+    }
+}
+
+impl<O, S, H> Solver<O> for AugmentedLagrangian<S, H>
+where
+    O: ArgminConstraints<Output = f64>,
+    O::Param: ArgminScaledAdd<O::Param, f64, O::Param> + ArgminNorm<f64>,
+    S: Clone + Solver<OpWrapper<AugmentedLagrangianOp<O, H>>>,
+    H: LagrangianHessian<O>,
+{
+    const NAME: &'static str = "Augmented Lagrangian";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        let problem = op.clone_op();
+        let h = problem.eq_constraints(&param)?;
+        let g = problem.ineq_constraints(&param)?;
+        if self.lambda_eq.is_empty() {
+            self.lambda_eq = vec![0.0; h.len()];
+        }
+        if self.lambda_ineq.is_empty() {
+            self.lambda_ineq = vec![0.0; g.len()];
+        }
+        check_rows("AugmentedLagrangian", &h, &self.lambda_eq)?;
+        check_rows("AugmentedLagrangian", &g, &self.lambda_ineq)?;
+        self.prev_violation = f64::INFINITY;
+        let violation = infeasibility(&h, &g);
+        let cost = if violation <= self.tol {
+            op.apply(&param)?
+        } else {
+            f64::INFINITY
+        };
+        Ok(Some(
+            ArgminIterData::new()
+                .param(param)
+                .cost(cost)
+                .kv(make_kv!("mu" => self.mu; "violation" => violation;)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+
+        // create operator for the subproblem
+        let al_op = OpWrapper::new_move(AugmentedLagrangianOp {
+            op: op.clone_op(),
+            lambda_eq: self.lambda_eq.clone(),
+            lambda_ineq: self.lambda_ineq.clone(),
+            mu: self.mu,
+            hessian: std::marker::PhantomData,
+        });
+
+        // solve subproblem
+        let ArgminResult {
+            operator: al_op,
+            state:
+                IterState {
+                    param: next_param,
+                    iter: inner_iters,
+                    ..
+                },
+        } = Executor::new(al_op, self.inner.clone(), param)
+            .max_iters(self.max_inner_iters)
+            .ctrlc(false)
+            .run()?;
+
+        op.consume_op(al_op);
+
+        let problem = op.clone_op();
+        let h = problem.eq_constraints(&next_param)?;
+        let g = problem.ineq_constraints(&next_param)?;
+        let violation = self.violation(&h, &g);
+        let infeas = infeasibility(&h, &g);
+
+        // first order multiplier update
+        for (lambda, hi) in self.lambda_eq.iter_mut().zip(h.iter()) {
+            *lambda += self.mu * hi;
+        }
+        for (nu, gj) in self.lambda_ineq.iter_mut().zip(g.iter()) {
+            *nu = (*nu + self.mu * gj).max(0.0);
+        }
+
+        // gradient of the Lagrangian with the updated multipliers
+        let mut grad = op.gradient(&next_param)?;
+        let jh = problem.eq_jacobian(&next_param)?;
+        check_rows("AugmentedLagrangian", &h, &jh)?;
+        for (lambda, row) in self.lambda_eq.iter().zip(jh.iter()) {
+            grad = grad.scaled_add(lambda, row);
+        }
+        let jg = problem.ineq_jacobian(&next_param)?;
+        check_rows("AugmentedLagrangian", &g, &jg)?;
+        for (nu, row) in self.lambda_ineq.iter().zip(jg.iter()) {
+            grad = grad.scaled_add(nu, row);
+        }
+
+        let cost = if infeas <= self.tol {
+            op.apply(&next_param)?
+        } else {
+            f64::INFINITY
+        };
+
+        let kv = make_kv!(
+            "mu" => self.mu;
+            "violation" => infeas;
+            "kkt_violation" => violation;
+            "lambda_eq" => self.lambda_eq;
+            "lambda_ineq" => self.lambda_ineq;
+            "inner_iters" => inner_iters;
+        );
+        let out = ArgminIterData::new()
+            .param(next_param)
+            .cost(cost)
+            .grad(grad.clone())
+            .kv(kv);
+
+        if violation <= self.tol && grad.norm() <= self.gtol {
+            return Ok(out.termination_reason(TerminationReason::TargetPrecisionReached));
+        }
+
+        // increase penalty if the violation did not decrease sufficiently
+        if violation > self.reduction * self.prev_violation {
+            self.mu *= self.mu_factor;
+        }
+        self.prev_violation = violation;
+
+        Ok(out)
+    }
+}
+
+/// Augmented Lagrangian of a constrained problem for fixed multipliers and penalty parameter.
+/// This is the operator passed to the inner solver of `AugmentedLagrangian`.
+#[doc(hidden)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+pub struct AugmentedLagrangianOp<O, H = WithoutHessian> {
+    /// constrained problem
+    op: O,
+    /// multipliers of the equality constraints
+    lambda_eq: Vec<f64>,
+    /// multipliers of the inequality constraints
+    lambda_ineq: Vec<f64>,
+    /// penalty parameter
+    mu: f64,
+    /// availability of the Hessian
+    hessian: std::marker::PhantomData<H>,
+}
+
+impl<O, H> AugmentedLagrangianOp<O, H> {
+    /// Weights `lambda_i + mu h_i` of the gradients of the equality constraints
+    fn eq_weights(&self, h: &[f64]) -> Vec<f64> {
+        self.lambda_eq
+            .iter()
+            .zip(h.iter())
+            .map(|(lambda, hi)| lambda + self.mu * hi)
+            .collect()
+    }
+
+    /// Weights `max(0, nu_j + mu g_j)` of the gradients of the inequality constraints
+    fn ineq_weights(&self, g: &[f64]) -> Vec<f64> {
+        self.lambda_ineq
+            .iter()
+            .zip(g.iter())
+            .map(|(nu, gj)| (nu + self.mu * gj).max(0.0))
+            .collect()
+    }
+}
+
+impl<O, H> ArgminOp for AugmentedLagrangianOp<O, H>
+where
+    O: ArgminConstraints<Output = f64>,
+    O::Param: ArgminScaledAdd<O::Param, f64, O::Param>,
+    H: LagrangianHessian<O>,
+{
+    type Param = O::Param;
+    type Output = f64;
+    type Hessian = O::Hessian;
+    type Jacobian = O::Jacobian;
+
+    fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        let h = self.op.eq_constraints(p)?;
+        let g = self.op.ineq_constraints(p)?;
+        check_rows("AugmentedLagrangianOp", &h, &self.lambda_eq)?;
+        check_rows("AugmentedLagrangianOp", &g, &self.lambda_ineq)?;
+        let eq: f64 = self
+            .lambda_eq
+            .iter()
+            .zip(h.iter())
+            .map(|(lambda, hi)| lambda * hi + 0.5 * self.mu * hi.powi(2))
+            .sum();
+        let ineq: f64 = self
+            .lambda_ineq
+            .iter()
+            .zip(self.ineq_weights(&g).iter())
+            .map(|(nu, w)| (w.powi(2) - nu.powi(2)) / (2.0 * self.mu))
+            .sum();
+        Ok(self.op.apply(p)? + eq + ineq)
+    }
+
+    fn gradient(&self, p: &Self::Param) -> Result<Self::Param, Error> {
+        let h = self.op.eq_constraints(p)?;
+        let g = self.op.ineq_constraints(p)?;
+        let jh = self.op.eq_jacobian(p)?;
+        let jg = self.op.ineq_jacobian(p)?;
+        check_rows("AugmentedLagrangianOp", &h, &jh)?;
+        check_rows("AugmentedLagrangianOp", &g, &jg)?;
+        let mut grad = self.op.gradient(p)?;
+        for (w, row) in self.eq_weights(&h).iter().zip(jh.iter()) {
+            grad = grad.scaled_add(w, row);
+        }
+        for (w, row) in self.ineq_weights(&g).iter().zip(jg.iter()) {
+            grad = grad.scaled_add(w, row);
+        }
+        Ok(grad)
+    }
+
+    fn hessian(&self, p: &Self::Param) -> Result<Self::Hessian, Error> {
+        H::hessian(self, p)
+    }
+
+    fn jacobian(&self, p: &Self::Param) -> Result<Self::Jacobian, Error> {
+        self.op.jacobian(p)
+    }
+}
+
+/// Products with the Hessian of the augmented Lagrangian are approximated by finite differences of
+/// the gradient, therefore matrix-free inner solvers do not require `with_hessian()`.
+impl<O, H> ArgminHessianVecProduct for AugmentedLagrangianOp<O, H> where
+    AugmentedLagrangianOp<O, H>: ArgminOp
+{
+}
+
+/// Hessian of the augmented Lagrangian is not available (default of `AugmentedLagrangian`)
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct WithoutHessian;
+
+/// Hessian of the augmented Lagrangian is assembled from the Hessians of the cost function and the
+/// constraints (see `AugmentedLagrangian::with_hessian`)
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct WithHessian;
+
+/// Computation of the Hessian of the augmented Lagrangian (`WithoutHessian` or `WithHessian`)
+pub trait LagrangianHessian<O: ArgminConstraints>:
+    Clone + Default + Send + Sync + SerializeAlias + DeserializeOwnedAlias
+{
+    /// Hessian of the augmented Lagrangian `op` at `p`
+    fn hessian(op: &AugmentedLagrangianOp<O, Self>, p: &O::Param) -> Result<O::Hessian, Error>;
+}
+
+impl<O: ArgminConstraints> LagrangianHessian<O> for WithoutHessian {
+    fn hessian(_op: &AugmentedLagrangianOp<O, Self>, _p: &O::Param) -> Result<O::Hessian, Error> {
+        Err(ArgminError::NotImplemented {
+            text: "AugmentedLagrangian: the Hessian requires `with_hessian()`.".to_string(),
+        }
+        .into())
+    }
+}
+
+impl<O> LagrangianHessian<O> for WithHessian
+where
+    O: ArgminConstraints,
+    O::Param: ArgminDot<O::Param, O::Hessian>,
+    O::Hessian: ArgminAdd<O::Hessian, O::Hessian> + ArgminMul<f64, O::Hessian>,
+{
+    fn hessian(op: &AugmentedLagrangianOp<O, Self>, p: &O::Param) -> Result<O::Hessian, Error> {
+        let h = op.op.eq_constraints(p)?;
+        let g = op.op.ineq_constraints(p)?;
+        let mut hessian = op.op.hessian(p)?;
+        if !h.is_empty() {
+            let jh = op.op.eq_jacobian(p)?;
+            let hh = op.op.eq_hessians(p)?;
+            check_rows("AugmentedLagrangianOp", &h, &jh)?;
+            check_rows("AugmentedLagrangianOp", &h, &hh)?;
+            for ((w, row), hi) in op.eq_weights(&h).iter().zip(jh.iter()).zip(hh.iter()) {
+                hessian = hessian.add(&hi.mul(w)).add(&row.dot(row).mul(&op.mu));
+            }
+        }
+        let active = op.ineq_weights(&g);
+        if active.iter().any(|&w| w > 0.0) {
+            let jg = op.op.ineq_jacobian(p)?;
+            let hg = op.op.ineq_hessians(p)?;
+            check_rows("AugmentedLagrangianOp", &g, &jg)?;
+            check_rows("AugmentedLagrangianOp", &g, &hg)?;
+            for ((w, row), hj) in active.iter().zip(jg.iter()).zip(hg.iter()) {
+                if *w > 0.0 {
+                    hessian = hessian.add(&hj.mul(w)).add(&row.dot(row).mul(&op.mu));
+                }
+            }
+        }
+        Ok(hessian)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::newton::NewtonCG;
+    use crate::solver::quasinewton::LBFGS;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(
+        augmented_lagrangian,
+        AugmentedLagrangian<LBFGS<MoreThuenteLineSearch<Vec<f64>>, Vec<f64>>>
+    );
+
+    /// min x^2 + y^2  s.t.  x + y = 1, x >= 0.8 (without Hessians)
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Problem {}
+
+    impl ArgminOp for Problem {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(p[0].powi(2) + p[1].powi(2))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![2.0 * p[0], 2.0 * p[1]])
+        }
+    }
+
+    impl ArgminConstraints for Problem {
+        fn eq_constraints(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![p[0] + p[1] - 1.0])
+        }
+
+        fn eq_jacobian(&self, _p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![1.0, 1.0]])
+        }
+
+        fn ineq_constraints(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![0.8 - p[0]])
+        }
+
+        fn ineq_jacobian(&self, _p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![-1.0, 0.0]])
+        }
+    }
+
+    #[test]
+    fn test_augmented_lagrangian() {
+        let linesearch = MoreThuenteLineSearch::new();
+        let solver = AugmentedLagrangian::new(LBFGS::new(linesearch, 5));
+        let res = Executor::new(Problem {}, solver, vec![0.0, 0.0])
+            .max_iters(50)
+            .run()
+            .unwrap();
+        let x = res.state.get_best_param();
+        assert!((x[0] - 0.8).abs() < 1e-5);
+        assert!((x[1] - 0.2).abs() < 1e-5);
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+    }
+
+    /// `Problem` with Hessians
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct ProblemWithHessian {}
+
+    impl ArgminOp for ProblemWithHessian {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Problem {}.apply(p)
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Problem {}.gradient(p)
+        }
+
+        fn hessian(&self, _p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![2.0, 0.0], vec![0.0, 2.0]])
+        }
+    }
+
+    impl ArgminConstraints for ProblemWithHessian {
+        fn eq_constraints(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Problem {}.eq_constraints(p)
+        }
+
+        fn eq_jacobian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Problem {}.eq_jacobian(p)
+        }
+
+        fn eq_hessians(&self, _p: &Vec<f64>) -> Result<Vec<Vec<Vec<f64>>>, Error> {
+            Ok(vec![vec![vec![0.0; 2]; 2]])
+        }
+
+        fn ineq_constraints(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Problem {}.ineq_constraints(p)
+        }
+
+        fn ineq_jacobian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Problem {}.ineq_jacobian(p)
+        }
+
+        fn ineq_hessians(&self, _p: &Vec<f64>) -> Result<Vec<Vec<Vec<f64>>>, Error> {
+            Ok(vec![vec![vec![0.0; 2]; 2]])
+        }
+    }
+
+    #[test]
+    fn test_with_hessian() {
+        let linesearch = MoreThuenteLineSearch::new();
+        let solver = AugmentedLagrangian::new(NewtonCG::new(linesearch)).with_hessian();
+        let res = Executor::new(ProblemWithHessian {}, solver, vec![0.0, 0.0])
+            .max_iters(50)
+            .run()
+            .unwrap();
+        let x = res.state.get_best_param();
+        assert!((x[0] - 0.8).abs() < 1e-5);
+        assert!((x[1] - 0.2).abs() < 1e-5);
+
+        // the inner solver has no access to the Hessian by default
+        let al_op: AugmentedLagrangianOp<ProblemWithHessian> = AugmentedLagrangianOp {
+            op: ProblemWithHessian {},
+            lambda_eq: vec![0.0],
+            lambda_ineq: vec![0.0],
+            mu: 1.0,
+            hessian: std::marker::PhantomData,
+        };
+        assert!(al_op.hessian(&vec![0.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_multipliers() {
+        let solver = AugmentedLagrangian::new(());
+        assert!(solver.clone().multipliers(vec![-1.0], vec![1.0]).is_ok());
+        assert!(solver.multipliers(vec![1.0], vec![-1.0]).is_err());
+    }
+}
@@ -0,0 +1,348 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Bracketing of one dimensional problems
+//!
+//! Helpers which find intervals containing a minimum or a root of a scalar function. They are used
+//! by the one dimensional solvers if no (suitable) interval is provided.
+//!
+//! # References:
+//!
+//! [0] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+//! Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+//! ISBN 0-521-88068-8.
+
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Golden ratio
+const GOLD: f64 = 1.618_033_988_749_895;
+
+/// Maximum magnification of a parabolic-fit step
+const GLIMIT: f64 = 100.0;
+
+/// Maximum number of function evaluations used for bracketing
+const MAX_EVALS: u64 = 1000;
+
+/// Three points `a`, `b` and `c` with `b` between `a` and `c` and `f(b) <= min(f(a), f(c))`,
+/// therefore enclosing a (local) minimum.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinimumBracket {
+    /// first outer point
+    pub a: f64,
+    /// inner point
+    pub b: f64,
+    /// second outer point
+    pub c: f64,
+    /// function value at `a`
+    pub fa: f64,
+    /// function value at `b`
+    pub fb: f64,
+    /// function value at `c`
+    pub fc: f64,
+}
+
+/// Bracket a minimum of `op` starting from `x0` (mnbrak).
+///
+/// The first trial step is `step`; subsequent steps are enlarged by the golden ratio or by
+/// parabolic extrapolation until the function increases again.
+pub fn bracket_minimum<O>(
+    op: &mut OpWrapper<O>,
+    x0: f64,
+    step: f64,
+) -> Result<MinimumBracket, Error>
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    if step == 0.0 || !step.is_finite() {
+        return Err(ArgminError::InvalidParameter {
+            text: "bracket_minimum: step must be finite and non-zero.".to_string(),
+        }
+        .into());
+    }
+    let mut a = x0;
+    let mut b = x0 + step;
+    let mut fa = op.apply(&a)?;
+    let mut fb = op.apply(&b)?;
+    // go downhill from a to b
+    if fb > fa {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut fa, &mut fb);
+    }
+    let mut c = b + GOLD * (b - a);
+    let mut fc = op.apply(&c)?;
+    let mut evals = 3;
+    while fb > fc {
+        if evals >= MAX_EVALS || !c.is_finite() {
+            return Err(ArgminError::ConditionViolated {
+                text: "bracket_minimum: unable to find a bracket; the function may be unbounded."
+                    .to_string(),
+            }
+            .into());
+        }
+        // parabolic extrapolation from a, b and c
+        let r = (b - a) * (fb - fc);
+        let q = (b - c) * (fb - fa);
+        let denom = 2.0 * (q - r).abs().max(std::f64::EPSILON).copysign(q - r);
+        let mut u = b - ((b - c) * q - (b - a) * r) / denom;
+        let ulim = b + GLIMIT * (c - b);
+        let mut fu;
+        if (b - u) * (u - c) > 0.0 {
+            // u between b and c
+            fu = op.apply(&u)?;
+            evals += 1;
+            if fu < fc {
+                return Ok(MinimumBracket::ordered(b, u, c, fb, fu, fc));
+            } else if fu > fb {
+                return Ok(MinimumBracket::ordered(a, b, u, fa, fb, fu));
+            }
+            u = c + GOLD * (c - b);
+            fu = op.apply(&u)?;
+            evals += 1;
+        } else if (c - u) * (u - ulim) > 0.0 {
+            // u between c and its allowed limit
+            fu = op.apply(&u)?;
+            evals += 1;
+            if fu < fc {
+                b = c;
+                c = u;
+                u = c + GOLD * (c - b);
+                fb = fc;
+                fc = fu;
+                fu = op.apply(&u)?;
+                evals += 1;
+            }
+        } else if (u - ulim) * (ulim - c) >= 0.0 {
+            // limit u to its maximum allowed value
+            u = ulim;
+            fu = op.apply(&u)?;
+            evals += 1;
+        } else {
+            // reject parabolic u, use default magnification
+            u = c + GOLD * (c - b);
+            fu = op.apply(&u)?;
+            evals += 1;
+        }
+        a = b;
+        b = c;
+        c = u;
+        fa = fb;
+        fb = fc;
+        fc = fu;
+    }
+    Ok(MinimumBracket::ordered(a, b, c, fa, fb, fc))
+}
+
+impl MinimumBracket {
+    /// Create a bracket with `a < c`
+    fn ordered(a: f64, b: f64, c: f64, fa: f64, fb: f64, fc: f64) -> Self {
+        if a < c {
+            MinimumBracket {
+                a,
+                b,
+                c,
+                fa,
+                fb,
+                fc,
+            }
+        } else {
+            MinimumBracket {
+                a: c,
+                b,
+                c: a,
+                fa: fc,
+                fb,
+                fc: fa,
+            }
+        }
+    }
+}
+
+/// Error returned if no sign change could be found while expanding a root bracket.
+#[derive(Debug)]
+pub struct RootBracketError {
+    /// lower end of the last interval tried
+    pub a: f64,
+    /// upper end of the last interval tried
+    pub b: f64,
+    /// all intervals tried, starting with the initial one
+    pub history: Vec<(f64, f64)>,
+}
+
+impl fmt::Display for RootBracketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Root bracket error: f(a) and f(b) have the same sign for [{}, {}] (after {} \
+             expansions).",
+            self.a,
+            self.b,
+            self.history.len().saturating_sub(1)
+        )
+    }
+}
+
+impl std::error::Error for RootBracketError {
+    fn description(&self) -> &str {
+        "Unable to find an interval bracketing a root of the function."
+    }
+}
+
+/// Interval `[a, b]` with `f(a)` and `f(b)` of opposite sign (or zero), therefore enclosing a root.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RootBracket {
+    /// lower end of the interval
+    pub a: f64,
+    /// upper end of the interval
+    pub b: f64,
+    /// function value at `a`
+    pub fa: f64,
+    /// function value at `b`
+    pub fb: f64,
+    /// all intervals tried, starting with the initial one
+    pub history: Vec<(f64, f64)>,
+}
+
+/// Expand `[a, b]` until it brackets a root of `op` (zbrac).
+///
+/// In each step the end with the smaller absolute function value is moved outwards by `factor`
+/// times the current width of the interval. If no sign change is found after `max_expansions`
+/// steps, a `RootBracketError` is returned.
+pub fn expand_root_bracket<O>(
+    op: &mut OpWrapper<O>,
+    a: f64,
+    b: f64,
+    factor: f64,
+    max_expansions: u64,
+) -> Result<RootBracket, Error>
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    if a >= b || factor <= 0.0 {
+        return Err(ArgminError::InvalidParameter {
+            text: "expand_root_bracket: a must be smaller than b and factor must be > 0."
+                .to_string(),
+        }
+        .into());
+    }
+    let (mut a, mut b) = (a, b);
+    let mut fa = op.apply(&a)?;
+    let mut fb = op.apply(&b)?;
+    let mut history = vec![(a, b)];
+    for _ in 0..max_expansions {
+        if fa * fb <= 0.0 {
+            break;
+        }
+        if fa.abs() < fb.abs() {
+            a += factor * (a - b);
+            fa = op.apply(&a)?;
+        } else {
+            b += factor * (b - a);
+            fb = op.apply(&b)?;
+        }
+        history.push((a, b));
+    }
+    if fa * fb > 0.0 || fa.is_nan() || fb.is_nan() {
+        return Err(RootBracketError { a, b, history }.into());
+    }
+    Ok(RootBracket {
+        a,
+        b,
+        fa,
+        fb,
+        history,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Parabola {}
+
+    impl ArgminOp for Parabola {
+        type Param = f64;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &f64) -> Result<f64, Error> {
+            Ok((p - 10.0).powi(2))
+        }
+    }
+
+    #[test]
+    fn test_bracket_minimum() {
+        for &(x0, step) in &[(0.0, 1.0), (0.0, -1.0), (30.0, 0.1), (9.9, 1.0)] {
+            let mut op = OpWrapper::new(&Parabola {});
+            let br = bracket_minimum(&mut op, x0, step).unwrap();
+            assert!(br.a < br.b && br.b < br.c);
+            assert!(br.a <= 10.0 && 10.0 <= br.c);
+            assert!(br.fb <= br.fa && br.fb <= br.fc);
+        }
+    }
+
+    #[test]
+    fn test_bracket_minimum_unbounded() {
+        #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+        #[derive(Clone, Default)]
+        struct Linear {}
+
+        impl ArgminOp for Linear {
+            type Param = f64;
+            type Output = f64;
+            type Hessian = ();
+            type Jacobian = ();
+
+            fn apply(&self, p: &f64) -> Result<f64, Error> {
+                Ok(-p)
+            }
+        }
+
+        let mut op = OpWrapper::new(&Linear {});
+        assert!(bracket_minimum(&mut op, 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_expand_root_bracket() {
+        #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+        #[derive(Clone, Default)]
+        struct Shifted {}
+
+        impl ArgminOp for Shifted {
+            type Param = f64;
+            type Output = f64;
+            type Hessian = ();
+            type Jacobian = ();
+
+            fn apply(&self, p: &f64) -> Result<f64, Error> {
+                Ok(p - 10.0)
+            }
+        }
+
+        // the root at 10 lies outside of the initial interval
+        let mut shifted = OpWrapper::new(&Shifted {});
+        let br = expand_root_bracket(&mut shifted, 0.0, 1.0, 1.6, 50).unwrap();
+        assert!(br.fa * br.fb <= 0.0);
+        assert!(br.history.len() > 1);
+        assert_eq!(br.history[0], (0.0, 1.0));
+
+        // (x - 10)^2 has no sign change
+        let mut op = OpWrapper::new(&Parabola {});
+        let err = expand_root_bracket(&mut op, 0.0, 1.0, 1.6, 50).unwrap_err();
+        let err = err.downcast_ref::<RootBracketError>().unwrap();
+        assert_eq!(err.history.len(), 51);
+        assert_eq!(err.history[0], (0.0, 1.0));
+        assert_eq!(err.history[50], (err.a, err.b));
+    }
+}
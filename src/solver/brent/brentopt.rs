@@ -0,0 +1,344 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Richard P. Brent (1973). Algorithms for Minimization without Derivatives.
+//! Prentice-Hall. ISBN 0-13-022335-2.
+//!
+//! [1] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+//! Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+//! ISBN 0-521-88068-8.
+
+use crate::prelude::*;
+use crate::solver::bracketing::bracket_minimum;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// (3 - sqrt(5)) / 2
+const CGOLD: f64 = 0.381_966_011_250_105;
+
+/// Absolute tolerance used to avoid a vanishing tolerance at zero
+const ZEPS: f64 = 1e-10;
+
+/// Brent's method for minimization
+///
+/// Finds a minimum of a scalar function by combining parabolic interpolation with golden section
+/// steps. If no interval is given via `bracket`, a bracket is searched for starting from the
+/// initial parameter (see `bracketing::bracket_minimum`).
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/brentopt.rs)
+///
+/// # References:
+///
+/// [0] Richard P. Brent (1973). Algorithms for Minimization without Derivatives.
+/// Prentice-Hall. ISBN 0-13-022335-2.
+///
+/// [1] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+/// Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+/// ISBN 0-521-88068-8.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct BrentOpt {
+    /// required relative accuracy
+    tol: f64,
+    /// user provided interval
+    bracket: Option<(f64, f64)>,
+    /// initial step of the automatic bracketing
+    step: f64,
+    /// left boundary of current interval
+    a: f64,
+    /// right boundary of current interval
+    b: f64,
+    /// point with the lowest function value so far
+    x: f64,
+    /// point with the second lowest function value
+    w: f64,
+    /// previous value of `w`
+    v: f64,
+    /// function value at `x`
+    fx: f64,
+    /// function value at `w`
+    fw: f64,
+    /// function value at `v`
+    fv: f64,
+    /// current step
+    d: f64,
+    /// step before the last one
+    e: f64,
+}
+
+impl BrentOpt {
+    /// Constructor
+    /// The parameter `tol` specifies the relative error to be targeted.
+    pub fn new(tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "BrentOpt: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(BrentOpt {
+            tol,
+            bracket: None,
+            step: 1.0,
+            a: f64::NAN,
+            b: f64::NAN,
+            x: f64::NAN,
+            w: f64::NAN,
+            v: f64::NAN,
+            fx: f64::NAN,
+            fw: f64::NAN,
+            fv: f64::NAN,
+            d: 0.0,
+            e: 0.0,
+        })
+    }
+
+    /// Set interval `[min, max]` which contains the minimum. This disables automatic bracketing.
+    pub fn bracket(mut self, min: f64, max: f64) -> Result<Self, Error> {
+        if min >= max {
+            return Err(ArgminError::InvalidParameter {
+                text: "BrentOpt: min must be smaller than max.".to_string(),
+            }
+            .into());
+        }
+        self.bracket = Some((min, max));
+        Ok(self)
+    }
+
+    /// Set initial step of the automatic bracketing (default: 1.0)
+    pub fn initial_step(mut self, step: f64) -> Result<Self, Error> {
+        if step == 0.0 || !step.is_finite() {
+            return Err(ArgminError::InvalidParameter {
+                text: "BrentOpt: initial step must be finite and non-zero.".to_string(),
+            }
+            .into());
+        }
+        self.step = step;
+        Ok(self)
+    }
+}
+
+impl<O> Solver<O> for BrentOpt
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    const NAME: &'static str = "Brent minimizer";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let (a, b, x, fx) = match self.bracket {
+            Some((min, max)) => {
+                let x = min + CGOLD * (max - min);
+                (min, max, x, op.apply(&x)?)
+            }
+            None => {
+                let br = bracket_minimum(op, state.get_param(), self.step)?;
+                (br.a, br.c, br.b, br.fb)
+            }
+        };
+        self.a = a;
+        self.b = b;
+        self.x = x;
+        self.w = x;
+        self.v = x;
+        self.fx = fx;
+        self.fw = fx;
+        self.fv = fx;
+        self.d = 0.0;
+        self.e = 0.0;
+        Ok(Some(
+            ArgminIterData::new()
+                .param(self.x)
+                .cost(self.fx)
+                .kv(make_kv!("a" => self.a; "b" => self.b;)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // BrentOpt maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let xm = 0.5 * (self.a + self.b);
+        let tol1 = self.tol * self.x.abs() + ZEPS;
+        let tol2 = 2.0 * tol1;
+        if (self.x - xm).abs() <= tol2 - 0.5 * (self.b - self.a) {
+            return Ok(ArgminIterData::new()
+                .termination_reason(TerminationReason::TargetPrecisionReached)
+                .param(self.x)
+                .cost(self.fx));
+        }
+
+        let golden_step = |x: f64, a: f64, b: f64| if x >= xm { a - x } else { b - x };
+        if self.e.abs() > tol1 {
+            // try a parabolic fit through x, v and w
+            let r = (self.x - self.w) * (self.fx - self.fv);
+            let q = (self.x - self.v) * (self.fx - self.fw);
+            let mut p = (self.x - self.v) * q - (self.x - self.w) * r;
+            let mut q = 2.0 * (q - r);
+            if q > 0.0 {
+                p = -p;
+            }
+            q = q.abs();
+            let etemp = self.e;
+            self.e = self.d;
+            if p.abs() >= (0.5 * q * etemp).abs()
+                || p <= q * (self.a - self.x)
+                || p >= q * (self.b - self.x)
+            {
+                // parabolic step not acceptable, take golden section step
+                self.e = golden_step(self.x, self.a, self.b);
+                self.d = CGOLD * self.e;
+            } else {
+                self.d = p / q;
+                let u = self.x + self.d;
+                if u - self.a < tol2 || self.b - u < tol2 {
+                    self.d = tol1.copysign(xm - self.x);
+                }
+            }
+        } else {
+            self.e = golden_step(self.x, self.a, self.b);
+            self.d = CGOLD * self.e;
+        }
+
+        let u = if self.d.abs() >= tol1 {
+            self.x + self.d
+        } else {
+            self.x + tol1.copysign(self.d)
+        };
+        let fu = op.apply(&u)?;
+
+        if fu <= self.fx {
+            if u >= self.x {
+                self.a = self.x;
+            } else {
+                self.b = self.x;
+            }
+            self.v = self.w;
+            self.w = self.x;
+            self.x = u;
+            self.fv = self.fw;
+            self.fw = self.fx;
+            self.fx = fu;
+        } else {
+            if u < self.x {
+                self.a = u;
+            } else {
+                self.b = u;
+            }
+            if fu <= self.fw || (self.w - self.x).abs() < f64::EPSILON {
+                self.v = self.w;
+                self.w = u;
+                self.fv = self.fw;
+                self.fw = fu;
+            } else if fu <= self.fv
+                || (self.v - self.x).abs() < f64::EPSILON
+                || (self.v - self.w).abs() < f64::EPSILON
+            {
+                self.v = u;
+                self.fv = fu;
+            }
+        }
+
+        Ok(ArgminIterData::new()
+            .param(self.x)
+            .cost(self.fx)
+            .kv(make_kv!("a" => self.a; "b" => self.b;)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::goldensectionsearch::GoldenSectionSearch;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(brentopt, BrentOpt);
+
+    /// `f(x) = -sin(x)`, minimum at `pi / 2`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct NegSin {}
+
+    impl ArgminOp for NegSin {
+        type Param = f64;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(-p.sin())
+        }
+    }
+
+    /// Runs the solver and returns the minimum, the number of iterations and the number of
+    /// function evaluations
+    fn run<S: Solver<NegSin>>(solver: S, init_param: f64) -> (f64, u64, u64) {
+        let res = Executor::new(NegSin {}, solver, init_param)
+            .max_iters(1000)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+        assert!((res.state.get_best_cost() + 1.0).abs() < 1e-14);
+        (
+            res.state.get_best_param(),
+            res.state.get_iter(),
+            res.state.get_cost_func_count(),
+        )
+    }
+
+    #[test]
+    fn test_bracket() {
+        assert!(BrentOpt::new(1e-8).unwrap().bracket(1.0, 0.0).is_err());
+        assert!(BrentOpt::new(1e-8).unwrap().bracket(0.0, 1.0).is_ok());
+    }
+
+    #[test]
+    fn test_tol() {
+        assert!(BrentOpt::new(0.0).is_err());
+        assert!(BrentOpt::new(-1e-8).is_err());
+    }
+
+    #[test]
+    fn test_minimum() {
+        let brent = BrentOpt::new(1e-8).unwrap();
+        let (x, _, _) = run(brent.clone().bracket(0.0, 3.0).unwrap(), 0.0);
+        assert!((x - f64::consts::FRAC_PI_2).abs() < 1e-7);
+        // the minimum is bracketed automatically starting from the initial parameter
+        let (x, _, _) = run(brent, 0.0);
+        assert!((x - f64::consts::FRAC_PI_2).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_brent_vs_golden_section() {
+        // parabolic interpolation needs fewer iterations and evaluations than golden sections
+        for bracket in &[Some((0.0, 3.0)), None] {
+            let brent = BrentOpt::new(1e-8).unwrap();
+            let golden = GoldenSectionSearch::new(1e-8).unwrap();
+            let (brent, golden) = match *bracket {
+                Some((min, max)) => (
+                    run(brent.bracket(min, max).unwrap(), 0.0),
+                    run(golden.bracket(min, max).unwrap(), 0.0),
+                ),
+                None => (run(brent, 0.0), run(golden, 0.0)),
+            };
+            assert!((brent.0 - golden.0).abs() < 1e-7);
+            assert!(2 * brent.1 < golden.1);
+            assert!(2 * brent.2 < golden.2);
+        }
+    }
+}
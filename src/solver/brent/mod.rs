@@ -11,38 +11,30 @@
 //! and inverse quadratic interpolation. It has the reliability of bisection
 //! but it can be as quick as some of the less-reliable methods.
 //!
+//! The module also contains Brent's method for minimization (`BrentOpt`).
+//!
 //! # References:
 //!
 //! https://en.wikipedia.org/wiki/Brent%27s_method
 //!
 
+/// Brent's method for minimization
+pub mod brentopt;
+
+pub use self::brentopt::*;
+
 /// Implementation of Brent's optimization method,
 /// see https://en.wikipedia.org/wiki/Brent%27s_method
 use crate::prelude::*;
+use crate::solver::bracketing::RootBracketError;
 #[cfg(feature = "serde1")]
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 use std::f64;
-use std::fmt;
-
-/// Error to be thrown if Brent is initialized with improper parameters.
-#[derive(Debug)]
-pub struct BrentError;
-
-impl fmt::Display for BrentError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Brent error: f(min) and f(max) must have different signs."
-        )
-    }
-}
 
-impl std::error::Error for BrentError {
-    fn description(&self) -> &str {
-        "Brent requires a min and max value bracketing the root of the function."
-    }
-}
+/// Error returned if `min` and `max` do not bracket a root of the function
+#[deprecated(note = "Brent returns a `RootBracketError`")]
+pub type BrentError = RootBracketError;
 
 /// Brent's method
 ///
@@ -109,7 +101,12 @@ where
         self.fa = op.apply(&self.a)?;
         self.fb = op.apply(&self.b)?;
         if self.fa * self.fb > 0.0 {
-            return Err(Error::from_boxed_compat(Box::new(BrentError)));
+            return Err(RootBracketError {
+                a: self.a,
+                b: self.b,
+                history: vec![(self.a, self.b)],
+            }
+            .into());
         }
         self.fc = self.fb;
         Ok(Some(
@@ -198,4 +195,30 @@ mod tests {
     use crate::test_trait_impl;
 
     test_trait_impl!(brent, Brent);
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Square {}
+
+    impl ArgminOp for Square {
+        type Param = f64;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &f64) -> Result<f64, Error> {
+            Ok(p.powi(2) - 2.0)
+        }
+    }
+
+    #[test]
+    fn test_invalid_bracket() {
+        let err = Executor::new(Square {}, Brent::new(2.0, 3.0, 1e-10), 0.0)
+            .run()
+            .err()
+            .unwrap();
+        let err = err.downcast_ref::<RootBracketError>().unwrap();
+        assert_eq!((err.a, err.b), (2.0, 3.0));
+        assert_eq!(err.history, vec![(2.0, 3.0)]);
+    }
 }
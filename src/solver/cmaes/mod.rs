@@ -0,0 +1,652 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Nikolaus Hansen (2016). The CMA Evolution Strategy: A Tutorial. arXiv:1604.00772
+//!
+//! [1] Anne Auger and Nikolaus Hansen (2005). A Restart CMA Evolution Strategy With Increasing
+//! Population Size. IEEE Congress on Evolutionary Computation.
+//!
+//! [2] Nikolaus Hansen (2009). Benchmarking a BI-Population CMA-ES on the BBOB-2009 Function
+//! Testbed. GECCO.
+
+use crate::linalg::symmetric_eigen;
+use crate::prelude::*;
+use rand::prelude::*;
+use rand_xorshift::XorShiftRng;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Restart strategy of CMA-ES
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CMAESRestart {
+    /// Do not restart
+    None,
+    /// Restart with a population size increased by `factor` (IPOP-CMA-ES)
+    IPOP {
+        /// maximum number of restarts
+        max_restarts: u64,
+        /// factor by which the population size is increased at each restart
+        factor: f64,
+    },
+    /// Alternate between restarts with large and small populations (BIPOP-CMA-ES)
+    BIPOP {
+        /// maximum number of restarts
+        max_restarts: u64,
+    },
+}
+
+/// Covariance Matrix Adaptation Evolution Strategy (CMA-ES)
+///
+/// Samples a population from a multivariate normal distribution whose mean, step size and
+/// covariance matrix are adapted after every generation. One iteration corresponds to one
+/// generation. The current population is reported via `ArgminIterData::population`, the parameter
+/// vector and cost are the best ones found so far.
+///
+/// A run (or restart) stops if the costs of the recent generations stagnate (`tol_fun`), if the
+/// search distribution collapses (`tol_x`) or if the covariance matrix becomes ill-conditioned
+/// (`TerminationReason::Aborted`). With `CMAESRestart::IPOP` or `CMAESRestart::BIPOP` the algorithm is then restarted from the
+/// initial parameter vector until the maximum number of restarts is exhausted.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/cmaes.rs)
+///
+/// # References:
+///
+/// [0] Nikolaus Hansen (2016). The CMA Evolution Strategy: A Tutorial. arXiv:1604.00772
+///
+/// [1] Anne Auger and Nikolaus Hansen (2005). A Restart CMA Evolution Strategy With Increasing
+/// Population Size. IEEE Congress on Evolutionary Computation.
+///
+/// [2] Nikolaus Hansen (2009). Benchmarking a BI-Population CMA-ES on the BBOB-2009 Function
+/// Testbed. GECCO.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct CMAES {
+    /// initial step size
+    sigma0: f64,
+    /// user defined population size
+    lambda0: Option<usize>,
+    /// restart strategy
+    restart: CMAESRestart,
+    /// tolerance on the range of recent costs
+    tol_fun: f64,
+    /// tolerance on the standard deviations (relative to sigma0)
+    tol_x: f64,
+    /// random number generator
+    rng: XorShiftRng,
+    /// initial mean
+    x0: Vec<f64>,
+    /// default population size
+    lambda_default: usize,
+    /// number of restarts so far
+    restarts: u64,
+    /// number of function evaluations spent in the large (0) and small (1) BIPOP regime
+    bipop_evals: [u64; 2],
+    /// population size of the last large BIPOP run
+    lambda_large: usize,
+    /// state of the current run
+    run: Option<CMAESRun>,
+    /// best parameter vector
+    best_param: Vec<f64>,
+    /// best cost
+    best_cost: f64,
+    /// termination reason once no more restarts are left
+    termination: TerminationReason,
+}
+
+/// State of a single CMA-ES run
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+struct CMAESRun {
+    lambda: usize,
+    mu: usize,
+    weights: Vec<f64>,
+    mueff: f64,
+    cc: f64,
+    cs: f64,
+    c1: f64,
+    cmu: f64,
+    damps: f64,
+    chi_n: f64,
+    sigma: f64,
+    mean: Vec<f64>,
+    pc: Vec<f64>,
+    ps: Vec<f64>,
+    c: Vec<Vec<f64>>,
+    b: Vec<Vec<f64>>,
+    d: Vec<f64>,
+    generation: u64,
+    history: Vec<f64>,
+    bipop_regime: usize,
+}
+
+impl CMAESRun {
+    fn new(mean: Vec<f64>, sigma: f64, lambda: usize, bipop_regime: usize) -> Self {
+        let n = mean.len();
+        let nf = n as f64;
+        let mu = lambda / 2;
+        let raw: Vec<f64> = (0..mu)
+            .map(|i| ((lambda as f64 + 1.0) / 2.0).ln() - ((i + 1) as f64).ln())
+            .collect();
+        let sum: f64 = raw.iter().sum();
+        let weights: Vec<f64> = raw.iter().map(|w| w / sum).collect();
+        let mueff = 1.0 / weights.iter().map(|w| w.powi(2)).sum::<f64>();
+        let cc = (4.0 + mueff / nf) / (nf + 4.0 + 2.0 * mueff / nf);
+        let cs = (mueff + 2.0) / (nf + mueff + 5.0);
+        let c1 = 2.0 / ((nf + 1.3).powi(2) + mueff);
+        let cmu = (1.0 - c1).min(2.0 * (mueff - 2.0 + 1.0 / mueff) / ((nf + 2.0).powi(2) + mueff));
+        let damps = 1.0 + 2.0 * (((mueff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chi_n = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf.powi(2)));
+        let eye: Vec<Vec<f64>> = (0..n)
+            .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+        CMAESRun {
+            lambda,
+            mu,
+            weights,
+            mueff,
+            cc,
+            cs,
+            c1,
+            cmu,
+            damps,
+            chi_n,
+            sigma,
+            mean,
+            pc: vec![0.0; n],
+            ps: vec![0.0; n],
+            c: eye.clone(),
+            b: eye,
+            d: vec![1.0; n],
+            generation: 0,
+            history: vec![],
+            bipop_regime,
+        }
+    }
+
+    /// B * D * z
+    fn transform(&self, z: &[f64]) -> Vec<f64> {
+        let dz: Vec<f64> = z
+            .iter()
+            .zip(self.d.iter())
+            .map(|(zi, di)| zi * di)
+            .collect();
+        self.b.iter().map(|row| dot(row, &dz)).collect()
+    }
+
+    /// C^(-1/2) * y = B * D^(-1) * B^T * y
+    fn inv_sqrt_c(&self, y: &[f64]) -> Vec<f64> {
+        let n = y.len();
+        let bty: Vec<f64> = (0..n)
+            .map(|j| (0..n).map(|i| self.b[i][j] * y[i]).sum::<f64>() / self.d[j])
+            .collect();
+        self.b.iter().map(|row| dot(row, &bty)).collect()
+    }
+}
+
+impl CMAES {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `sigma0`: initial step size
+    pub fn new(sigma0: f64) -> Result<Self, Error> {
+        if sigma0 <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "CMA-ES: sigma0 must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(CMAES {
+            sigma0,
+            lambda0: None,
+            restart: CMAESRestart::None,
+            tol_fun: 1e-12,
+            tol_x: 1e-12,
+            rng: XorShiftRng::from_entropy(),
+            x0: vec![],
+            lambda_default: 0,
+            restarts: 0,
+            bipop_evals: [0, 0],
+            lambda_large: 0,
+            run: None,
+            best_param: vec![],
+            best_cost: std::f64::INFINITY,
+            termination: TerminationReason::NotTerminated,
+        })
+    }
+
+    /// Set population size (default: `4 + floor(3 * ln(n))`)
+    pub fn population_size(mut self, lambda: usize) -> Result<Self, Error> {
+        if lambda < 2 {
+            return Err(ArgminError::InvalidParameter {
+                text: "CMA-ES: population size must be >= 2.".to_string(),
+            }
+            .into());
+        }
+        self.lambda0 = Some(lambda);
+        Ok(self)
+    }
+
+    /// Set restart strategy
+    pub fn restart(mut self, restart: CMAESRestart) -> Result<Self, Error> {
+        if let CMAESRestart::IPOP { factor, .. } = restart {
+            if factor < 1.0 {
+                return Err(ArgminError::InvalidParameter {
+                    text: "CMA-ES: IPOP factor must be >= 1.".to_string(),
+                }
+                .into());
+            }
+        }
+        self.restart = restart;
+        Ok(self)
+    }
+
+    /// Set tolerance on the range of the costs of recent generations
+    pub fn tol_fun(mut self, tol_fun: f64) -> Self {
+        self.tol_fun = tol_fun;
+        self
+    }
+
+    /// Set tolerance on the standard deviations relative to `sigma0`
+    pub fn tol_x(mut self, tol_x: f64) -> Self {
+        self.tol_x = tol_x;
+        self
+    }
+
+    /// Seed the random number generator
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Draw a standard normally distributed number (Box-Muller transform)
+    fn randn(&mut self) -> f64 {
+        let u1: f64 = 1.0 - self.rng.gen::<f64>();
+        let u2: f64 = self.rng.gen();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    /// Checks the stopping criteria of the current run
+    fn run_terminated(&self, run: &CMAESRun) -> Option<TerminationReason> {
+        let n = run.mean.len() as f64;
+        let window = 10 + (30.0 * n / run.lambda as f64).ceil() as usize;
+        if run.history.len() >= window {
+            let recent = &run.history[run.history.len() - window..];
+            let max = recent
+                .iter()
+                .cloned()
+                .fold(std::f64::NEG_INFINITY, f64::max);
+            let min = recent.iter().cloned().fold(std::f64::INFINITY, f64::min);
+            if max - min <= self.tol_fun {
+                return Some(TerminationReason::NoChangeInCost);
+            }
+        }
+        let small = run
+            .pc
+            .iter()
+            .zip(run.c.iter().enumerate())
+            .all(|(pci, (i, ci))| {
+                run.sigma * pci.abs().max(ci[i].sqrt()) < self.tol_x * self.sigma0
+            });
+        if small {
+            return Some(TerminationReason::TargetToleranceReached);
+        }
+        let dmax = run.d.iter().cloned().fold(0.0, f64::max);
+        let dmin = run.d.iter().cloned().fold(std::f64::INFINITY, f64::min);
+        if dmin <= 0.0 || (dmax / dmin).powi(2) > 1e14 {
+            // numerically ill-conditioned covariance matrix, the run cannot be continued
+            return Some(TerminationReason::Aborted);
+        }
+        None
+    }
+
+    /// Start a new run according to the restart strategy
+    fn restart_run(&mut self) {
+        self.restarts += 1;
+        let run = match self.restart {
+            CMAESRestart::None => unreachable!(),
+            CMAESRestart::IPOP { factor, .. } => {
+                let lambda = self.run.as_ref().unwrap().lambda;
+                let lambda = (lambda as f64 * factor).round() as usize;
+                CMAESRun::new(self.x0.clone(), self.sigma0, lambda, 0)
+            }
+            CMAESRestart::BIPOP { .. } => {
+                if self.bipop_evals[0] <= self.bipop_evals[1] {
+                    self.lambda_large *= 2;
+                    CMAESRun::new(self.x0.clone(), self.sigma0, self.lambda_large, 0)
+                } else {
+                    let u: f64 = self.rng.gen();
+                    let ratio = 0.5 * self.lambda_large as f64 / self.lambda_default as f64;
+                    let lambda = ((self.lambda_default as f64 * ratio.powf(u.powi(2))).floor()
+                        as usize)
+                        .max(2);
+                    let sigma = self.sigma0 * 10f64.powf(-2.0 * self.rng.gen::<f64>());
+                    CMAESRun::new(self.x0.clone(), sigma, lambda, 1)
+                }
+            }
+        };
+        self.run = Some(run);
+    }
+
+    fn max_restarts(&self) -> u64 {
+        match self.restart {
+            CMAESRestart::None => 0,
+            CMAESRestart::IPOP { max_restarts, .. } => max_restarts,
+            CMAESRestart::BIPOP { max_restarts } => max_restarts,
+        }
+    }
+}
+
+impl<O> Solver<O> for CMAES
+where
+    O: ArgminOp<Param = Vec<f64>, Output = f64>,
+{
+    const NAME: &'static str = "CMA-ES";
+
+    fn init(
+        &mut self,
+        _op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let x0 = state.get_param();
+        let n = x0.len();
+        if n == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "CMA-ES: initial parameter vector must not be empty.".to_string(),
+            }
+            .into());
+        }
+        self.lambda_default = self
+            .lambda0
+            .unwrap_or(4 + (3.0 * (n as f64).ln()).floor() as usize);
+        self.lambda_large = self.lambda_default;
+        self.x0 = x0.clone();
+        self.best_param = x0;
+        self.run = Some(CMAESRun::new(
+            self.x0.clone(),
+            self.sigma0,
+            self.lambda_default,
+            0,
+        ));
+        Ok(None)
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let mut run = self.run.take().unwrap();
+        let n = run.mean.len();
+
+        // Sample and evaluate new population
+        let mut population: Vec<(Vec<f64>, Vec<f64>, f64)> = Vec::with_capacity(run.lambda);
+        for _ in 0..run.lambda {
+            let z: Vec<f64> = (0..n).map(|_| self.randn()).collect();
+            let y = run.transform(&z);
+            let x: Vec<f64> = run
+                .mean
+                .iter()
+                .zip(y.iter())
+                .map(|(m, yi)| m + run.sigma * yi)
+                .collect();
+            let cost = op.apply(&x)?;
+            population.push((x, y, cost));
+        }
+        population.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+        self.bipop_evals[run.bipop_regime] += run.lambda as u64;
+
+        if population[0].2 < self.best_cost {
+            self.best_cost = population[0].2;
+            self.best_param = population[0].0.clone();
+        }
+        run.history.push(population[0].2);
+
+        // Recombination
+        let yw: Vec<f64> = (0..n)
+            .map(|i| {
+                (0..run.mu)
+                    .map(|k| run.weights[k] * population[k].1[i])
+                    .sum()
+            })
+            .collect();
+        for (m, yi) in run.mean.iter_mut().zip(yw.iter()) {
+            *m += run.sigma * yi;
+        }
+
+        // Step size control
+        let c_yw = run.inv_sqrt_c(&yw);
+        let fs = (run.cs * (2.0 - run.cs) * run.mueff).sqrt();
+        for (psi, cyi) in run.ps.iter_mut().zip(c_yw.iter()) {
+            *psi = (1.0 - run.cs) * *psi + fs * cyi;
+        }
+        let ps_norm = dot(&run.ps, &run.ps).sqrt();
+        let hsig = ps_norm
+            / (1.0 - (1.0 - run.cs).powi(2 * (run.generation as i32 + 1))).sqrt()
+            / run.chi_n
+            < 1.4 + 2.0 / (n as f64 + 1.0);
+
+        // Covariance matrix adaptation
+        let fc = if hsig {
+            (run.cc * (2.0 - run.cc) * run.mueff).sqrt()
+        } else {
+            0.0
+        };
+        for (pci, yi) in run.pc.iter_mut().zip(yw.iter()) {
+            *pci = (1.0 - run.cc) * *pci + fc * yi;
+        }
+        let dh = if hsig { 0.0 } else { run.cc * (2.0 - run.cc) };
+        for i in 0..n {
+            for j in 0..=i {
+                let rank_mu: f64 = (0..run.mu)
+                    .map(|k| run.weights[k] * population[k].1[i] * population[k].1[j])
+                    .sum();
+                let cij = (1.0 - run.c1 - run.cmu) * run.c[i][j]
+                    + run.c1 * (run.pc[i] * run.pc[j] + dh * run.c[i][j])
+                    + run.cmu * rank_mu;
+                run.c[i][j] = cij;
+                run.c[j][i] = cij;
+            }
+        }
+
+        run.sigma *= ((run.cs / run.damps) * (ps_norm / run.chi_n - 1.0)).exp();
+
+        // Update B and D from C
+        let (eigvals, eigvecs) = symmetric_eigen(&run.c);
+        run.d = eigvals.iter().map(|e| e.max(0.0).sqrt()).collect();
+        run.b = eigvecs;
+        run.generation += 1;
+
+        let population: Vec<(Vec<f64>, f64)> = population
+            .into_iter()
+            .map(|(x, _, cost)| (x, cost))
+            .collect();
+
+        let kv = make_kv!(
+            "sigma" => run.sigma;
+            "population_size" => run.lambda;
+            "restarts" => self.restarts;
+        );
+
+        let stop = self.run_terminated(&run);
+        self.run = Some(run);
+        if let Some(reason) = stop {
+            if self.restarts < self.max_restarts() {
+                self.restart_run();
+            } else {
+                self.termination = reason;
+            }
+        }
+
+        Ok(ArgminIterData::new()
+            .param(self.best_param.clone())
+            .cost(self.best_cost)
+            .population(population)
+            .kv(kv))
+    }
+
+    fn terminate(&mut self, _state: &IterState<O>) -> TerminationReason {
+        self.termination
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+    use crate::testfunctions::{rosenbrock, sphere};
+
+    test_trait_impl!(cmaes, CMAES);
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Sphere {}
+
+    impl ArgminOp for Sphere {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(sphere(p))
+        }
+    }
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Rosenbrock {}
+
+    impl ArgminOp for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(rosenbrock(p, 1.0, 100.0))
+        }
+    }
+
+    /// Runs the solver until it terminates and returns the population size and number of
+    /// restarts reported in every iteration
+    fn run<O>(solver: &mut CMAES, op: O, x0: Vec<f64>) -> Vec<(usize, u64)>
+    where
+        O: ArgminOp<Param = Vec<f64>, Output = f64>,
+    {
+        let mut op = OpWrapper::new(&op);
+        let state = IterState::new(x0);
+        solver.init(&mut op, &state).unwrap();
+        let mut kvs = vec![];
+        while solver.termination == TerminationReason::NotTerminated {
+            assert!(kvs.len() < 100_000);
+            let kv = solver.next_iter(&mut op, &state).unwrap().get_kv();
+            let get = |key: &str| kv.kv.iter().find(|(k, _)| *k == key).unwrap().1.clone();
+            kvs.push((
+                get("population_size").parse().unwrap(),
+                get("restarts").parse().unwrap(),
+            ));
+        }
+        kvs
+    }
+
+    #[test]
+    fn test_sigma0() {
+        assert!(CMAES::new(0.0).is_err());
+        assert!(CMAES::new(0.5).is_ok());
+    }
+
+    #[test]
+    fn test_ipop_factor() {
+        let restart = CMAESRestart::IPOP {
+            max_restarts: 3,
+            factor: 0.5,
+        };
+        assert!(CMAES::new(0.5).unwrap().restart(restart).is_err());
+    }
+
+    #[test]
+    fn test_sphere() {
+        let mut solver = CMAES::new(0.5).unwrap().seed(1);
+        run(&mut solver, Sphere {}, vec![1.0, -2.0, 0.5]);
+        assert!(solver.best_cost < 1e-10);
+        assert!(solver.best_param.iter().all(|x| x.abs() < 1e-5));
+    }
+
+    #[test]
+    fn test_rosenbrock() {
+        let mut solver = CMAES::new(0.5).unwrap().seed(2);
+        run(&mut solver, Rosenbrock {}, vec![-1.2, 1.0, -1.2, 1.0]);
+        assert!(solver.best_cost < 1e-8);
+        assert!(solver.best_param.iter().all(|x| (x - 1.0).abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_ipop() {
+        let restart = CMAESRestart::IPOP {
+            max_restarts: 3,
+            factor: 2.0,
+        };
+        let mut solver = CMAES::new(0.5)
+            .unwrap()
+            .restart(restart)
+            .unwrap()
+            .tol_fun(1e-8)
+            .seed(3);
+        let kvs = run(&mut solver, Sphere {}, vec![1.0, -2.0]);
+        assert_eq!(solver.restarts, 3);
+        // the population size (default: 6) doubles with every restart
+        for restarts in 0..=3 {
+            assert!(kvs.contains(&(6 << restarts, restarts)));
+        }
+        assert!(kvs
+            .iter()
+            .all(|&(lambda, restarts)| lambda == 6 << restarts));
+        assert!(solver.best_cost < 1e-8);
+    }
+
+    #[test]
+    fn test_bipop() {
+        let mut solver = CMAES::new(0.5)
+            .unwrap()
+            .restart(CMAESRestart::BIPOP { max_restarts: 6 })
+            .unwrap()
+            .tol_fun(1e-8)
+            .seed(4);
+        let kvs = run(&mut solver, Sphere {}, vec![1.0, -2.0]);
+        assert_eq!(solver.restarts, 6);
+        assert_eq!(kvs.last().unwrap().1, 6);
+        // the first run uses the default population size, small runs use at most the default
+        // population size and large runs double the population size
+        assert!(kvs.iter().filter(|kv| kv.1 == 0).all(|kv| kv.0 == 6));
+        assert!(kvs.iter().any(|kv| kv.0 == 12));
+        assert!(kvs.iter().any(|kv| kv.1 > 0 && kv.0 <= 6));
+        assert!(solver.best_cost < 1e-8);
+    }
+
+    #[test]
+    fn test_seed() {
+        let run_seeded = |seed: u64| {
+            let mut solver = CMAES::new(0.5)
+                .unwrap()
+                .restart(CMAESRestart::BIPOP { max_restarts: 2 })
+                .unwrap()
+                .seed(seed);
+            let kvs = run(&mut solver, Rosenbrock {}, vec![-1.2, 1.0]);
+            (kvs, solver.best_param, solver.best_cost)
+        };
+        assert!(run_seeded(5) == run_seeded(5));
+        assert!(run_seeded(5) != run_seeded(6));
+    }
+}
@@ -10,6 +10,7 @@
 //! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
 //! Springer. ISBN 0-387-30303-0.
 
+use crate::preconditioner::IdentityPreconditioner;
 use crate::prelude::*;
 // use num_complex::Complex;
 #[cfg(feature = "serde1")]
@@ -20,6 +21,9 @@ use std::fmt::Debug;
 /// The conjugate gradient method is a solver for systems of linear equations with a symmetric and
 /// positive-definite matrix.
 ///
+/// A preconditioner (see `argmin::preconditioner`) can be provided via `preconditioner(...)`. In
+/// this case the residual `r^T M^{-1} r` replaces `r^T r` in the computation of the step lengths.
+///
 /// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/conjugategradient.rs)
 ///
 /// # References:
@@ -28,7 +32,7 @@ use std::fmt::Debug;
 /// Springer. ISBN 0-387-30303-0.
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
 #[derive(Clone)]
-pub struct ConjugateGradient<P, S> {
+pub struct ConjugateGradient<P, S, M = IdentityPreconditioner> {
     /// b (right hand side)
     b: P,
     /// residual
@@ -37,18 +41,20 @@ pub struct ConjugateGradient<P, S> {
     p: P,
     /// previous p
     p_prev: P,
-    /// r^T * r
+    /// r^T * M^{-1} * r
     #[cfg_attr(feature = "serde1", serde(skip))]
-    rtr: S,
+    rtz: S,
     /// alpha
     #[cfg_attr(feature = "serde1", serde(skip))]
     alpha: S,
     /// beta
     #[cfg_attr(feature = "serde1", serde(skip))]
     beta: S,
+    /// preconditioner
+    precond: M,
 }
 
-impl<P, S> ConjugateGradient<P, S>
+impl<P, S> ConjugateGradient<P, S, IdentityPreconditioner>
 where
     P: Clone + Default,
     S: Default,
@@ -64,11 +70,32 @@ where
             r: P::default(),
             p: P::default(),
             p_prev: P::default(),
-            rtr: S::default(),
+            rtz: S::default(),
             alpha: S::default(),
             beta: S::default(),
+            precond: IdentityPreconditioner::new(),
         })
     }
+}
+
+impl<P, S, M> ConjugateGradient<P, S, M>
+where
+    P: Clone + Default,
+    S: Default,
+{
+    /// Set preconditioner
+    pub fn preconditioner<M2: Preconditioner<P>>(self, precond: M2) -> ConjugateGradient<P, S, M2> {
+        ConjugateGradient {
+            b: self.b,
+            r: self.r,
+            p: self.p,
+            p_prev: self.p_prev,
+            rtz: self.rtz,
+            alpha: self.alpha,
+            beta: self.beta,
+            precond,
+        }
+    }
 
     /// Return the current search direction (This is needed by NewtonCG for instance)
     pub fn p(&self) -> P {
@@ -86,7 +113,7 @@ where
     }
 }
 
-impl<P, O, S> Solver<O> for ConjugateGradient<P, S>
+impl<P, O, S, M> Solver<O> for ConjugateGradient<P, S, M>
 where
     O: ArgminOp<Param = P, Output = P>,
     P: Clone
@@ -99,6 +126,7 @@ where
         + ArgminConj
         + ArgminMul<f64, P>,
     S: Debug + ArgminDiv<S, S> + ArgminNorm<f64> + ArgminConj,
+    M: Preconditioner<P> + SerializeAlias,
 {
     const NAME: &'static str = "Conjugate Gradient";
 
@@ -110,9 +138,10 @@ where
         let init_param = state.get_param();
         let ap = op.apply(&init_param)?;
         let r0 = self.b.sub(&ap).mul(&(-1.0));
-        self.r = r0.clone();
-        self.p = r0.mul(&(-1.0));
-        self.rtr = self.r.dot(&self.r.conj());
+        let z0 = self.precond.precondition(&r0)?;
+        self.r = r0;
+        self.p = z0.mul(&(-1.0));
+        self.rtz = self.r.dot(&z0.conj());
         Ok(None)
     }
 
@@ -124,13 +153,14 @@ where
     ) -> Result<ArgminIterData<O>, Error> {
         self.p_prev = self.p.clone();
         let apk = op.apply(&self.p)?;
-        self.alpha = self.rtr.div(&self.p.dot(&apk.conj()));
+        self.alpha = self.rtz.div(&self.p.dot(&apk.conj()));
         let new_param = state.get_param().scaled_add(&self.alpha, &self.p);
         self.r = self.r.scaled_add(&self.alpha, &apk);
-        let rtr_n = self.r.dot(&self.r.conj());
-        self.beta = rtr_n.div(&self.rtr);
-        self.rtr = rtr_n;
-        self.p = self.r.mul(&(-1.0)).scaled_add(&self.beta, &self.p);
+        let z = self.precond.precondition(&self.r)?;
+        let rtz_n = self.r.dot(&z.conj());
+        self.beta = rtz_n.div(&self.rtz);
+        self.rtz = rtz_n;
+        self.p = z.mul(&(-1.0)).scaled_add(&self.beta, &self.p);
         let norm = self.r.dot(&self.r.conj());
 
         Ok(ArgminIterData::new()
@@ -144,10 +174,43 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::preconditioner::JacobiPreconditioner;
     use crate::test_trait_impl;
 
     test_trait_impl!(
         conjugate_gradient,
         ConjugateGradient<NoOperator<Vec<f64>, Vec<f64>, (), ()>, f64>
     );
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct BadlyScaled {}
+
+    impl ArgminOp for BadlyScaled {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![1000.0 * p[0], 1.0 * p[1], 0.001 * p[2]])
+        }
+    }
+
+    #[test]
+    fn test_jacobi_preconditioner() {
+        // with the exact diagonal as preconditioner, CG converges in a single iteration
+        let precond = JacobiPreconditioner::new(&vec![1000.0, 1.0, 0.001]).unwrap();
+        let solver: ConjugateGradient<_, f64, _> = ConjugateGradient::new(vec![1.0, 1.0, 1.0])
+            .unwrap()
+            .preconditioner(precond);
+        let res = Executor::new(BadlyScaled {}, solver, vec![0.0; 3])
+            .max_iters(1)
+            .run()
+            .unwrap();
+        let x = res.state.get_param();
+        assert!((x[0] - 0.001).abs() < 1e-12);
+        assert!((x[1] - 1.0).abs() < 1e-12);
+        assert!((x[2] - 1000.0).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,580 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Rainer Storn and Kenneth Price (1997). Differential Evolution - A Simple and Efficient
+//! Heuristic for global Optimization over Continuous Spaces. Journal of Global Optimization 11,
+//! 341–359.
+//!
+//! [1] Janez Brest, Sao Greiner, Borko Boskovic, Marjan Mernik and Viljem Zumer (2006).
+//! Self-Adapting Control Parameters in Differential Evolution: A Comparative Study on Numerical
+//! Benchmark Problems. IEEE Transactions on Evolutionary Computation 10(6), 646-657.
+//!
+//! [2] Jingqiao Zhang and Arthur C. Sanderson (2009). JADE: Adaptive Differential Evolution With
+//! Optional External Archive. IEEE Transactions on Evolutionary Computation 13(5), 945-958.
+
+use crate::prelude::*;
+use rand::prelude::*;
+use rand_xorshift::XorShiftRng;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// Mutation strategy of differential evolution
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DEStrategy {
+    /// DE/rand/1/bin: `x_r1 + F * (x_r2 - x_r3)`
+    Rand1Bin,
+    /// DE/best/1/bin: `x_best + F * (x_r1 - x_r2)`
+    Best1Bin,
+    /// DE/current-to-best/1/bin: `x_i + F * (x_best - x_i) + F * (x_r1 - x_r2)`
+    CurrentToBest1Bin,
+}
+
+/// Control parameter adaptation of differential evolution
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DEAdaptation {
+    /// Fixed `F` and `CR`
+    None,
+    /// jDE: every individual carries its own `F` and `CR`, which are regenerated with
+    /// probability `tau_f` and `tau_cr`, respectively.
+    JDE {
+        /// probability of regenerating `F`
+        tau_f: f64,
+        /// probability of regenerating `CR`
+        tau_cr: f64,
+    },
+    /// JADE (without archive): `F` and `CR` are sampled around means which are adapted towards the
+    /// successful values. `x_best` in `CurrentToBest1Bin` is drawn from the best `p * NP`
+    /// individuals.
+    JADE {
+        /// learning rate of the means
+        c: f64,
+        /// fraction of the population considered for `x_best`
+        p: f64,
+    },
+}
+
+/// Differential Evolution (DE)
+///
+/// Maintains a population which is initialized uniformly within the search region. In each
+/// iteration, one mutant vector per individual is generated according to the chosen strategy and
+/// combined with the individual via binomial crossover. The resulting trial vector is clamped to
+/// the search region and replaces the individual if its cost is not worse.
+///
+/// The current population is reported via `ArgminIterData::population`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/differentialevolution.rs)
+///
+/// # References:
+///
+/// [0] Rainer Storn and Kenneth Price (1997). Differential Evolution - A Simple and Efficient
+/// Heuristic for global Optimization over Continuous Spaces. Journal of Global Optimization 11,
+/// 341–359.
+///
+/// [1] Janez Brest, Sao Greiner, Borko Boskovic, Marjan Mernik and Viljem Zumer (2006).
+/// Self-Adapting Control Parameters in Differential Evolution: A Comparative Study on Numerical
+/// Benchmark Problems. IEEE Transactions on Evolutionary Computation 10(6), 646-657.
+///
+/// [2] Jingqiao Zhang and Arthur C. Sanderson (2009). JADE: Adaptive Differential Evolution With
+/// Optional External Archive. IEEE Transactions on Evolutionary Computation 13(5), 945-958.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct DifferentialEvolution<P> {
+    /// search region
+    search_region: (P, P),
+    /// population size
+    num_individuals: usize,
+    /// mutation strategy
+    strategy: DEStrategy,
+    /// parameter adaptation
+    adaptation: DEAdaptation,
+    /// differential weight (mean of F for JADE)
+    f: f64,
+    /// crossover probability (mean of CR for JADE)
+    cr: f64,
+    /// population
+    population: Vec<(P, f64)>,
+    /// F of each individual (jDE)
+    individual_f: Vec<f64>,
+    /// CR of each individual (jDE)
+    individual_cr: Vec<f64>,
+    /// random number generator
+    rng: XorShiftRng,
+}
+
+impl<P> DifferentialEvolution<P>
+where
+    P: DEPosition,
+{
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `search_region`: lower and upper bounds of the search region
+    /// * `num_individuals`: population size (at least 4)
+    pub fn new(search_region: (P, P), num_individuals: usize) -> Result<Self, Error> {
+        if num_individuals < 4 {
+            return Err(ArgminError::InvalidParameter {
+                text: "DifferentialEvolution: num_individuals must be >= 4.".to_string(),
+            }
+            .into());
+        }
+        Ok(DifferentialEvolution {
+            search_region,
+            num_individuals,
+            strategy: DEStrategy::Rand1Bin,
+            adaptation: DEAdaptation::None,
+            f: 0.8,
+            cr: 0.9,
+            population: vec![],
+            individual_f: vec![],
+            individual_cr: vec![],
+            rng: XorShiftRng::from_entropy(),
+        })
+    }
+
+    /// Set mutation strategy
+    pub fn strategy(mut self, strategy: DEStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set parameter adaptation
+    pub fn adaptation(mut self, adaptation: DEAdaptation) -> Result<Self, Error> {
+        match adaptation {
+            DEAdaptation::JDE { tau_f, tau_cr }
+                if !(0.0..=1.0).contains(&tau_f) || !(0.0..=1.0).contains(&tau_cr) =>
+            {
+                return Err(ArgminError::InvalidParameter {
+                    text: "DifferentialEvolution: tau_f and tau_cr must be in [0, 1].".to_string(),
+                }
+                .into());
+            }
+            DEAdaptation::JADE { c, p } if !(0.0..=1.0).contains(&c) || p <= 0.0 || p > 1.0 => {
+                return Err(ArgminError::InvalidParameter {
+                    text: "DifferentialEvolution: c must be in [0, 1] and p in (0, 1].".to_string(),
+                }
+                .into());
+            }
+            _ => {}
+        }
+        self.adaptation = adaptation;
+        Ok(self)
+    }
+
+    /// Set differential weight `F` (initial mean of `F` for JADE)
+    pub fn f(mut self, f: f64) -> Result<Self, Error> {
+        if f <= 0.0 || f > 2.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "DifferentialEvolution: F must be in (0, 2].".to_string(),
+            }
+            .into());
+        }
+        self.f = f;
+        Ok(self)
+    }
+
+    /// Set crossover probability `CR` (initial mean of `CR` for JADE)
+    pub fn cr(mut self, cr: f64) -> Result<Self, Error> {
+        if !(0.0..=1.0).contains(&cr) {
+            return Err(ArgminError::InvalidParameter {
+                text: "DifferentialEvolution: CR must be in [0, 1].".to_string(),
+            }
+            .into());
+        }
+        self.cr = cr;
+        Ok(self)
+    }
+
+    /// Seed the random number generator
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Index of the individual with the lowest cost
+    fn best_index(&self) -> usize {
+        self.population
+            .iter()
+            .enumerate()
+            .fold(0, |best, (i, ind)| {
+                if ind.1 < self.population[best].1 {
+                    i
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Draw `k` distinct indices which differ from `exclude`
+    fn distinct_indices(&mut self, exclude: usize, k: usize) -> Vec<usize> {
+        let mut idx: Vec<usize> = Vec::with_capacity(k);
+        while idx.len() < k {
+            let r = self.rng.gen_range(0, self.num_individuals);
+            if r != exclude && !idx.contains(&r) {
+                idx.push(r);
+            }
+        }
+        idx
+    }
+
+    /// Draw `F` and `CR` for individual `i`
+    // `f64::clamp` requires Rust 1.50
+    #[allow(clippy::manual_clamp)]
+    fn control_parameters(&mut self, i: usize) -> (f64, f64) {
+        match self.adaptation {
+            DEAdaptation::None => (self.f, self.cr),
+            DEAdaptation::JDE { tau_f, tau_cr } => {
+                let f = if self.rng.gen::<f64>() < tau_f {
+                    0.1 + 0.9 * self.rng.gen::<f64>()
+                } else {
+                    self.individual_f[i]
+                };
+                let cr = if self.rng.gen::<f64>() < tau_cr {
+                    self.rng.gen::<f64>()
+                } else {
+                    self.individual_cr[i]
+                };
+                (f, cr)
+            }
+            DEAdaptation::JADE { .. } => {
+                // CR ~ N(mu_cr, 0.1) truncated to [0, 1] (Box-Muller transform)
+                let u1: f64 = 1.0 - self.rng.gen::<f64>();
+                let u2: f64 = self.rng.gen();
+                let n = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                let cr = (self.cr + 0.1 * n).max(0.0).min(1.0);
+                // F ~ Cauchy(mu_f, 0.1), regenerated if <= 0 and truncated to 1
+                let mut f = 0.0;
+                while f <= 0.0 {
+                    let u: f64 = self.rng.gen();
+                    f = self.f + 0.1 * (std::f64::consts::PI * (u - 0.5)).tan();
+                }
+                (f.min(1.0), cr)
+            }
+        }
+    }
+
+    /// Draw a random individual uniformly from the search region
+    fn random_individual(&mut self) -> P
+    where
+        P: FromIterator<f64>,
+        for<'a> &'a P: IntoIterator<Item = &'a f64>,
+    {
+        let rng = &mut self.rng;
+        let (lower, upper) = &self.search_region;
+        lower
+            .into_iter()
+            .zip(upper)
+            .map(|(l, u)| l + (u - l) * rng.gen::<f64>())
+            .collect()
+    }
+
+    /// Binomial crossover of target and mutant vector
+    fn crossover(&mut self, target: &P, mutant: &P, cr: f64) -> P
+    where
+        P: FromIterator<f64>,
+        for<'a> &'a P: IntoIterator<Item = &'a f64>,
+    {
+        let n = target.into_iter().count();
+        let j_rand = self.rng.gen_range(0, n.max(1));
+        let rng = &mut self.rng;
+        target
+            .into_iter()
+            .zip(mutant)
+            .enumerate()
+            .map(|(j, (t, m))| {
+                if j == j_rand || rng.gen::<f64>() < cr {
+                    *m
+                } else {
+                    *t
+                }
+            })
+            .collect()
+    }
+}
+
+impl<O> Solver<O> for DifferentialEvolution<O::Param>
+where
+    O: ArgminOp<Output = f64>,
+    O::Param: DEPosition + FromIterator<f64>,
+    for<'a> &'a O::Param: IntoIterator<Item = &'a f64>,
+{
+    const NAME: &'static str = "Differential Evolution";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let (lower, upper) = self.search_region.clone();
+        // The initial parameter vector is the first individual, all others are random
+        let mut population = Vec::with_capacity(self.num_individuals);
+        let init_param = O::Param::min(&O::Param::max(&state.get_param(), &lower), &upper);
+        let init_cost = op.apply(&init_param)?;
+        population.push((init_param, init_cost));
+        for _ in 1..self.num_individuals {
+            let x = self.random_individual();
+            let cost = op.apply(&x)?;
+            population.push((x, cost));
+        }
+        self.population = population;
+        self.individual_f = vec![self.f; self.num_individuals];
+        self.individual_cr = vec![self.cr; self.num_individuals];
+
+        let best = self.best_index();
+        Ok(Some(
+            ArgminIterData::new()
+                .param(self.population[best].0.clone())
+                .cost(self.population[best].1)
+                .population(self.population.clone()),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let best = self.best_index();
+
+        // Candidates for x_best in JADE: the best p * NP individuals
+        let pbest: Vec<usize> = match self.adaptation {
+            DEAdaptation::JADE { p, .. } => {
+                let mut sorted: Vec<usize> = (0..self.num_individuals).collect();
+                sorted.sort_by(|&a, &b| {
+                    self.population[a]
+                        .1
+                        .partial_cmp(&self.population[b].1)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                let num = ((p * self.num_individuals as f64).round() as usize).max(1);
+                sorted.truncate(num);
+                sorted
+            }
+            _ => vec![best],
+        };
+
+        let mut next_population = Vec::with_capacity(self.num_individuals);
+        let mut successful_f = vec![];
+        let mut successful_cr = vec![];
+        for i in 0..self.num_individuals {
+            let (f, cr) = self.control_parameters(i);
+            let r = self.distinct_indices(i, 3);
+            let x_best = &self.population[pbest[self.rng.gen_range(0, pbest.len())]].0;
+            let x_i = &self.population[i].0;
+            let (x1, x2, x3) = (
+                &self.population[r[0]].0,
+                &self.population[r[1]].0,
+                &self.population[r[2]].0,
+            );
+
+            let mutant = match self.strategy {
+                DEStrategy::Rand1Bin => x1.add(&x2.sub(x3).mul(&f)),
+                DEStrategy::Best1Bin => x_best.add(&x1.sub(x2).mul(&f)),
+                DEStrategy::CurrentToBest1Bin => {
+                    x_i.add(&x_best.sub(x_i).mul(&f)).add(&x1.sub(x2).mul(&f))
+                }
+            };
+
+            let x_i = x_i.clone();
+            let trial = self.crossover(&x_i, &mutant, cr);
+            let trial = O::Param::min(
+                &O::Param::max(&trial, &self.search_region.0),
+                &self.search_region.1,
+            );
+            let trial_cost = op.apply(&trial)?;
+
+            if trial_cost <= self.population[i].1 {
+                next_population.push((trial, trial_cost));
+                self.individual_f[i] = f;
+                self.individual_cr[i] = cr;
+                successful_f.push(f);
+                successful_cr.push(cr);
+            } else {
+                next_population.push(self.population[i].clone());
+            }
+        }
+        self.population = next_population;
+
+        if let DEAdaptation::JADE { c, .. } = self.adaptation {
+            if !successful_f.is_empty() {
+                let mean_cr = successful_cr.iter().sum::<f64>() / successful_cr.len() as f64;
+                // Lehmer mean of the successful F values
+                let lehmer = successful_f.iter().map(|f| f.powi(2)).sum::<f64>()
+                    / successful_f.iter().sum::<f64>();
+                self.cr = (1.0 - c) * self.cr + c * mean_cr;
+                self.f = (1.0 - c) * self.f + c * lehmer;
+            }
+        }
+
+        let best = self.best_index();
+        Ok(ArgminIterData::new()
+            .param(self.population[best].0.clone())
+            .cost(self.population[best].1)
+            .population(self.population.clone())
+            .kv(make_kv!(
+                "successful_trials" => successful_f.len();
+                "mean_f" => self.individual_f.iter().sum::<f64>() / self.num_individuals as f64;
+                "mean_cr" => self.individual_cr.iter().sum::<f64>() / self.num_individuals as f64;
+            )))
+    }
+}
+
+trait_bound!(DEPosition
+; Clone
+, ArgminAdd<Self, Self>
+, ArgminSub<Self, Self>
+, ArgminMul<f64, Self>
+, ArgminMinMax
+, std::fmt::Debug
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+    use crate::testfunctions::sphere;
+
+    test_trait_impl!(differentialevolution, DifferentialEvolution<Vec<f64>>);
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Sphere {}
+
+    impl ArgminOp for Sphere {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(sphere(p))
+        }
+    }
+
+    /// Rastrigin function, global minimum 0 at the origin
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Rastrigin {}
+
+    impl ArgminOp for Rastrigin {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(p.iter()
+                .map(|x| x.powi(2) - 10.0 * (2.0 * std::f64::consts::PI * x).cos() + 10.0)
+                .sum())
+        }
+    }
+
+    /// Variants covered by the convergence tests: every strategy with fixed parameters and every
+    /// adaptation
+    fn variants() -> Vec<(DEStrategy, DEAdaptation)> {
+        vec![
+            (DEStrategy::Rand1Bin, DEAdaptation::None),
+            (DEStrategy::Best1Bin, DEAdaptation::None),
+            (DEStrategy::CurrentToBest1Bin, DEAdaptation::None),
+            (
+                DEStrategy::Rand1Bin,
+                DEAdaptation::JDE {
+                    tau_f: 0.1,
+                    tau_cr: 0.1,
+                },
+            ),
+            (
+                DEStrategy::CurrentToBest1Bin,
+                DEAdaptation::JADE { c: 0.1, p: 0.1 },
+            ),
+        ]
+    }
+
+    /// Runs differential evolution and returns the best parameter vector and cost
+    fn run<O>(
+        op: O,
+        strategy: DEStrategy,
+        adaptation: DEAdaptation,
+        cr: f64,
+        seed: u64,
+    ) -> (Vec<f64>, f64)
+    where
+        O: ArgminOp<Param = Vec<f64>, Output = f64>,
+    {
+        let region = (vec![-5.12; 3], vec![5.12; 3]);
+        let solver = DifferentialEvolution::new(region, 40)
+            .unwrap()
+            .strategy(strategy)
+            .adaptation(adaptation)
+            .unwrap()
+            .f(0.5)
+            .unwrap()
+            .cr(cr)
+            .unwrap()
+            .seed(seed);
+        let res = Executor::new(op, solver, vec![3.0, -2.0, 4.0])
+            .max_iters(500)
+            .run()
+            .unwrap();
+        (res.state.get_best_param(), res.state.get_best_cost())
+    }
+
+    #[test]
+    fn test_sphere() {
+        for (strategy, adaptation) in variants() {
+            let (param, cost) = run(Sphere {}, strategy, adaptation, 0.9, 1);
+            assert!(cost < 1e-12, "{:?} {:?}: {}", strategy, adaptation, cost);
+            assert!(param.iter().all(|x| x.abs() < 1e-6));
+        }
+    }
+
+    #[test]
+    fn test_rastrigin() {
+        for (strategy, adaptation) in variants() {
+            // a small crossover probability suits the separable Rastrigin function
+            let (param, cost) = run(Rastrigin {}, strategy, adaptation, 0.2, 2);
+            assert!(cost < 1e-8, "{:?} {:?}: {}", strategy, adaptation, cost);
+            assert!(param.iter().all(|x| x.abs() < 1e-4));
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        let adaptation = DEAdaptation::JADE { c: 0.1, p: 0.1 };
+        let strategy = DEStrategy::CurrentToBest1Bin;
+        let a = run(Rastrigin {}, strategy, adaptation, 0.2, 3);
+        assert_eq!(a, run(Rastrigin {}, strategy, adaptation, 0.2, 3));
+        assert_ne!(a.0, run(Rastrigin {}, strategy, adaptation, 0.2, 4).0);
+    }
+
+    #[test]
+    fn test_num_individuals() {
+        let region = (vec![-1.0, -1.0], vec![1.0, 1.0]);
+        assert!(DifferentialEvolution::new(region.clone(), 3).is_err());
+        assert!(DifferentialEvolution::new(region, 4).is_ok());
+    }
+
+    #[test]
+    fn test_adaptation_parameters() {
+        let de = DifferentialEvolution::new((vec![-1.0], vec![1.0]), 10).unwrap();
+        assert!(de
+            .clone()
+            .adaptation(DEAdaptation::JADE { c: 0.1, p: 0.0 })
+            .is_err());
+        assert!(de
+            .adaptation(DEAdaptation::JDE {
+                tau_f: 0.1,
+                tau_cr: 0.1
+            })
+            .is_ok());
+    }
+}
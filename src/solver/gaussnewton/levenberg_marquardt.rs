@@ -0,0 +1,444 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] K. Madsen, H. B. Nielsen and O. Tingleff (2004). Methods for Non-Linear Least Squares
+//! Problems. Technical University of Denmark.
+
+use crate::numdiff::FiniteDiffMatrix;
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::default::Default;
+
+/// Levenberg-Marquardt method
+///
+/// Solves nonlinear least squares problems by computing steps from the damped normal equations
+/// `(J^T J + lambda * D) p = -J^T r`. The damping factor `lambda` is adapted in every iteration
+/// according to Nielsen's strategy, based on the ratio of actual to predicted reduction of
+/// `0.5 * ||r||^2`. `D` is the identity unless a (diagonal) scaling matrix is provided via
+/// `scaling`.
+///
+/// Just like `GaussNewton`, the operator needs to implement `apply` (returning the residuals) and
+/// `jacobian`. The reported cost is the norm of the residuals.
+///
+/// The solver terminates if the norm of the gradient `J^T r` falls below `gtol`
+/// (`TargetPrecisionReached`), if the norm of the step falls below `xtol * (||x|| + xtol)`
+/// (`TargetToleranceReached`) or if the relative reduction of the cost of an accepted step falls
+/// below `ftol` (`NoChangeInCost`).
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/levenberg_marquardt.rs)
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+///
+/// [1] K. Madsen, H. B. Nielsen and O. Tingleff (2004). Methods for Non-Linear Least Squares
+/// Problems. Technical University of Denmark.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct LevenbergMarquardt<P, J> {
+    /// damping factor
+    lambda: f64,
+    /// factor by which lambda is increased after a rejected step
+    nu: f64,
+    /// scaling matrix D
+    scaling: Option<J>,
+    /// gradient tolerance
+    gtol: f64,
+    /// step tolerance
+    xtol: f64,
+    /// relative cost reduction tolerance
+    ftol: f64,
+    /// J^T J at the current parameter vector
+    jtj: Option<J>,
+    /// gradient J^T r at the current parameter vector
+    grad: Option<P>,
+    /// norm of the current gradient
+    grad_norm: f64,
+    /// whether the last step was smaller than the step tolerance
+    step_small: bool,
+    /// relative reduction of the cost in the last iteration
+    rel_reduction: f64,
+}
+
+impl<P, J> LevenbergMarquardt<P, J> {
+    /// Constructor
+    pub fn new() -> Self {
+        LevenbergMarquardt {
+            lambda: 1e-3,
+            nu: 2.0,
+            scaling: None,
+            gtol: 1e-10,
+            xtol: std::f64::EPSILON.sqrt(),
+            ftol: std::f64::EPSILON.sqrt(),
+            jtj: None,
+            grad: None,
+            grad_norm: std::f64::INFINITY,
+            step_small: false,
+            rel_reduction: std::f64::INFINITY,
+        }
+    }
+
+    /// Set initial damping factor
+    pub fn lambda(mut self, lambda: f64) -> Result<Self, Error> {
+        if lambda <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Levenberg-Marquardt: lambda must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.lambda = lambda;
+        Ok(self)
+    }
+
+    /// Set gradient tolerance
+    pub fn gtol(mut self, gtol: f64) -> Result<Self, Error> {
+        if gtol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Levenberg-Marquardt: gtol must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.gtol = gtol;
+        Ok(self)
+    }
+
+    /// Set step tolerance
+    pub fn xtol(mut self, xtol: f64) -> Result<Self, Error> {
+        if xtol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Levenberg-Marquardt: xtol must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.xtol = xtol;
+        Ok(self)
+    }
+
+    /// Set relative cost reduction tolerance
+    pub fn ftol(mut self, ftol: f64) -> Result<Self, Error> {
+        if ftol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Levenberg-Marquardt: ftol must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.ftol = ftol;
+        Ok(self)
+    }
+}
+
+impl<P, J: FiniteDiffMatrix> LevenbergMarquardt<P, J> {
+    /// Set diagonal scaling matrix `D`. Its diagonal entries must be positive.
+    pub fn scaling(mut self, scaling: J) -> Result<Self, Error> {
+        let rows = scaling.to_rows();
+        if rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| row.get(i).cloned().unwrap_or(0.0))
+            .any(|d| d.is_nan() || d <= 0.0)
+        {
+            return Err(ArgminError::InvalidParameter {
+                text: "Levenberg-Marquardt: diagonal entries of scaling must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.scaling = Some(scaling);
+        Ok(self)
+    }
+}
+
+impl<P, J> Default for LevenbergMarquardt<P, J> {
+    fn default() -> LevenbergMarquardt<P, J> {
+        LevenbergMarquardt::new()
+    }
+}
+
+impl<P, J> LevenbergMarquardt<P, J>
+where
+    P: ArgminNorm<f64>,
+    J: Clone + ArgminTranspose + ArgminDot<J, J>,
+{
+    /// Compute and store `J^T J` and the gradient `J^T r`
+    fn update_derivatives<R>(&mut self, jacobian: J, residuals: &R)
+    where
+        J: ArgminDot<R, P>,
+    {
+        let jt = jacobian.clone().t();
+        let grad = jt.dot(residuals);
+        self.grad_norm = grad.norm();
+        self.jtj = Some(jt.dot(&jacobian));
+        self.grad = Some(grad);
+    }
+}
+
+impl<O> Solver<O> for LevenbergMarquardt<O::Param, O::Jacobian>
+where
+    O: ArgminOp,
+    O::Param: ArgminSub<O::Param, O::Param>
+        + ArgminAdd<O::Param, O::Param>
+        + ArgminMul<f64, O::Param>
+        + ArgminDot<O::Param, f64>
+        + ArgminNorm<f64>,
+    O::Output: ArgminNorm<f64>,
+    O::Jacobian: ArgminTranspose
+        + ArgminInv<O::Jacobian>
+        + ArgminEye
+        + ArgminAdd<O::Jacobian, O::Jacobian>
+        + ArgminMul<f64, O::Jacobian>
+        + ArgminDot<O::Jacobian, O::Jacobian>
+        + ArgminDot<O::Output, O::Param>
+        + ArgminDot<O::Param, O::Param>,
+{
+    const NAME: &'static str = "Levenberg-Marquardt method";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        let residuals = op.apply(&param)?;
+        let jacobian = op.jacobian(&param)?;
+        self.update_derivatives(jacobian, &residuals);
+        self.step_small = false;
+        self.rel_reduction = std::f64::INFINITY;
+
+        Ok(Some(
+            ArgminIterData::new()
+                .param(param)
+                .cost(residuals.norm())
+                .grad(self.grad.clone().unwrap())
+                .kv(make_kv!("lambda" => self.lambda;)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let cost = state.get_cost();
+        let jtj = self.jtj.clone().unwrap();
+        let grad = self.grad.clone().unwrap();
+
+        let d = match self.scaling {
+            Some(ref scaling) => scaling.clone(),
+            None => jtj.eye_like(),
+        };
+
+        // Solve (J^T J + lambda * D) p = J^T r; the actual step is -p
+        let p = jtj.add(&d.mul(&self.lambda)).inv()?.dot(&grad);
+        let p_norm = p.norm();
+
+        self.step_small = p_norm <= self.xtol * (param.norm() + self.xtol);
+        if self.step_small {
+            return Ok(ArgminIterData::new()
+                .param(param)
+                .cost(cost)
+                .grad(grad)
+                .kv(make_kv!("lambda" => self.lambda; "step_accepted" => false;)));
+        }
+
+        let new_param = param.sub(&p);
+        let new_residuals = op.apply(&new_param)?;
+        let new_cost = new_residuals.norm();
+
+        // gain ratio of the actual reduction of 0.5 * ||r||^2 and the reduction predicted by the
+        // linear model
+        let actual = 0.5 * (cost.powi(2) - new_cost.powi(2));
+        let predicted = 0.5 * p.dot(&d.dot(&p).mul(&self.lambda).add(&grad));
+        let rho = actual / predicted;
+
+        let step_accepted = rho > 0.0;
+        if step_accepted {
+            self.lambda *= (1.0f64 / 3.0).max(1.0 - (2.0 * rho - 1.0).powi(3));
+            self.nu = 2.0;
+            self.rel_reduction = (cost - new_cost) / cost;
+            let jacobian = op.jacobian(&new_param)?;
+            self.update_derivatives(jacobian, &new_residuals);
+        } else {
+            self.lambda *= self.nu;
+            self.nu *= 2.0;
+        }
+
+        let kv = make_kv!(
+            "lambda" => self.lambda;
+            "rho" => rho;
+            "step_accepted" => step_accepted;
+            "grad_norm" => self.grad_norm;
+        );
+
+        Ok(if step_accepted {
+            ArgminIterData::new()
+                .param(new_param)
+                .cost(new_cost)
+                .grad(self.grad.clone().unwrap())
+                .kv(kv)
+        } else {
+            ArgminIterData::new()
+                .param(param)
+                .cost(cost)
+                .grad(grad)
+                .kv(kv)
+        })
+    }
+
+    fn terminate(&mut self, _state: &IterState<O>) -> TerminationReason {
+        if self.grad_norm <= self.gtol {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        if self.step_small {
+            return TerminationReason::TargetToleranceReached;
+        }
+        if self.rel_reduction <= self.ftol {
+            return TerminationReason::NoChangeInCost;
+        }
+        TerminationReason::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(
+        levenberg_marquardt_method,
+        LevenbergMarquardt<Vec<f64>, Vec<Vec<f64>>>
+    );
+
+    #[test]
+    fn test_lambda() {
+        let solver: LevenbergMarquardt<Vec<f64>, Vec<Vec<f64>>> = LevenbergMarquardt::new();
+        assert!(solver.clone().lambda(0.0).is_err());
+        assert!(solver.lambda(1.0).is_ok());
+    }
+
+    #[test]
+    fn test_scaling() {
+        let solver: LevenbergMarquardt<Vec<f64>, Vec<Vec<f64>>> = LevenbergMarquardt::new();
+        let nan = std::f64::NAN;
+        assert!(solver
+            .clone()
+            .scaling(vec![vec![1.0, 0.0], vec![0.0, 0.0]])
+            .is_err());
+        assert!(solver
+            .clone()
+            .scaling(vec![vec![-1.0, 0.0], vec![0.0, 1.0]])
+            .is_err());
+        assert!(solver
+            .clone()
+            .scaling(vec![vec![nan, 0.0], vec![0.0, 1.0]])
+            .is_err());
+        assert!(solver.scaling(vec![vec![2.0, 0.0], vec![0.0, 0.5]]).is_ok());
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    mod ndarray_tests {
+        use super::*;
+        use ndarray::{array, Array1, Array2};
+
+        /// Residuals of the model `y = a * exp(b * t)` with respect to data generated by
+        /// `a = 2` and `b = -0.5`
+        #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+        #[derive(Clone, Default)]
+        struct Exponential {}
+
+        impl Exponential {
+            const T: [f64; 5] = [0.0, 1.0, 2.0, 3.0, 4.0];
+        }
+
+        impl ArgminOp for Exponential {
+            type Param = Array1<f64>;
+            type Output = Array1<f64>;
+            type Hessian = ();
+            type Jacobian = Array2<f64>;
+
+            fn apply(&self, p: &Array1<f64>) -> Result<Array1<f64>, Error> {
+                Ok(Self::T
+                    .iter()
+                    .map(|t| p[0] * (p[1] * t).exp() - 2.0 * (-0.5 * t).exp())
+                    .collect())
+            }
+
+            fn jacobian(&self, p: &Array1<f64>) -> Result<Array2<f64>, Error> {
+                Ok(Array2::from_shape_fn((Self::T.len(), 2), |(i, j)| {
+                    let t = Self::T[i];
+                    if j == 0 {
+                        (p[1] * t).exp()
+                    } else {
+                        p[0] * t * (p[1] * t).exp()
+                    }
+                }))
+            }
+        }
+
+        /// Single residual `atan(x)`, for which the undamped Gauss-Newton step overshoots
+        /// far from the root
+        #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+        #[derive(Clone, Default)]
+        struct Arctan {}
+
+        impl ArgminOp for Arctan {
+            type Param = Array1<f64>;
+            type Output = Array1<f64>;
+            type Hessian = ();
+            type Jacobian = Array2<f64>;
+
+            fn apply(&self, p: &Array1<f64>) -> Result<Array1<f64>, Error> {
+                Ok(array![p[0].atan()])
+            }
+
+            fn jacobian(&self, p: &Array1<f64>) -> Result<Array2<f64>, Error> {
+                Ok(array![[1.0 / (1.0 + p[0].powi(2))]])
+            }
+        }
+
+        #[test]
+        fn test_least_squares_fit() {
+            let res = Executor::new(Exponential {}, LevenbergMarquardt::new(), array![1.0, 0.0])
+                .max_iters(100)
+                .run()
+                .unwrap();
+            assert_ne!(
+                res.state.get_termination_reason(),
+                TerminationReason::MaxItersReached
+            );
+            let param = res.state.get_best_param();
+            assert!((param[0] - 2.0).abs() < 1e-6);
+            assert!((param[1] + 0.5).abs() < 1e-6);
+            assert!(res.state.get_best_cost() < 1e-6);
+        }
+
+        #[test]
+        fn test_rejected_steps_increase_damping() {
+            let mut op = OpWrapper::new(&Arctan {});
+            let mut solver = LevenbergMarquardt::new();
+            let mut state = IterState::new(array![2.0]);
+            let data = solver.init(&mut op, &state).unwrap().unwrap();
+            state.cost(data.get_cost().unwrap());
+
+            let lambda = solver.lambda;
+            let data = solver.next_iter(&mut op, &state).unwrap();
+            assert_eq!(data.get_param().unwrap(), array![2.0]);
+            assert!((solver.lambda - 2.0 * lambda).abs() < std::f64::EPSILON);
+
+            // the factor by which the damping is increased doubles after every rejection
+            let data = solver.next_iter(&mut op, &state).unwrap();
+            assert_eq!(data.get_param().unwrap(), array![2.0]);
+            assert!((solver.lambda - 8.0 * lambda).abs() < std::f64::EPSILON);
+        }
+    }
+}
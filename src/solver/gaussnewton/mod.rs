@@ -16,6 +16,9 @@
 pub mod gaussnewton_linesearch;
 /// Gauss-Newton method
 pub mod gaussnewton_method;
+/// Levenberg-Marquardt method
+pub mod levenberg_marquardt;
 
 pub use self::gaussnewton_linesearch::*;
 pub use self::gaussnewton_method::*;
+pub use self::levenberg_marquardt::*;
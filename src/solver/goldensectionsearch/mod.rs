@@ -0,0 +1,268 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Golden-section search
+//!
+//! # References:
+//!
+//! [0] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+//! Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+//! ISBN 0-521-88068-8.
+//!
+//! https://en.wikipedia.org/wiki/Golden-section_search
+
+use crate::prelude::*;
+use crate::solver::bracketing::bracket_minimum;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// (sqrt(5) - 1) / 2
+const R: f64 = 0.618_033_988_749_895;
+
+/// 1 - R
+const C: f64 = 1.0 - R;
+
+/// Absolute tolerance used to avoid a vanishing tolerance at zero
+const ZEPS: f64 = 1e-10;
+
+/// Golden-section search
+///
+/// Finds a minimum of a scalar function by successively narrowing an interval by the golden
+/// ratio. Each iteration requires one function evaluation. If no interval is given via `bracket`,
+/// a bracket is searched for starting from the initial parameter (see
+/// `bracketing::bracket_minimum`).
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/goldensectionsearch.rs)
+///
+/// # References:
+///
+/// [0] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+/// Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+/// ISBN 0-521-88068-8.
+///
+/// https://en.wikipedia.org/wiki/Golden-section_search
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct GoldenSectionSearch {
+    /// required relative accuracy
+    tol: f64,
+    /// user provided interval
+    bracket: Option<(f64, f64)>,
+    /// initial step of the automatic bracketing
+    step: f64,
+    /// left boundary of current interval
+    x0: f64,
+    /// right boundary of current interval
+    x3: f64,
+    /// left inner point
+    x1: f64,
+    /// right inner point
+    x2: f64,
+    /// function value at `x1`
+    f1: f64,
+    /// function value at `x2`
+    f2: f64,
+}
+
+impl GoldenSectionSearch {
+    /// Constructor
+    /// The parameter `tol` specifies the relative error to be targeted.
+    pub fn new(tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "GoldenSectionSearch: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(GoldenSectionSearch {
+            tol,
+            bracket: None,
+            step: 1.0,
+            x0: f64::NAN,
+            x3: f64::NAN,
+            x1: f64::NAN,
+            x2: f64::NAN,
+            f1: f64::NAN,
+            f2: f64::NAN,
+        })
+    }
+
+    /// Set interval `[min, max]` which contains the minimum. This disables automatic bracketing.
+    pub fn bracket(mut self, min: f64, max: f64) -> Result<Self, Error> {
+        if min >= max {
+            return Err(ArgminError::InvalidParameter {
+                text: "GoldenSectionSearch: min must be smaller than max.".to_string(),
+            }
+            .into());
+        }
+        self.bracket = Some((min, max));
+        Ok(self)
+    }
+
+    /// Set initial step of the automatic bracketing (default: 1.0)
+    pub fn initial_step(mut self, step: f64) -> Result<Self, Error> {
+        if step == 0.0 || !step.is_finite() {
+            return Err(ArgminError::InvalidParameter {
+                text: "GoldenSectionSearch: initial step must be finite and non-zero.".to_string(),
+            }
+            .into());
+        }
+        self.step = step;
+        Ok(self)
+    }
+
+    /// Current best point and its function value
+    fn best(&self) -> (f64, f64) {
+        if self.f1 < self.f2 {
+            (self.x1, self.f1)
+        } else {
+            (self.x2, self.f2)
+        }
+    }
+}
+
+impl<O> Solver<O> for GoldenSectionSearch
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    const NAME: &'static str = "Golden-section search";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        match self.bracket {
+            Some((min, max)) => {
+                self.x0 = min;
+                self.x3 = max;
+                self.x1 = min + C * (max - min);
+                self.x2 = min + R * (max - min);
+                self.f1 = op.apply(&self.x1)?;
+                self.f2 = op.apply(&self.x2)?;
+            }
+            None => {
+                let br = bracket_minimum(op, state.get_param(), self.step)?;
+                self.x0 = br.a;
+                self.x3 = br.c;
+                // place the new point in the larger of the two segments
+                if br.c - br.b > br.b - br.a {
+                    self.x1 = br.b;
+                    self.f1 = br.fb;
+                    self.x2 = br.b + C * (br.c - br.b);
+                    self.f2 = op.apply(&self.x2)?;
+                } else {
+                    self.x2 = br.b;
+                    self.f2 = br.fb;
+                    self.x1 = br.b - C * (br.b - br.a);
+                    self.f1 = op.apply(&self.x1)?;
+                }
+            }
+        }
+        let (x, fx) = self.best();
+        Ok(Some(
+            ArgminIterData::new()
+                .param(x)
+                .cost(fx)
+                .kv(make_kv!("a" => self.x0; "b" => self.x3;)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // GoldenSectionSearch maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        if (self.x3 - self.x0).abs() <= self.tol * (self.x1.abs() + self.x2.abs()) + ZEPS {
+            let (x, fx) = self.best();
+            return Ok(ArgminIterData::new()
+                .termination_reason(TerminationReason::TargetPrecisionReached)
+                .param(x)
+                .cost(fx));
+        }
+        if self.f2 < self.f1 {
+            self.x0 = self.x1;
+            self.x1 = self.x2;
+            self.x2 = R * self.x2 + C * self.x3;
+            self.f1 = self.f2;
+            self.f2 = op.apply(&self.x2)?;
+        } else {
+            self.x3 = self.x2;
+            self.x2 = self.x1;
+            self.x1 = R * self.x1 + C * self.x0;
+            self.f2 = self.f1;
+            self.f1 = op.apply(&self.x1)?;
+        }
+        let (x, fx) = self.best();
+        Ok(ArgminIterData::new()
+            .param(x)
+            .cost(fx)
+            .kv(make_kv!("a" => self.x0; "b" => self.x3;)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(goldensectionsearch, GoldenSectionSearch);
+
+    #[test]
+    fn test_bracket() {
+        assert!(GoldenSectionSearch::new(1e-8)
+            .unwrap()
+            .bracket(1.0, 0.0)
+            .is_err());
+        assert!(GoldenSectionSearch::new(1e-8)
+            .unwrap()
+            .bracket(0.0, 1.0)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_tol() {
+        assert!(GoldenSectionSearch::new(0.0).is_err());
+        assert!(GoldenSectionSearch::new(-1e-8).is_err());
+    }
+
+    /// `f(x) = (x - 2)^4 + (x - 2)^2`, minimum at 2
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Quartic {}
+
+    impl ArgminOp for Quartic {
+        type Param = f64;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok((p - 2.0).powi(4) + (p - 2.0).powi(2))
+        }
+    }
+
+    #[test]
+    fn test_minimum() {
+        let solver = GoldenSectionSearch::new(1e-8).unwrap();
+        for solver in vec![solver.clone().bracket(-1.0, 3.0).unwrap(), solver] {
+            // without bracket, the minimum is bracketed automatically starting from -5
+            let res = Executor::new(Quartic {}, solver, -5.0)
+                .max_iters(1000)
+                .run()
+                .unwrap();
+            assert_eq!(
+                res.state.get_termination_reason(),
+                TerminationReason::TargetPrecisionReached
+            );
+            assert!((res.state.get_best_param() - 2.0).abs() < 1e-7);
+            assert!(res.state.get_best_cost() < 1e-14);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] H. A. van der Vorst (1992). Bi-CGSTAB: A Fast and Smoothly Converging Variant of Bi-CG for
+//! the Solution of Nonsymmetric Linear Systems. SIAM Journal on Scientific and Statistical
+//! Computing 13(2), 631-644.
+//!
+//! [1] Yousef Saad (2003). Iterative Methods for Sparse Linear Systems. 2nd edition.
+//! SIAM. ISBN 0-89871-534-2.
+
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Biconjugate gradient stabilized method (BiCGSTAB) for systems of linear equations with a
+/// general (non-symmetric) matrix.
+///
+/// Every iteration requires two products with the matrix, but in contrast to GMRES the memory
+/// requirements do not grow with the number of iterations. If the method breaks down because the
+/// shadow residual becomes orthogonal to the residual, the shadow residual is reset to the current
+/// residual.
+///
+/// The norm of the residual is reported as cost and as `residual` in the key-value store, together
+/// with the step lengths `alpha` and `omega`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/bicgstab.rs)
+///
+/// # References:
+///
+/// [0] H. A. van der Vorst (1992). Bi-CGSTAB: A Fast and Smoothly Converging Variant of Bi-CG for
+/// the Solution of Nonsymmetric Linear Systems. SIAM Journal on Scientific and Statistical
+/// Computing 13(2), 631-644.
+///
+/// [1] Yousef Saad (2003). Iterative Methods for Sparse Linear Systems. 2nd edition.
+/// SIAM. ISBN 0-89871-534-2.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct BiCGSTAB<P> {
+    /// b (right hand side)
+    b: P,
+    /// relative tolerance
+    tol: f64,
+    /// norm of b
+    b_norm: f64,
+    /// residual
+    r: P,
+    /// shadow residual
+    r_hat: P,
+    /// search direction
+    p: P,
+    /// A * p
+    v: P,
+    /// r_hat^T r
+    rho: f64,
+    /// alpha
+    alpha: f64,
+    /// omega
+    omega: f64,
+    /// norm of the residual
+    residual: f64,
+}
+
+impl<P: Clone + Default> BiCGSTAB<P> {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// `b`: right hand side of `A * x = b`
+    pub fn new(b: P) -> Result<Self, Error> {
+        Ok(BiCGSTAB {
+            b,
+            tol: 1e-10,
+            b_norm: std::f64::NAN,
+            r: P::default(),
+            r_hat: P::default(),
+            p: P::default(),
+            v: P::default(),
+            rho: 1.0,
+            alpha: 1.0,
+            omega: 1.0,
+            residual: std::f64::NAN,
+        })
+    }
+
+    /// Set relative tolerance `||b - A * x|| <= tol * ||b||` (default: 1e-10)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "BiCGSTAB: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+}
+
+impl<P, O> Solver<O> for BiCGSTAB<P>
+where
+    O: ArgminOp<Param = P, Output = P>,
+    P: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminDot<P, f64>
+        + ArgminSub<P, P>
+        + ArgminScaledAdd<P, f64, P>
+        + ArgminNorm<f64>
+        + ArgminZeroLike,
+{
+    const NAME: &'static str = "BiCGSTAB";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let x0 = state.get_param();
+        self.r = self.b.sub(&op.apply(&x0)?);
+        self.r_hat = self.r.clone();
+        self.p = x0.zero_like();
+        self.v = x0.zero_like();
+        self.rho = 1.0;
+        self.alpha = 1.0;
+        self.omega = 1.0;
+        self.b_norm = self.b.norm();
+        self.residual = self.r.norm();
+        Ok(Some(ArgminIterData::new().cost(self.residual)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let mut rho = self.r_hat.dot(&self.r);
+        if rho.abs() <= std::f64::EPSILON * self.r_hat.norm() * self.residual {
+            // breakdown: restart with the current residual as shadow residual
+            self.r_hat = self.r.clone();
+            self.p = self.r.clone();
+            rho = self.r.dot(&self.r);
+        } else {
+            let beta = (rho / self.rho) * (self.alpha / self.omega);
+            self.p = self
+                .r
+                .scaled_add(&beta, &self.p.scaled_add(&(-self.omega), &self.v));
+        }
+        self.rho = rho;
+
+        self.v = op.apply(&self.p)?;
+        self.alpha = rho / self.r_hat.dot(&self.v);
+        let x = state.get_param().scaled_add(&self.alpha, &self.p);
+        let s = self.r.scaled_add(&(-self.alpha), &self.v);
+
+        let s_norm = s.norm();
+        let x = if s_norm <= self.tol * self.b_norm {
+            self.r = s;
+            self.residual = s_norm;
+            x
+        } else {
+            let t = op.apply(&s)?;
+            self.omega = t.dot(&s) / t.dot(&t);
+            self.r = s.scaled_add(&(-self.omega), &t);
+            self.residual = self.r.norm();
+            x.scaled_add(&self.omega, &s)
+        };
+
+        Ok(ArgminIterData::new()
+            .param(x)
+            .cost(self.residual)
+            .kv(make_kv!(
+                "residual" => self.residual;
+                "alpha" => self.alpha;
+                "omega" => self.omega;
+            )))
+    }
+
+    fn terminate(&mut self, _state: &IterState<O>) -> TerminationReason {
+        if self.residual <= self.tol * self.b_norm {
+            TerminationReason::TargetPrecisionReached
+        } else {
+            TerminationReason::NotTerminated
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(bicgstab, BiCGSTAB<Vec<f64>>);
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct NonSymmetric {}
+
+    impl ArgminOp for NonSymmetric {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![
+                4.0 * p[0] + 1.0 * p[1] - 1.0 * p[2],
+                -2.0 * p[0] + 5.0 * p[1] + 1.0 * p[2],
+                1.0 * p[0] - 3.0 * p[1] + 6.0 * p[2],
+            ])
+        }
+    }
+
+    #[test]
+    fn test_bicgstab() {
+        let b = vec![1.0, 2.0, 3.0];
+        let solver = BiCGSTAB::new(b.clone()).unwrap();
+        let res = Executor::new(NonSymmetric {}, solver, vec![0.0; 3])
+            .max_iters(20)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+        let ax = NonSymmetric {}.apply(&res.state.get_best_param()).unwrap();
+        for (a, b) in ax.iter().zip(b.iter()) {
+            assert!((a - b).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_tol() {
+        assert!(BiCGSTAB::new(vec![1.0]).unwrap().tol(0.0).is_err());
+    }
+}
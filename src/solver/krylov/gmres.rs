@@ -0,0 +1,312 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Youcef Saad and Martin H. Schultz (1986). GMRES: A Generalized Minimal Residual Algorithm
+//! for Solving Nonsymmetric Linear Systems. SIAM Journal on Scientific and Statistical Computing
+//! 7(3), 856-869.
+//!
+//! [1] Yousef Saad (2003). Iterative Methods for Sparse Linear Systems. 2nd edition.
+//! SIAM. ISBN 0-89871-534-2.
+
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Restarted generalized minimal residual method GMRES(m) for systems of linear equations with a
+/// general (non-symmetric) matrix.
+///
+/// Each iteration extends an orthonormal basis of the Krylov subspace by one vector (Arnoldi
+/// process with modified Gram-Schmidt) and computes the parameter vector which minimizes the
+/// norm of the residual over this subspace. The least squares problems are solved incrementally
+/// with Givens rotations. After `m` iterations the basis is discarded and the method is restarted
+/// from the current parameter vector, which limits the memory requirements to `m + 1` vectors.
+///
+/// The norm of the residual is reported as cost and as `residual` in the key-value store, together
+/// with the number of completed restart cycles (`cycle`).
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/gmres.rs)
+///
+/// # References:
+///
+/// [0] Youcef Saad and Martin H. Schultz (1986). GMRES: A Generalized Minimal Residual Algorithm
+/// for Solving Nonsymmetric Linear Systems. SIAM Journal on Scientific and Statistical Computing
+/// 7(3), 856-869.
+///
+/// [1] Yousef Saad (2003). Iterative Methods for Sparse Linear Systems. 2nd edition.
+/// SIAM. ISBN 0-89871-534-2.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct GMRES<P> {
+    /// b (right hand side)
+    b: P,
+    /// restart length
+    m: usize,
+    /// relative tolerance
+    tol: f64,
+    /// norm of b
+    b_norm: f64,
+    /// parameter vector at the beginning of the current cycle
+    x0: P,
+    /// orthonormal basis of the Krylov subspace
+    v: Vec<P>,
+    /// columns of the triangular factor of the Hessenberg matrix
+    r: Vec<Vec<f64>>,
+    /// cosines of the Givens rotations
+    cs: Vec<f64>,
+    /// sines of the Givens rotations
+    sn: Vec<f64>,
+    /// rotated right hand side of the least squares problem
+    s: Vec<f64>,
+    /// norm of the residual
+    residual: f64,
+    /// number of completed cycles
+    cycle: u64,
+}
+
+impl<P: Clone + Default> GMRES<P> {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// `b`: right hand side of `A * x = b`
+    ///
+    /// `m`: number of iterations after which the method is restarted
+    pub fn new(b: P, m: usize) -> Result<Self, Error> {
+        if m == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "GMRES: m must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(GMRES {
+            b,
+            m,
+            tol: 1e-10,
+            b_norm: std::f64::NAN,
+            x0: P::default(),
+            v: vec![],
+            r: vec![],
+            cs: vec![],
+            sn: vec![],
+            s: vec![],
+            residual: std::f64::NAN,
+            cycle: 0,
+        })
+    }
+
+    /// Set relative tolerance `||b - A * x|| <= tol * ||b||` (default: 1e-10)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "GMRES: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+}
+
+impl<P> GMRES<P>
+where
+    P: Clone + ArgminSub<P, P> + ArgminMul<f64, P> + ArgminNorm<f64>,
+{
+    /// Starts a new cycle at `x0`
+    fn restart<O: ArgminOp<Param = P, Output = P>>(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        x0: P,
+    ) -> Result<(), Error> {
+        let r0 = self.b.sub(&op.apply(&x0)?);
+        let beta = r0.norm();
+        self.x0 = x0;
+        self.v = if beta > 0.0 {
+            vec![r0.mul(&(1.0 / beta))]
+        } else {
+            vec![]
+        };
+        self.r = vec![];
+        self.cs = vec![];
+        self.sn = vec![];
+        self.s = vec![beta];
+        self.residual = beta;
+        Ok(())
+    }
+}
+
+impl<P, O> Solver<O> for GMRES<P>
+where
+    O: ArgminOp<Param = P, Output = P>,
+    P: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminDot<P, f64>
+        + ArgminSub<P, P>
+        + ArgminScaledAdd<P, f64, P>
+        + ArgminMul<f64, P>
+        + ArgminNorm<f64>,
+{
+    const NAME: &'static str = "GMRES";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        self.b_norm = self.b.norm();
+        self.cycle = 0;
+        self.restart(op, state.get_param())?;
+        Ok(Some(ArgminIterData::new().cost(self.residual)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let j = self.r.len();
+
+        // Arnoldi step
+        let mut w = op.apply(&self.v[j])?;
+        let mut h: Vec<f64> = Vec::with_capacity(j + 2);
+        for vi in self.v.iter() {
+            let hij = w.dot(vi);
+            w = w.scaled_add(&(-hij), vi);
+            h.push(hij);
+        }
+        let h_next = w.norm();
+
+        // apply previous rotations and compute a new one which eliminates h_next
+        for i in 0..j {
+            let tmp = self.cs[i] * h[i] + self.sn[i] * h[i + 1];
+            h[i + 1] = -self.sn[i] * h[i] + self.cs[i] * h[i + 1];
+            h[i] = tmp;
+        }
+        let rho = h[j].hypot(h_next);
+        let (c, s) = if rho > 0.0 {
+            (h[j] / rho, h_next / rho)
+        } else {
+            (1.0, 0.0)
+        };
+        h[j] = rho;
+        self.cs.push(c);
+        self.sn.push(s);
+        self.s.push(-s * self.s[j]);
+        self.s[j] *= c;
+        self.r.push(h);
+        self.residual = self.s[j + 1].abs();
+
+        // solve the triangular system and update the parameter vector
+        let k = self.r.len();
+        let mut y = vec![0.0; k];
+        for i in (0..k).rev() {
+            let sum: f64 = ((i + 1)..k).map(|l| self.r[l][i] * y[l]).sum();
+            y[i] = (self.s[i] - sum) / self.r[i][i];
+        }
+        let x = self
+            .v
+            .iter()
+            .zip(y.iter())
+            .fold(self.x0.clone(), |x, (vi, yi)| x.scaled_add(yi, vi));
+
+        let residual = self.residual;
+        let cycle = self.cycle;
+        if h_next <= std::f64::EPSILON * rho.max(std::f64::MIN_POSITIVE) {
+            // the Krylov subspace is invariant under A: x is the exact solution
+            self.residual = 0.0;
+        } else if k == self.m {
+            self.cycle += 1;
+            self.restart(op, x.clone())?;
+        } else {
+            self.v.push(w.mul(&(1.0 / h_next)));
+        }
+
+        Ok(ArgminIterData::new()
+            .param(x)
+            .cost(residual)
+            .kv(make_kv!("residual" => residual; "cycle" => cycle;)))
+    }
+
+    fn terminate(&mut self, _state: &IterState<O>) -> TerminationReason {
+        if self.residual <= self.tol * self.b_norm {
+            TerminationReason::TargetPrecisionReached
+        } else {
+            TerminationReason::NotTerminated
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(gmres, GMRES<Vec<f64>>);
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct NonSymmetric {}
+
+    impl ArgminOp for NonSymmetric {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![
+                4.0 * p[0] + 1.0 * p[1] - 1.0 * p[2],
+                -2.0 * p[0] + 5.0 * p[1] + 1.0 * p[2],
+                1.0 * p[0] - 3.0 * p[1] + 6.0 * p[2],
+            ])
+        }
+    }
+
+    fn check_solution(x: &[f64]) {
+        let ax = NonSymmetric {}.apply(&x.to_vec()).unwrap();
+        for (a, b) in ax.iter().zip([1.0, 2.0, 3.0].iter()) {
+            assert!((a - b).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_full() {
+        let solver = GMRES::new(vec![1.0, 2.0, 3.0], 3).unwrap();
+        let res = Executor::new(NonSymmetric {}, solver, vec![0.0; 3])
+            .max_iters(10)
+            .run()
+            .unwrap();
+        check_solution(&res.state.get_best_param());
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+        assert!(res.state.get_iter() <= 3);
+    }
+
+    #[test]
+    fn test_restarted() {
+        let solver = GMRES::new(vec![1.0, 2.0, 3.0], 1).unwrap();
+        let res = Executor::new(NonSymmetric {}, solver, vec![0.0; 3])
+            .max_iters(100)
+            .run()
+            .unwrap();
+        check_solution(&res.state.get_best_param());
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+    }
+
+    #[test]
+    fn test_parameters() {
+        assert!(GMRES::new(vec![1.0], 0).is_err());
+        assert!(GMRES::new(vec![1.0], 1).unwrap().tol(0.0).is_err());
+    }
+}
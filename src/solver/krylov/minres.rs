@@ -0,0 +1,254 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] C. C. Paige and M. A. Saunders (1975). Solution of Sparse Indefinite Systems of Linear
+//! Equations. SIAM Journal on Numerical Analysis 12(4), 617-629.
+//!
+//! [1] Yousef Saad (2003). Iterative Methods for Sparse Linear Systems. 2nd edition.
+//! SIAM. ISBN 0-89871-534-2.
+
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Minimal residual method (MINRES) for systems of linear equations with a symmetric, possibly
+/// indefinite, matrix.
+///
+/// The symmetric Lanczos process is combined with Givens rotations such that the norm of the
+/// residual is minimized over the Krylov subspace while only a few vectors need to be stored. In
+/// contrast to `ConjugateGradient`, the matrix does not need to be positive definite. The matrix
+/// must however be symmetric, otherwise `GMRES` or `BiCGSTAB` should be used.
+///
+/// The norm of the residual is reported as cost and as `residual` in the key-value store. It is
+/// obtained from the recurrences and therefore does not require an additional product with the
+/// matrix.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/minres.rs)
+///
+/// # References:
+///
+/// [0] C. C. Paige and M. A. Saunders (1975). Solution of Sparse Indefinite Systems of Linear
+/// Equations. SIAM Journal on Numerical Analysis 12(4), 617-629.
+///
+/// [1] Yousef Saad (2003). Iterative Methods for Sparse Linear Systems. 2nd edition.
+/// SIAM. ISBN 0-89871-534-2.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct MINRES<P> {
+    /// b (right hand side)
+    b: P,
+    /// relative tolerance
+    tol: f64,
+    /// norm of b
+    b_norm: f64,
+    /// current Lanczos vector
+    v: P,
+    /// previous Lanczos vector
+    v_prev: P,
+    /// current off-diagonal element of the tridiagonal matrix
+    beta: f64,
+    /// search direction
+    w: P,
+    /// previous search direction
+    w_prev: P,
+    /// cosine of the last Givens rotation
+    cs: f64,
+    /// sine of the last Givens rotation
+    sn: f64,
+    /// rotated elements of the tridiagonal matrix
+    dbar: f64,
+    /// rotated elements of the tridiagonal matrix
+    epsln: f64,
+    /// norm of the residual (up to its sign)
+    phibar: f64,
+}
+
+impl<P: Clone + Default> MINRES<P> {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// `b`: right hand side of `A * x = b`
+    pub fn new(b: P) -> Result<Self, Error> {
+        Ok(MINRES {
+            b,
+            tol: 1e-10,
+            b_norm: std::f64::NAN,
+            v: P::default(),
+            v_prev: P::default(),
+            beta: std::f64::NAN,
+            w: P::default(),
+            w_prev: P::default(),
+            cs: -1.0,
+            sn: 0.0,
+            dbar: 0.0,
+            epsln: 0.0,
+            phibar: std::f64::NAN,
+        })
+    }
+
+    /// Set relative tolerance `||b - A * x|| <= tol * ||b||` (default: 1e-10)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "MINRES: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+}
+
+impl<P, O> Solver<O> for MINRES<P>
+where
+    O: ArgminOp<Param = P, Output = P>,
+    P: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminDot<P, f64>
+        + ArgminSub<P, P>
+        + ArgminScaledAdd<P, f64, P>
+        + ArgminMul<f64, P>
+        + ArgminNorm<f64>
+        + ArgminZeroLike,
+{
+    const NAME: &'static str = "MINRES";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let x0 = state.get_param();
+        let r0 = self.b.sub(&op.apply(&x0)?);
+        self.beta = r0.norm();
+        self.v = if self.beta > 0.0 {
+            r0.mul(&(1.0 / self.beta))
+        } else {
+            r0
+        };
+        self.v_prev = x0.zero_like();
+        self.w = x0.zero_like();
+        self.w_prev = x0.zero_like();
+        self.cs = -1.0;
+        self.sn = 0.0;
+        self.dbar = 0.0;
+        self.epsln = 0.0;
+        self.phibar = self.beta;
+        self.b_norm = self.b.norm();
+        Ok(Some(ArgminIterData::new().cost(self.phibar)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        // Lanczos step
+        let av = op.apply(&self.v)?;
+        let alpha = self.v.dot(&av);
+        let p = av
+            .scaled_add(&(-alpha), &self.v)
+            .scaled_add(&(-self.beta), &self.v_prev);
+        let beta = p.norm();
+
+        // apply the previous rotation and compute a new one
+        let oldeps = self.epsln;
+        let delta = self.cs * self.dbar + self.sn * alpha;
+        let gbar = self.sn * self.dbar - self.cs * alpha;
+        self.epsln = self.sn * beta;
+        self.dbar = -self.cs * beta;
+        let gamma = gbar.hypot(beta).max(std::f64::EPSILON);
+        self.cs = gbar / gamma;
+        self.sn = beta / gamma;
+        let phi = self.cs * self.phibar;
+        self.phibar *= self.sn;
+
+        // update search directions and parameter vector
+        let w = self
+            .v
+            .scaled_add(&(-oldeps), &self.w_prev)
+            .scaled_add(&(-delta), &self.w)
+            .mul(&(1.0 / gamma));
+        self.w_prev = std::mem::replace(&mut self.w, w);
+        let x = state.get_param().scaled_add(&phi, &self.w);
+
+        // next Lanczos vector
+        let v = if beta > 0.0 { p.mul(&(1.0 / beta)) } else { p };
+        self.v_prev = std::mem::replace(&mut self.v, v);
+        self.beta = beta;
+
+        let residual = self.phibar.abs();
+        Ok(ArgminIterData::new()
+            .param(x)
+            .cost(residual)
+            .kv(make_kv!("residual" => residual;)))
+    }
+
+    fn terminate(&mut self, _state: &IterState<O>) -> TerminationReason {
+        if self.phibar.abs() <= self.tol * self.b_norm {
+            TerminationReason::TargetPrecisionReached
+        } else {
+            TerminationReason::NotTerminated
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(minres, MINRES<Vec<f64>>);
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Indefinite {}
+
+    impl ArgminOp for Indefinite {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![
+                2.0 * p[0] + 1.0 * p[1],
+                1.0 * p[0] - 3.0 * p[1] + 1.0 * p[2],
+                1.0 * p[1] + 1.0 * p[2] + 2.0 * p[3],
+                2.0 * p[2] - 1.0 * p[3],
+            ])
+        }
+    }
+
+    #[test]
+    fn test_minres() {
+        let b = vec![1.0, 2.0, 3.0, 4.0];
+        let solver = MINRES::new(b.clone()).unwrap();
+        let res = Executor::new(Indefinite {}, solver, vec![0.0; 4])
+            .max_iters(20)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+        assert!(res.state.get_iter() <= 5);
+        let ax = Indefinite {}.apply(&res.state.get_best_param()).unwrap();
+        for (a, b) in ax.iter().zip(b.iter()) {
+            assert!((a - b).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_tol() {
+        assert!(MINRES::new(vec![1.0]).unwrap().tol(0.0).is_err());
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Krylov subspace methods for linear systems `A * x = b`
+//!
+//! * [GMRES(m)](gmres/struct.GMRES.html): general (non-symmetric) systems
+//! * [BiCGSTAB](bicgstab/struct.BiCGSTAB.html): general (non-symmetric) systems
+//! * [MINRES](minres/struct.MINRES.html): symmetric (possibly indefinite) systems
+//!
+//! Just like for `ConjugateGradient`, `ArgminOp::apply` computes the product `A * x`, therefore
+//! the matrix `A` never needs to be formed explicitly. The right hand side `b` is passed to the
+//! constructor of the solver. The norm of the residual `||b - A * x||` is reported as cost and as
+//! `residual` in the key-value store in every iteration. The solvers terminate once the residual
+//! falls below `tol * ||b||`.
+//!
+//! # References:
+//!
+//! [0] Yousef Saad (2003). Iterative Methods for Sparse Linear Systems. 2nd edition.
+//! SIAM. ISBN 0-89871-534-2.
+
+/// BiCGSTAB
+pub mod bicgstab;
+/// GMRES(m)
+pub mod gmres;
+/// MINRES
+pub mod minres;
+
+pub use self::bicgstab::*;
+pub use self::gmres::*;
+pub use self::minres::*;
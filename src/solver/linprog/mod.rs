@@ -0,0 +1,55 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Solvers for linear programs
+//!
+//! ```text
+//! min c^T x  subject to  A_eq x = b_eq,  A_ub x <= b_ub,  x >= 0
+//! ```
+//!
+//! The objective is provided via `ArgminOp`: `apply` returns `c^T x` and `gradient` returns `c`.
+//! The linear constraints are passed to the solvers directly. Infeasible and unbounded problems
+//! are reported by returning a `LinProgError`.
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+/// Two-phase revised simplex method
+pub mod simplex;
+
+pub use self::simplex::*;
+
+use std::fmt;
+
+/// Error returned if a linear program has no optimal solution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LinProgError {
+    /// There is no point satisfying all constraints
+    Infeasible,
+    /// The objective is unbounded below on the feasible set
+    Unbounded,
+}
+
+impl fmt::Display for LinProgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinProgError::Infeasible => write!(f, "Linear program error: problem is infeasible."),
+            LinProgError::Unbounded => write!(f, "Linear program error: problem is unbounded."),
+        }
+    }
+}
+
+impl std::error::Error for LinProgError {
+    fn description(&self) -> &str {
+        match self {
+            LinProgError::Infeasible => "No point satisfies all constraints.",
+            LinProgError::Unbounded => "The objective function is unbounded below.",
+        }
+    }
+}
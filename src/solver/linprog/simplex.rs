@@ -0,0 +1,522 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] Robert G. Bland (1977). New Finite Pivoting Rules for the Simplex Method.
+//! Mathematics of Operations Research 2(2), 103-107.
+
+use super::LinProgError;
+use crate::linalg;
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// Two-phase revised simplex method
+///
+/// Solves
+///
+/// ```text
+/// min c^T x  subject to  A_eq x = b_eq,  A_ub x <= b_ub,  x >= 0
+/// ```
+///
+/// where `c` is obtained from `ArgminOp::gradient` at the initial parameter vector (which only
+/// determines the number of variables). The problem is transformed into standard form by adding a
+/// slack variable to each inequality. Rows for which no slack variable can serve as initial basic
+/// variable get an artificial variable, whose sum is minimized in phase one to find a feasible
+/// basis. Phase two then minimizes the actual objective. Each iteration performs a single pivot;
+/// the entering and leaving variables are chosen according to Bland's rule (smallest index),
+/// which prevents cycling on degenerate problems.
+///
+/// The basis matrix is factorized anew in every iteration, therefore this solver is meant for
+/// small, dense problems.
+///
+/// If the problem is infeasible or unbounded, `LinProgError::Infeasible` or
+/// `LinProgError::Unbounded` is returned. As long as no feasible point is known (phase one), the
+/// reported cost is `INFINITY`. The current phase as well as the entering and leaving variables
+/// are reported in the key-value store.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/linprog.rs)
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+///
+/// [1] Robert G. Bland (1977). New Finite Pivoting Rules for the Simplex Method.
+/// Mathematics of Operations Research 2(2), 103-107.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+pub struct RevisedSimplex {
+    /// equality constraints `A_eq x = b_eq`
+    equality: Option<(Vec<Vec<f64>>, Vec<f64>)>,
+    /// inequality constraints `A_ub x <= b_ub`
+    inequality: Option<(Vec<Vec<f64>>, Vec<f64>)>,
+    /// tolerance
+    tol: f64,
+    /// constraint matrix of the standard form problem (including slack and artificial variables)
+    a: Vec<Vec<f64>>,
+    /// right-hand side of the standard form problem
+    b: Vec<f64>,
+    /// cost vector of the standard form problem
+    c: Vec<f64>,
+    /// number of variables of the original problem
+    num_vars: usize,
+    /// index of the first artificial variable
+    first_artificial: usize,
+    /// indices of the basic variables
+    basis: Vec<usize>,
+    /// current phase (1 or 2)
+    phase: u64,
+}
+
+impl RevisedSimplex {
+    /// Constructor
+    pub fn new() -> Self {
+        RevisedSimplex {
+            tol: 1e-9,
+            phase: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Set equality constraints `A_eq x = b_eq` (`a` is given row-wise)
+    pub fn equality(mut self, a: Vec<Vec<f64>>, b: Vec<f64>) -> Result<Self, Error> {
+        check_constraints("A_eq", &a, &b)?;
+        self.equality = Some((a, b));
+        Ok(self)
+    }
+
+    /// Set inequality constraints `A_ub x <= b_ub` (`a` is given row-wise)
+    pub fn inequality(mut self, a: Vec<Vec<f64>>, b: Vec<f64>) -> Result<Self, Error> {
+        check_constraints("A_ub", &a, &b)?;
+        self.inequality = Some((a, b));
+        Ok(self)
+    }
+
+    /// Set tolerance used for reduced costs, pivots and the feasibility check (default: 1e-9)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "RevisedSimplex: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Indices of the current basic variables. Indices `>= n` refer to slack variables, in the
+    /// order of the inequality constraints.
+    pub fn basis(&self) -> &[usize] {
+        &self.basis
+    }
+
+    /// Cost of variable `j` in the current phase
+    fn phase_cost(&self, j: usize) -> f64 {
+        match self.phase {
+            1 if j >= self.first_artificial => 1.0,
+            1 => 0.0,
+            _ => self.c[j],
+        }
+    }
+
+    /// Column `j` of the constraint matrix
+    fn column(&self, j: usize) -> Vec<f64> {
+        self.a.iter().map(|row| row[j]).collect()
+    }
+
+    /// Basis matrix and its transpose
+    fn basis_matrix(&self) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        let bm: Vec<Vec<f64>> = self
+            .a
+            .iter()
+            .map(|row| self.basis.iter().map(|&j| row[j]).collect())
+            .collect();
+        let bt = (0..self.basis.len())
+            .map(|k| bm.iter().map(|row| row[k]).collect())
+            .collect();
+        (bm, bt)
+    }
+
+    /// Variables of the original problem for the basic solution `xb`
+    fn param<P: FromIterator<f64>>(&self, xb: &[f64]) -> P {
+        let mut x = vec![0.0; self.num_vars];
+        for (&j, &v) in self.basis.iter().zip(xb.iter()) {
+            if j < self.num_vars {
+                x[j] = v;
+            }
+        }
+        x.into_iter().collect()
+    }
+
+    /// Cost of the basic solution `xb`; `INFINITY` during phase one.
+    fn cost<O>(&self, op: &mut OpWrapper<O>, x: &O::Param) -> Result<f64, Error>
+    where
+        O: ArgminOp<Output = f64>,
+    {
+        if self.phase == 1 {
+            return Ok(std::f64::INFINITY);
+        }
+        op.apply(x)
+    }
+
+    /// Checks whether phase one found a feasible point, moves remaining (zero) artificial
+    /// variables out of the basis and removes redundant equality constraints.
+    fn end_phase_one(&mut self, xb: &[f64]) -> Result<(), Error> {
+        let infeasibility: f64 = self
+            .basis
+            .iter()
+            .zip(xb.iter())
+            .filter(|(&j, _)| j >= self.first_artificial)
+            .map(|(_, v)| v)
+            .sum();
+        let scale = 1.0 + self.b.iter().fold(0.0, |acc: f64, v| acc.max(v.abs()));
+        if infeasibility > self.tol * scale {
+            return Err(Error::from_boxed_compat(Box::new(LinProgError::Infeasible)));
+        }
+        let mut r = 0;
+        while r < self.basis.len() {
+            if self.basis[r] < self.first_artificial {
+                r += 1;
+                continue;
+            }
+            // row `r` of `B^-1 A`
+            let (_, bt) = self.basis_matrix();
+            let mut e = vec![0.0; self.basis.len()];
+            e[r] = 1.0;
+            let w = linalg::solve(&bt, &e)?;
+            let pivot = (0..self.first_artificial)
+                .filter(|j| !self.basis.contains(j))
+                .find(|&j| dot(&w, &self.column(j)).abs() > self.tol);
+            match pivot {
+                Some(j) => {
+                    self.basis[r] = j;
+                    r += 1;
+                }
+                None => {
+                    self.a.remove(r);
+                    self.b.remove(r);
+                    self.basis.remove(r);
+                }
+            }
+        }
+        self.phase = 2;
+        Ok(())
+    }
+}
+
+/// Checks that all rows of `a` have the same length and that `b` has one entry per row
+fn check_constraints(name: &str, a: &[Vec<f64>], b: &[f64]) -> Result<(), Error> {
+    if a.len() != b.len() || a.iter().any(|row| row.len() != a[0].len()) {
+        return Err(ArgminError::InvalidParameter {
+            text: format!(
+                "RevisedSimplex: rows of {} must have equal length and match the right-hand side.",
+                name
+            ),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+impl<O> Solver<O> for RevisedSimplex
+where
+    O: ArgminOp<Output = f64>,
+    O::Param: FromIterator<f64>,
+    for<'a> &'a O::Param: IntoIterator<Item = &'a f64>,
+{
+    const NAME: &'static str = "Revised Simplex";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let c: Vec<f64> = op
+            .gradient(&state.get_param())?
+            .into_iter()
+            .cloned()
+            .collect();
+        let n = c.len();
+        let (a_ub, b_ub) = self.inequality.clone().unwrap_or_default();
+        let (a_eq, b_eq) = self.equality.clone().unwrap_or_default();
+        if a_ub.iter().chain(a_eq.iter()).any(|row| row.len() != n) {
+            return Err(ArgminError::InvalidParameter {
+                text: "RevisedSimplex: number of columns of the constraints does not match the parameter vector."
+                    .to_string(),
+            }
+            .into());
+        }
+
+        // rows of the standard form problem: inequalities (with slack) followed by equalities.
+        // Rows are negated if necessary to obtain a nonnegative right-hand side.
+        let m_ub = b_ub.len();
+        let m = m_ub + b_eq.len();
+        let mut rows: Vec<(Vec<f64>, f64, bool)> = Vec::with_capacity(m);
+        for (i, (row, bi)) in a_ub.into_iter().zip(b_ub).enumerate() {
+            let mut r = row;
+            r.extend((0..m_ub).map(|k| if k == i { 1.0 } else { 0.0 }));
+            rows.push((r, bi, true));
+        }
+        for (row, bi) in a_eq.into_iter().zip(b_eq) {
+            let mut r = row;
+            r.resize(n + m_ub, 0.0);
+            rows.push((r, bi, false));
+        }
+
+        self.first_artificial = n + m_ub;
+        let needs_artificial: Vec<bool> = rows
+            .iter()
+            .map(|(_, bi, is_ub)| !is_ub || *bi < 0.0)
+            .collect();
+        let num_artificial = needs_artificial.iter().filter(|x| **x).count();
+        self.a = Vec::with_capacity(m);
+        self.b = Vec::with_capacity(m);
+        self.basis = Vec::with_capacity(m);
+        let mut next_artificial = self.first_artificial;
+        for (i, ((mut row, mut bi, _), artificial)) in
+            rows.into_iter().zip(needs_artificial).enumerate()
+        {
+            if bi < 0.0 {
+                row.iter_mut().for_each(|v| *v = -*v);
+                bi = -bi;
+            }
+            row.resize(self.first_artificial + num_artificial, 0.0);
+            if artificial {
+                row[next_artificial] = 1.0;
+                self.basis.push(next_artificial);
+                next_artificial += 1;
+            } else {
+                self.basis.push(n + i);
+            }
+            self.a.push(row);
+            self.b.push(bi);
+        }
+        self.c = c;
+        self.c.resize(self.first_artificial + num_artificial, 0.0);
+        self.num_vars = n;
+        self.phase = if num_artificial > 0 { 1 } else { 2 };
+
+        let (bm, _) = self.basis_matrix();
+        let xb = linalg::solve(&bm, &self.b)?;
+        let param: O::Param = self.param(&xb);
+        let cost = self.cost(op, &param)?;
+        Ok(Some(ArgminIterData::new().param(param).cost(cost)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let phase = self.phase;
+        let (bm, bt) = self.basis_matrix();
+        let xb = linalg::solve(&bm, &self.b)?;
+        let cb: Vec<f64> = self.basis.iter().map(|&j| self.phase_cost(j)).collect();
+        let y = linalg::solve(&bt, &cb)?;
+
+        // Bland's rule: the nonbasic variable with the smallest index and negative reduced cost
+        // enters the basis. Artificial variables never reenter in phase two.
+        let num_columns = if phase == 1 {
+            self.c.len()
+        } else {
+            self.first_artificial
+        };
+        let entering = (0..num_columns)
+            .filter(|j| !self.basis.contains(j))
+            .find(|&j| self.phase_cost(j) - dot(&y, &self.column(j)) < -self.tol);
+
+        let (entering, leaving) = match entering {
+            Some(j) => {
+                let u = linalg::solve(&bm, &self.column(j))?;
+                // ratio test; ties are broken by the smallest index of the basic variable
+                let mut leaving: Option<(usize, f64)> = None;
+                for (r, (&ur, &xr)) in u.iter().zip(xb.iter()).enumerate() {
+                    if ur <= self.tol {
+                        continue;
+                    }
+                    let ratio = xr / ur;
+                    leaving = match leaving {
+                        Some((l, best))
+                            if ratio > best + self.tol
+                                || (ratio > best - self.tol && self.basis[l] < self.basis[r]) =>
+                        {
+                            Some((l, best))
+                        }
+                        _ => Some((r, ratio)),
+                    };
+                }
+                let (r, _) = leaving
+                    .ok_or_else(|| Error::from_boxed_compat(Box::new(LinProgError::Unbounded)))?;
+                let leaving = self.basis[r];
+                self.basis[r] = j;
+                (j as i64, leaving as i64)
+            }
+            None if phase == 1 => {
+                self.end_phase_one(&xb)?;
+                (-1, -1)
+            }
+            None => {
+                let param: O::Param = self.param(&xb);
+                let cost = self.cost(op, &param)?;
+                return Ok(ArgminIterData::new()
+                    .param(param)
+                    .cost(cost)
+                    .kv(make_kv!("phase" => phase;))
+                    .termination_reason(TerminationReason::TargetPrecisionReached));
+            }
+        };
+
+        let (bm, _) = self.basis_matrix();
+        let xb = linalg::solve(&bm, &self.b)?;
+        let param: O::Param = self.param(&xb);
+        let cost = self.cost(op, &param)?;
+        Ok(ArgminIterData::new().param(param).cost(cost).kv(make_kv!(
+            "phase" => phase;
+            "entering" => entering;
+            "leaving" => leaving;
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(revised_simplex, RevisedSimplex);
+
+    /// c^T x
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Linear {
+        c: Vec<f64>,
+    }
+
+    impl ArgminOp for Linear {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(dot(&self.c, p))
+        }
+
+        fn gradient(&self, _p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(self.c.clone())
+        }
+    }
+
+    fn solve(c: Vec<f64>, solver: RevisedSimplex) -> Result<(Vec<f64>, f64), Error> {
+        let n = c.len();
+        let res = Executor::new(Linear { c }, solver, vec![0.0; n])
+            .max_iters(100)
+            .run()?;
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+        Ok((res.state.get_best_param(), res.state.get_best_cost()))
+    }
+
+    #[test]
+    fn test_inequality_constrained() {
+        let solver = RevisedSimplex::new()
+            .inequality(
+                vec![vec![1.0, 0.0], vec![0.0, 2.0], vec![3.0, 2.0]],
+                vec![4.0, 12.0, 18.0],
+            )
+            .unwrap();
+        let (x, cost) = solve(vec![-3.0, -5.0], solver).unwrap();
+        assert!((x[0] - 2.0).abs() < 1e-10);
+        assert!((x[1] - 6.0).abs() < 1e-10);
+        assert!((cost + 36.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_two_phases() {
+        // x + y = 4, x - y <= 2, x + 2y >= 1
+        let solver = RevisedSimplex::new()
+            .equality(vec![vec![1.0, 1.0]], vec![4.0])
+            .unwrap()
+            .inequality(vec![vec![1.0, -1.0], vec![-1.0, -2.0]], vec![2.0, -1.0])
+            .unwrap();
+        let (x, cost) = solve(vec![2.0, 3.0], solver).unwrap();
+        assert!((x[0] - 3.0).abs() < 1e-10);
+        assert!((x[1] - 1.0).abs() < 1e-10);
+        assert!((cost - 9.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        // Example of Beale/Chvatal on which the simplex method cycles with the largest coefficient
+        // rule
+        let solver = RevisedSimplex::new()
+            .inequality(
+                vec![
+                    vec![0.5, -5.5, -2.5, 9.0],
+                    vec![0.5, -1.5, -0.5, 1.0],
+                    vec![1.0, 0.0, 0.0, 0.0],
+                ],
+                vec![0.0, 0.0, 1.0],
+            )
+            .unwrap();
+        let (x, cost) = solve(vec![-10.0, 57.0, 9.0, 24.0], solver).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-10);
+        assert!((x[2] - 1.0).abs() < 1e-10);
+        assert!((cost + 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_redundant_equality() {
+        let solver = RevisedSimplex::new()
+            .equality(vec![vec![1.0, 1.0], vec![2.0, 2.0]], vec![1.0, 2.0])
+            .unwrap();
+        let (x, cost) = solve(vec![1.0, 2.0], solver).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-10);
+        assert!(x[1].abs() < 1e-10);
+        assert!((cost - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_infeasible() {
+        let solver = RevisedSimplex::new()
+            .inequality(vec![vec![1.0, 1.0], vec![-1.0, -1.0]], vec![1.0, -2.0])
+            .unwrap();
+        let err = solve(vec![1.0, 1.0], solver).unwrap_err();
+        assert_eq!(format!("{}", err), format!("{}", LinProgError::Infeasible));
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let solver = RevisedSimplex::new()
+            .inequality(vec![vec![1.0, -1.0]], vec![1.0])
+            .unwrap();
+        let err = solve(vec![-1.0, 0.0], solver).unwrap_err();
+        assert_eq!(format!("{}", err), format!("{}", LinProgError::Unbounded));
+    }
+
+    #[test]
+    fn test_constraint_dimensions() {
+        assert!(RevisedSimplex::new()
+            .equality(vec![vec![1.0, 1.0], vec![1.0]], vec![1.0, 2.0])
+            .is_err());
+        assert!(RevisedSimplex::new()
+            .inequality(vec![vec![1.0, 1.0]], vec![1.0, 2.0])
+            .is_err());
+    }
+}
@@ -5,15 +5,28 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+pub mod augmentedlagrangian;
+pub mod bracketing;
 pub mod brent;
+pub mod cmaes;
 pub mod conjugategradient;
+pub mod differentialevolution;
 pub mod gaussnewton;
+pub mod goldensectionsearch;
 pub mod gradientdescent;
+pub mod krylov;
 pub mod landweber;
 pub mod linesearch;
+pub mod linprog;
 pub mod neldermead;
 pub mod newton;
+pub mod nonlinearsystem;
 pub mod particleswarm;
+pub mod proximalgradient;
+pub mod qp;
 pub mod quasinewton;
+pub mod rootfinding;
 pub mod simulatedannealing;
+pub mod sqp;
+pub mod stochastic;
 pub mod trustregion;
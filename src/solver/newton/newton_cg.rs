@@ -12,14 +12,25 @@
 //! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
 //! Springer. ISBN 0-387-30303-0.
 
+use crate::hessian_vec_product::{DenseHessian, HessianVecProducts, MatrixFree};
+use crate::preconditioner::IdentityPreconditioner;
 use crate::prelude::*;
-use crate::solver::conjugategradient::ConjugateGradient;
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 
 /// The Newton-CG method (also called truncated Newton method) uses a modified CG to solve the
 /// Newton equations approximately. After a search direction is found, a line search is performed.
 ///
+/// The CG iterations can be preconditioned by providing a preconditioner (see
+/// `argmin::preconditioner`) via `preconditioner(...)`.
+///
+/// The Hessian only enters via products with vectors. By default, it is evaluated once per
+/// iteration and used for these products. In matrix-free mode (`matrix_free()`), the Hessian is
+/// never evaluated and the products are computed by
+/// `ArgminHessianVecProduct::hessian_vec_product`, or approximated by finite differences of the
+/// gradient if this is not implemented (see `argmin::hessian_vec_product`). The total number of
+/// Hessian-vector products is reported as `hessian_vec_products` in the `kv` of each iteration.
+///
 /// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/newton_cg.rs)
 ///
 /// # References:
@@ -28,19 +39,54 @@ use serde::{Deserialize, Serialize};
 /// Springer. ISBN 0-387-30303-0.
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
 #[derive(Clone)]
-pub struct NewtonCG<L> {
+pub struct NewtonCG<L, M = IdentityPreconditioner, H = DenseHessian> {
     /// line search
     linesearch: L,
     /// curvature_threshold
     curvature_threshold: f64,
+    /// preconditioner of the CG iterations
+    precond: M,
+    /// number of Hessian-vector products
+    hvp_count: u64,
+    /// source of the Hessian-vector products
+    hessian_vec_products: std::marker::PhantomData<H>,
 }
 
-impl<L> NewtonCG<L> {
+impl<L> NewtonCG<L, IdentityPreconditioner> {
     /// Constructor
     pub fn new(linesearch: L) -> Self {
         NewtonCG {
             linesearch,
             curvature_threshold: 0.0,
+            precond: IdentityPreconditioner::new(),
+            hvp_count: 0,
+            hessian_vec_products: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<L, M> NewtonCG<L, M, DenseHessian> {
+    /// Only use Hessian-vector products instead of the Hessian (see `argmin::hessian_vec_product`)
+    pub fn matrix_free(self) -> NewtonCG<L, M, MatrixFree> {
+        NewtonCG {
+            linesearch: self.linesearch,
+            curvature_threshold: self.curvature_threshold,
+            precond: self.precond,
+            hvp_count: self.hvp_count,
+            hessian_vec_products: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<L, M, H> NewtonCG<L, M, H> {
+    /// Set preconditioner of the CG iterations
+    pub fn preconditioner<M2>(self, precond: M2) -> NewtonCG<L, M2, H> {
+        NewtonCG {
+            linesearch: self.linesearch,
+            curvature_threshold: self.curvature_threshold,
+            precond,
+            hvp_count: self.hvp_count,
+            hessian_vec_products: self.hessian_vec_products,
         }
     }
 
@@ -51,7 +97,7 @@ impl<L> NewtonCG<L> {
     }
 }
 
-impl<O, L> Solver<O> for NewtonCG<L>
+impl<O, L, M, H> Solver<O> for NewtonCG<L, M, H>
 where
     O: ArgminOp<Output = f64>,
     O::Param: Send
@@ -64,17 +110,11 @@ where
         + ArgminDot<O::Param, f64>
         + ArgminScaledAdd<O::Param, f64, O::Param>
         + ArgminMul<f64, O::Param>
-        + ArgminConj
         + ArgminZeroLike
         + ArgminNorm<f64>,
-    O::Hessian: Send
-        + Sync
-        + Clone
-        + SerializeAlias
-        + Default
-        + ArgminInv<O::Hessian>
-        + ArgminDot<O::Param, O::Param>,
     L: Clone + ArgminLineSearch<O::Param> + Solver<OpWrapper<O>>,
+    M: Clone + Preconditioner<O::Param> + SerializeAlias,
+    H: HessianVecProducts<O> + Clone + SerializeAlias,
 {
     const NAME: &'static str = "Newton-CG";
 
@@ -85,39 +125,37 @@ where
     ) -> Result<ArgminIterData<O>, Error> {
         let param = state.get_param();
         let grad = op.gradient(&param)?;
-        let hessian = op.hessian(&param)?;
-
-        // Solve CG subproblem
-        let cg_op: CGSubProblem<O::Param, O::Hessian> = CGSubProblem::new(hessian.clone());
-        let mut cg_op = OpWrapper::new(&cg_op);
-
-        let mut x_p = param.zero_like();
-        let mut x: O::Param = param.zero_like();
-        let mut cg = ConjugateGradient::new(grad.mul(&(-1.0)))?;
+        let hessian = H::hessian(op, &param, None)?;
 
-        let mut cg_state = IterState::new(x_p.clone());
-        cg.init(&mut cg_op, &cg_state)?;
+        // Solve the Newton equations H x = -g with a truncated CG method. The residual is
+        // r = H x + g.
         let grad_norm = grad.norm();
+        let tol = (0.5f64).min(grad_norm.sqrt()) * grad_norm;
+        let mut x: O::Param = param.zero_like();
+        let mut r = grad.clone();
+        let mut y = self.precond.precondition(&r)?;
+        let mut d = y.mul(&(-1.0));
+        let mut rty: f64 = r.dot(&y);
         for iter in 0.. {
-            let data = cg.next_iter(&mut cg_op, &cg_state)?;
-            x = data.get_param().unwrap();
-            let p = cg.p_prev();
-            let curvature = p.dot(&hessian.dot(&p));
+            let hd = H::product(op, &param, hessian.as_ref(), &d)?;
+            self.hvp_count += 1;
+            let curvature = d.dot(&hd);
             if curvature <= self.curvature_threshold {
                 if iter == 0 {
                     x = grad.mul(&(-1.0));
-                    break;
-                } else {
-                    x = x_p;
-                    break;
                 }
+                break;
             }
-            if data.get_cost().unwrap() <= (0.5f64).min(grad_norm.sqrt()) * grad_norm {
+            let alpha = rty / curvature;
+            x = x.scaled_add(&alpha, &d);
+            r = r.scaled_add(&alpha, &hd);
+            if r.norm() <= tol {
                 break;
             }
-            cg_state.param(x.clone());
-            cg_state.cost(data.get_cost().unwrap());
-            x_p = x.clone();
+            y = self.precond.precondition(&r)?;
+            let rty_n: f64 = r.dot(&y);
+            d = y.mul(&(-1.0)).scaled_add(&(rty_n / rty), &d);
+            rty = rty_n;
         }
 
         // perform line search
@@ -132,7 +170,7 @@ where
                     cost: next_cost,
                     ..
                 },
-        } = Executor::new(OpWrapper::new_from_op(&op), self.linesearch.clone(), param)
+        } = Executor::new(OpWrapper::new_from_op(op), self.linesearch.clone(), param)
             .grad(grad)
             .cost(state.get_cost())
             .ctrlc(false)
@@ -140,7 +178,10 @@ where
 
         op.consume_op(line_op);
 
-        Ok(ArgminIterData::new().param(next_param).cost(next_cost))
+        Ok(ArgminIterData::new()
+            .param(next_param)
+            .cost(next_cost)
+            .kv(make_kv!("hessian_vec_products" => self.hvp_count;)))
     }
 
     fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
@@ -152,42 +193,6 @@ where
     }
 }
 
-#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-#[derive(Clone, Default)]
-struct CGSubProblem<T, H> {
-    hessian: H,
-    phantom: std::marker::PhantomData<T>,
-}
-
-impl<T, H> CGSubProblem<T, H>
-where
-    T: Clone + Send + Sync,
-    H: Clone + Default + ArgminDot<T, T> + Send + Sync,
-{
-    /// constructor
-    pub fn new(hessian: H) -> Self {
-        CGSubProblem {
-            hessian,
-            phantom: std::marker::PhantomData,
-        }
-    }
-}
-
-impl<T, H> ArgminOp for CGSubProblem<T, H>
-where
-    T: Clone + Default + Send + Sync + SerializeAlias + DeserializeOwnedAlias,
-    H: Clone + Default + ArgminDot<T, T> + Send + Sync + SerializeAlias + DeserializeOwnedAlias,
-{
-    type Param = T;
-    type Output = T;
-    type Hessian = ();
-    type Jacobian = ();
-
-    fn apply(&self, p: &T) -> Result<T, Error> {
-        Ok(self.hessian.dot(&p))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,6 +200,4 @@ mod tests {
     use crate::test_trait_impl;
 
     test_trait_impl!(newton_cg, NewtonCG<MoreThuenteLineSearch<Vec<f64>>>);
-
-    test_trait_impl!(cg_subproblem, CGSubProblem<Vec<f64>, Vec<Vec<f64>>>);
 }
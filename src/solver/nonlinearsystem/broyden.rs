@@ -0,0 +1,302 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] C. G. Broyden (1965). A Class of Methods for Solving Nonlinear Simultaneous Equations.
+//! Mathematics of Computation 19(92), 577-593.
+//!
+//! [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use super::merit_backtracking;
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::default::Default;
+
+/// Update of the inverse Jacobian used by `Broyden`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BroydenUpdate {
+    /// "Good" Broyden update: rank one update of the Jacobian (applied to its inverse via
+    /// Sherman-Morrison)
+    Good,
+    /// "Bad" Broyden update: rank one update of the inverse Jacobian
+    Bad,
+}
+
+/// Broyden's method for systems of nonlinear equations
+///
+/// Quasi-Newton method which approximates the inverse Jacobian by rank one updates. The true
+/// Jacobian is only evaluated at the initial parameter vector and whenever the line search on
+/// `0.5 * ||F||^2` fails to make progress. Terminates if the norm of the residuals falls below
+/// `tol`.
+///
+/// The "good" update is skipped (and `update_skipped` is reported in the kv) whenever its
+/// denominator `dx^T H df` is small relative to `||dx|| ||H df||`, see `update_tol`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/nonlinearsystem.rs)
+///
+/// # References:
+///
+/// [0] C. G. Broyden (1965). A Class of Methods for Solving Nonlinear Simultaneous Equations.
+/// Mathematics of Computation 19(92), 577-593.
+///
+/// [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct Broyden<F, J> {
+    /// update formula
+    update: BroydenUpdate,
+    /// tolerance on the norm of the residuals
+    tol: f64,
+    /// relative tolerance on the denominator of the "good" update
+    update_tol: f64,
+    /// approximation of the inverse Jacobian
+    inv_jacobian: Option<J>,
+    /// residuals at the current parameter vector
+    residuals: Option<F>,
+    /// whether the inverse Jacobian was just recomputed from the true Jacobian
+    reset: bool,
+}
+
+impl<F, J> Broyden<F, J> {
+    /// Constructor
+    pub fn new(update: BroydenUpdate) -> Self {
+        Broyden {
+            update,
+            tol: std::f64::EPSILON.sqrt(),
+            update_tol: std::f64::EPSILON.sqrt(),
+            inv_jacobian: None,
+            residuals: None,
+            reset: true,
+        }
+    }
+
+    /// Set tolerance on the norm of the residuals
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Broyden: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Set relative tolerance on the denominator `dx^T H df` of the "good" update. The update is
+    /// skipped if `|dx^T H df| <= update_tol * ||dx|| ||H df||`.
+    pub fn update_tol(mut self, update_tol: f64) -> Result<Self, Error> {
+        if update_tol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Broyden: update_tol must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.update_tol = update_tol;
+        Ok(self)
+    }
+}
+
+impl<F, J> Default for Broyden<F, J> {
+    fn default() -> Broyden<F, J> {
+        Broyden::new(BroydenUpdate::Good)
+    }
+}
+
+impl<O> Solver<O> for Broyden<O::Output, O::Jacobian>
+where
+    O: ArgminOp,
+    O::Param: ArgminScaledAdd<O::Param, f64, O::Param>
+        + ArgminSub<O::Param, O::Param>
+        + ArgminMul<f64, O::Param>
+        + ArgminDot<O::Param, f64>
+        + ArgminDot<O::Output, O::Jacobian>,
+    O::Output: ArgminNorm<f64> + ArgminSub<O::Output, O::Output> + ArgminDot<O::Output, f64>,
+    O::Jacobian: ArgminInv<O::Jacobian>
+        + ArgminTranspose
+        + ArgminAdd<O::Jacobian, O::Jacobian>
+        + ArgminMul<f64, O::Jacobian>
+        + ArgminDot<O::Output, O::Param>
+        + ArgminDot<O::Param, O::Output>,
+{
+    const NAME: &'static str = "Broyden method";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        let residuals = op.apply(&param)?;
+        let cost = residuals.norm();
+        self.inv_jacobian = Some(op.jacobian(&param)?.inv()?);
+        self.residuals = Some(residuals);
+        self.reset = true;
+        Ok(Some(ArgminIterData::new().param(param).cost(cost)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let residuals = self.residuals.take().unwrap();
+        let inv_jacobian = self.inv_jacobian.take().unwrap();
+        let norm = residuals.norm();
+
+        let p = inv_jacobian.dot(&residuals).mul(&(-1.0));
+
+        let (new_param, new_residuals, new_norm) = match merit_backtracking(op, &param, &p, norm)? {
+            Some(step) => step,
+            None if !self.reset => {
+                // The approximation is too poor; restart from the true Jacobian.
+                self.inv_jacobian = Some(op.jacobian(&param)?.inv()?);
+                self.residuals = Some(residuals);
+                self.reset = true;
+                return Ok(ArgminIterData::new()
+                    .param(param)
+                    .cost(norm)
+                    .kv(make_kv!("jacobian_reset" => true; "update_skipped" => false;)));
+            }
+            None => {
+                return Err(ArgminError::ConditionViolated {
+                    text: "Broyden: line search failed to decrease ||F||; possibly stuck in \
+                               a local minimum of ||F||."
+                        .to_string(),
+                }
+                .into());
+            }
+        };
+
+        let dx = new_param.sub(&param);
+        let df = new_residuals.sub(&residuals);
+        let h_df: O::Param = inv_jacobian.dot(&df);
+        let u = dx.sub(&h_df);
+        let mut skipped = false;
+        let inv_jacobian = match self.update {
+            BroydenUpdate::Good => {
+                // H + (dx - H df) (H^T dx)^T / (dx^T H df)
+                let denom: f64 = dx.dot(&h_df);
+                let scale = (dx.dot(&dx) * h_df.dot(&h_df)).sqrt();
+                if denom.abs() <= self.update_tol * scale {
+                    // dx is (nearly) orthogonal to H df; the update would blow up H.
+                    skipped = true;
+                    inv_jacobian
+                } else {
+                    let ht_dx: O::Output = inv_jacobian.clone().t().dot(&dx);
+                    let outer: O::Jacobian = u.dot(&ht_dx);
+                    inv_jacobian.add(&outer.mul(&(1.0 / denom)))
+                }
+            }
+            BroydenUpdate::Bad => {
+                // H + (dx - H df) df^T / (df^T df)
+                let denom: f64 = df.dot(&df);
+                let outer: O::Jacobian = u.dot(&df);
+                inv_jacobian.add(&outer.mul(&(1.0 / denom)))
+            }
+        };
+        self.inv_jacobian = Some(inv_jacobian);
+        self.residuals = Some(new_residuals);
+        self.reset = false;
+
+        Ok(ArgminIterData::new()
+            .param(new_param)
+            .cost(new_norm)
+            .kv(make_kv!("jacobian_reset" => false; "update_skipped" => skipped;)))
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        if state.get_cost() < self.tol {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        TerminationReason::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(broyden, Broyden<Vec<f64>, Vec<Vec<f64>>>);
+
+    #[test]
+    fn test_tol() {
+        assert!(Broyden::<Vec<f64>, Vec<Vec<f64>>>::default()
+            .tol(0.0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_update_tol() {
+        let solver = Broyden::<Vec<f64>, Vec<Vec<f64>>>::default();
+        assert!(solver.clone().update_tol(-1.0).is_err());
+        assert!(solver.update_tol(0.0).is_ok());
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_solve() {
+        use crate::solver::nonlinearsystem::tests::{residual_norm, solve};
+
+        for update in &[BroydenUpdate::Good, BroydenUpdate::Bad] {
+            let (x, reason) = solve(Broyden::new(*update).tol(1e-10).unwrap());
+            assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+            assert!(residual_norm(&x) < 1e-10);
+        }
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_skip_good_update() {
+        use ndarray::{array, Array1, Array2};
+        #[cfg(feature = "serde1")]
+        use serde::{Deserialize, Serialize};
+
+        /// `F(x) = x`
+        #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+        #[derive(Clone, Default)]
+        struct Identity {}
+
+        impl ArgminOp for Identity {
+            type Param = Array1<f64>;
+            type Output = Array1<f64>;
+            type Hessian = ();
+            type Jacobian = Array2<f64>;
+
+            fn apply(&self, p: &Array1<f64>) -> Result<Array1<f64>, Error> {
+                Ok(p.clone())
+            }
+
+            fn jacobian(&self, _p: &Array1<f64>) -> Result<Array2<f64>, Error> {
+                Ok(Array2::eye(2))
+            }
+        }
+
+        let mut op = OpWrapper::new(&Identity {});
+        let mut solver = Broyden::new(BroydenUpdate::Good);
+        let state = IterState::new(array![1.0, 0.0]);
+        solver.init(&mut op, &state).unwrap();
+
+        // With this approximation the step is along (-1, -1), for which dx^T H df vanishes.
+        let inv_jacobian = array![[1.0, 0.0], [1.0, -2.0]];
+        solver.inv_jacobian = Some(inv_jacobian.clone());
+        solver.reset = false;
+        let data = solver.next_iter(&mut op, &state).unwrap();
+        let kv = data.get_kv();
+        let get = |key: &str| kv.kv.iter().find(|(k, _)| *k == key).unwrap().1.clone();
+        assert_eq!(get("jacobian_reset"), "false");
+        assert_eq!(get("update_skipped"), "true");
+        assert!(data.get_cost().unwrap() < 1.0);
+        assert_eq!(solver.inv_jacobian.unwrap(), inv_jacobian);
+    }
+}
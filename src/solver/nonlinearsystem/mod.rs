@@ -0,0 +1,116 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Solvers for square systems of nonlinear equations `F(x) = 0`
+//!
+//! Just like for `GaussNewton`, `ArgminOp::apply` returns the residual vector `F(x)` and
+//! `ArgminOp::jacobian` its Jacobian. The reported cost is the norm of the residuals. Steps are
+//! globalized by a backtracking line search on `0.5 * ||F(x)||^2`.
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] C. G. Broyden (1965). A Class of Methods for Solving Nonlinear Simultaneous Equations.
+//! Mathematics of Computation 19(92), 577-593.
+
+/// Broyden's method
+pub mod broyden;
+/// Newton-Raphson method
+pub mod newton_raphson;
+
+pub use self::broyden::*;
+pub use self::newton_raphson::*;
+
+use crate::prelude::*;
+
+/// Sufficient decrease parameter of the line search
+const ARMIJO_C: f64 = 1e-4;
+
+/// Maximum number of step size reductions of the line search
+const MAX_BACKTRACKS: usize = 30;
+
+/// Parameter vector, residuals and norm of the residuals after a successful line search
+type Step<O> = (<O as ArgminOp>::Param, <O as ArgminOp>::Output, f64);
+
+/// Backtracking line search on `phi(alpha) = 0.5 * ||F(x + alpha * p)||^2`.
+///
+/// Assumes that `p` is a (quasi-)Newton direction, i.e. `phi'(0) = -||F(x)||^2`. Returns the new
+/// parameter vector and its residuals, or `None` if no sufficient decrease could be achieved.
+fn merit_backtracking<O>(
+    op: &mut OpWrapper<O>,
+    x: &O::Param,
+    p: &O::Param,
+    norm0: f64,
+) -> Result<Option<Step<O>>, Error>
+where
+    O: ArgminOp,
+    O::Param: ArgminScaledAdd<O::Param, f64, O::Param>,
+    O::Output: ArgminNorm<f64>,
+{
+    let phi0 = 0.5 * norm0.powi(2);
+    let mut alpha = 1.0;
+    for _ in 0..MAX_BACKTRACKS {
+        let x_new = x.scaled_add(&alpha, p);
+        let f_new = op.apply(&x_new)?;
+        let norm = f_new.norm();
+        if 0.5 * norm.powi(2) <= (1.0 - 2.0 * ARMIJO_C * alpha) * phi0 {
+            return Ok(Some((x_new, f_new, norm)));
+        }
+        alpha *= 0.5;
+    }
+    Ok(None)
+}
+
+#[cfg(all(test, feature = "ndarrayl"))]
+mod tests {
+    use super::*;
+    use ndarray::{array, Array1, Array2};
+    #[cfg(feature = "serde1")]
+    use serde::{Deserialize, Serialize};
+
+    /// `x^2 + y^2 - 4 = 0` and `exp(x) + y - 1 = 0`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    pub(super) struct System {}
+
+    impl ArgminOp for System {
+        type Param = Array1<f64>;
+        type Output = Array1<f64>;
+        type Hessian = ();
+        type Jacobian = Array2<f64>;
+
+        fn apply(&self, p: &Array1<f64>) -> Result<Array1<f64>, Error> {
+            Ok(array![
+                p[0].powi(2) + p[1].powi(2) - 4.0,
+                p[0].exp() + p[1] - 1.0
+            ])
+        }
+
+        fn jacobian(&self, p: &Array1<f64>) -> Result<Array2<f64>, Error> {
+            Ok(array![[2.0 * p[0], 2.0 * p[1]], [p[0].exp(), 1.0]])
+        }
+    }
+
+    /// Solves `System` starting from `(1, 1)` and returns the solution and the termination reason
+    pub(super) fn solve<S: Solver<System>>(solver: S) -> (Array1<f64>, TerminationReason) {
+        let res = Executor::new(System {}, solver, array![1.0, 1.0])
+            .max_iters(50)
+            .run()
+            .unwrap();
+        (
+            res.state.get_best_param(),
+            res.state.get_termination_reason(),
+        )
+    }
+
+    /// Norm of the residuals of `System` at `p`
+    pub(super) fn residual_norm(p: &Array1<f64>) -> f64 {
+        System {}.apply(p).unwrap().norm()
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use super::merit_backtracking;
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::default::Default;
+
+/// Newton-Raphson method for systems of nonlinear equations
+///
+/// Computes the Newton step `p = -J^-1 F` in every iteration and takes the longest step `alpha *
+/// p` with `alpha = 1, 1/2, 1/4, ...` which sufficiently decreases `0.5 * ||F||^2`. Terminates if
+/// the norm of the residuals falls below `tol`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/nonlinearsystem.rs)
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct NewtonRaphson<F> {
+    /// tolerance on the norm of the residuals
+    tol: f64,
+    /// residuals at the current parameter vector
+    residuals: Option<F>,
+}
+
+impl<F> NewtonRaphson<F> {
+    /// Constructor
+    pub fn new() -> Self {
+        NewtonRaphson {
+            tol: std::f64::EPSILON.sqrt(),
+            residuals: None,
+        }
+    }
+
+    /// Set tolerance on the norm of the residuals
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Newton-Raphson: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+}
+
+impl<F> Default for NewtonRaphson<F> {
+    fn default() -> NewtonRaphson<F> {
+        NewtonRaphson::new()
+    }
+}
+
+impl<O> Solver<O> for NewtonRaphson<O::Output>
+where
+    O: ArgminOp,
+    O::Param: ArgminScaledAdd<O::Param, f64, O::Param> + ArgminMul<f64, O::Param>,
+    O::Output: ArgminNorm<f64>,
+    O::Jacobian: ArgminInv<O::Jacobian> + ArgminDot<O::Output, O::Param>,
+{
+    const NAME: &'static str = "Newton-Raphson method";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        let residuals = op.apply(&param)?;
+        let cost = residuals.norm();
+        self.residuals = Some(residuals);
+        Ok(Some(ArgminIterData::new().param(param).cost(cost)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let residuals = self.residuals.take().unwrap();
+        let jacobian = op.jacobian(&param)?;
+
+        let p = jacobian.inv()?.dot(&residuals).mul(&(-1.0));
+
+        match merit_backtracking(op, &param, &p, residuals.norm())? {
+            Some((new_param, new_residuals, norm)) => {
+                self.residuals = Some(new_residuals);
+                Ok(ArgminIterData::new().param(new_param).cost(norm))
+            }
+            None => Err(ArgminError::ConditionViolated {
+                text: "Newton-Raphson: line search failed to decrease ||F||; possibly stuck in a \
+                       local minimum of ||F||."
+                    .to_string(),
+            }
+            .into()),
+        }
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        if state.get_cost() < self.tol {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        TerminationReason::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(newton_raphson, NewtonRaphson<Vec<f64>>);
+
+    #[test]
+    fn test_tol() {
+        assert!(NewtonRaphson::<Vec<f64>>::new().tol(0.0).is_err());
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_solve() {
+        use crate::solver::nonlinearsystem::tests::{residual_norm, solve};
+
+        let (x, reason) = solve(NewtonRaphson::new().tol(1e-10).unwrap());
+        assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+        assert!(residual_norm(&x) < 1e-10);
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Amir Beck and Marc Teboulle (2009). A Fast Iterative Shrinkage-Thresholding Algorithm for
+//! Linear Inverse Problems. SIAM Journal on Imaging Sciences 2(1), 183-202.
+//!
+//! [1] Brendan O'Donoghue and Emmanuel Candès (2015). Adaptive Restart for Accelerated Gradient
+//! Schemes. Foundations of Computational Mathematics 15, 715-732.
+
+use super::{check_positive, check_shrink, prox_grad_step, ProximalOperator};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// Fast iterative shrinkage-thresholding algorithm (FISTA)
+///
+/// Accelerated proximal gradient method with backtracking on the smooth part (see the
+/// [module documentation](index.html)). The proximal gradient step is taken from an extrapolated
+/// point `y_k = x_k + (theta_{k-1} - 1) / theta_k * (x_k - x_{k-1})`. Optionally, the momentum is
+/// reset whenever the cost increases (adaptive restart). Terminates when the norm of the gradient
+/// mapping `||x_{k+1} - y_k|| / t` drops below `tol`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/proximalgradient.rs)
+///
+/// # References:
+///
+/// [0] Amir Beck and Marc Teboulle (2009). A Fast Iterative Shrinkage-Thresholding Algorithm for
+/// Linear Inverse Problems. SIAM Journal on Imaging Sciences 2(1), 183-202.
+///
+/// [1] Brendan O'Donoghue and Emmanuel Candès (2015). Adaptive Restart for Accelerated Gradient
+/// Schemes. Foundations of Computational Mathematics 15, 715-732.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct FISTA<G, P> {
+    /// proximal operator of the nonsmooth part
+    prox: G,
+    /// current step length
+    step: f64,
+    /// factor by which the step length is reduced during backtracking
+    shrink: f64,
+    /// tolerance of the norm of the gradient mapping
+    tol: f64,
+    /// adaptive restart
+    restart: bool,
+    /// momentum parameter
+    theta: f64,
+    /// extrapolated point
+    y: Option<P>,
+}
+
+impl<G, P> FISTA<G, P> {
+    /// Constructor
+    pub fn new(prox: G) -> Self {
+        FISTA {
+            prox,
+            step: 1.0,
+            shrink: 0.5,
+            tol: 1e-8,
+            restart: false,
+            theta: 1.0,
+            y: None,
+        }
+    }
+
+    /// Set initial step length (default: 1.0)
+    pub fn step(mut self, step: f64) -> Result<Self, Error> {
+        check_positive("FISTA", "step", step)?;
+        self.step = step;
+        Ok(self)
+    }
+
+    /// Set factor by which the step length is reduced during backtracking (default: 0.5)
+    pub fn shrink(mut self, shrink: f64) -> Result<Self, Error> {
+        check_shrink("FISTA", shrink)?;
+        self.shrink = shrink;
+        Ok(self)
+    }
+
+    /// Set tolerance of the norm of the gradient mapping (default: 1e-8)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        check_positive("FISTA", "tol", tol)?;
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Reset the momentum whenever the cost increases (default: false)
+    pub fn restart(mut self, restart: bool) -> Self {
+        self.restart = restart;
+        self
+    }
+}
+
+impl<O, G, P> Solver<O> for FISTA<G, P>
+where
+    O: ArgminOp<Param = P, Output = f64>,
+    P: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminSub<P, P>
+        + ArgminScaledSub<P, f64, P>
+        + ArgminScaledAdd<P, f64, P>
+        + ArgminDot<P, f64>
+        + ArgminNorm<f64>,
+    G: ProximalOperator<P> + SerializeAlias,
+{
+    const NAME: &'static str = "FISTA";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        let cost = op.apply(&param)? + self.prox.value(&param)?;
+        self.theta = 1.0;
+        self.y = Some(param.clone());
+        Ok(Some(ArgminIterData::new().param(param).cost(cost)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let y = self.y.take().unwrap_or_else(|| param.clone());
+        let fy = op.apply(&y)?;
+        let grad = op.gradient(&y)?;
+        let (new_param, fx, step) =
+            prox_grad_step(op, &self.prox, &y, fy, &grad, self.step, self.shrink)?;
+        self.step = step;
+        let grad_map = new_param.sub(&y).norm() / step;
+        let cost = fx + self.prox.value(&new_param)?;
+
+        let restarted = self.restart && cost > state.get_cost();
+        if restarted {
+            self.theta = 1.0;
+            self.y = Some(new_param.clone());
+        } else {
+            let theta = 0.5 * (1.0 + (1.0 + 4.0 * self.theta.powi(2)).sqrt());
+            let beta = (self.theta - 1.0) / theta;
+            self.y = Some(new_param.scaled_add(&beta, &new_param.sub(&param)));
+            self.theta = theta;
+        }
+
+        let out = ArgminIterData::new()
+            .param(new_param)
+            .cost(cost)
+            .kv(make_kv!(
+                "step" => step;
+                "gradient_mapping" => grad_map;
+                "theta" => self.theta;
+                "restart" => restarted;
+            ));
+        if grad_map <= self.tol {
+            return Ok(out.termination_reason(TerminationReason::TargetPrecisionReached));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::proximalgradient::{BoxProjection, L1};
+    use crate::test_trait_impl;
+
+    test_trait_impl!(fista, FISTA<L1, Vec<f64>>);
+
+    /// 0.5 * ||x - c||^2
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Quadratic {
+        c: Vec<f64>,
+    }
+
+    impl ArgminOp for Quadratic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            let d = p.sub(&self.c);
+            let dd: f64 = d.dot(&d);
+            Ok(0.5 * dd)
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(p.sub(&self.c))
+        }
+    }
+
+    #[test]
+    fn test_fista_l1() {
+        // solution is the soft-thresholded c
+        let op = Quadratic {
+            c: vec![3.0, -0.5, -2.0],
+        };
+        let solver = FISTA::new(L1::new(1.0).unwrap()).step(4.0).unwrap();
+        let res = Executor::new(op, solver, vec![0.0; 3])
+            .max_iters(200)
+            .run()
+            .unwrap();
+        let x = res.state.get_best_param();
+        assert!((x[0] - 2.0).abs() < 1e-6);
+        assert!(x[1].abs() < 1e-6);
+        assert!((x[2] + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fista_box_restart() {
+        let op = Quadratic { c: vec![3.0, -3.0] };
+        let prox = BoxProjection::new(vec![-1.0, -1.0], vec![1.0, 1.0]).unwrap();
+        let solver = FISTA::new(prox).restart(true);
+        let res = Executor::new(op, solver, vec![0.0; 2])
+            .max_iters(200)
+            .run()
+            .unwrap();
+        let x = res.state.get_best_param();
+        assert!((x[0] - 1.0).abs() < 1e-6);
+        assert!((x[1] + 1.0).abs() < 1e-6);
+    }
+}
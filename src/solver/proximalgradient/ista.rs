@@ -0,0 +1,149 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Amir Beck and Marc Teboulle (2009). A Fast Iterative Shrinkage-Thresholding Algorithm for
+//! Linear Inverse Problems. SIAM Journal on Imaging Sciences 2(1), 183-202.
+
+use super::{check_positive, check_shrink, prox_grad_step, ProximalOperator};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// Iterative shrinkage-thresholding algorithm (ISTA)
+///
+/// Proximal gradient method with backtracking on the smooth part (see the
+/// [module documentation](index.html)). Terminates when the norm of the gradient mapping
+/// `||x_{k+1} - x_k|| / t` drops below `tol`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/proximalgradient.rs)
+///
+/// # References:
+///
+/// [0] Amir Beck and Marc Teboulle (2009). A Fast Iterative Shrinkage-Thresholding Algorithm for
+/// Linear Inverse Problems. SIAM Journal on Imaging Sciences 2(1), 183-202.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct ISTA<G> {
+    /// proximal operator of the nonsmooth part
+    prox: G,
+    /// current step length
+    step: f64,
+    /// factor by which the step length is reduced during backtracking
+    shrink: f64,
+    /// tolerance of the norm of the gradient mapping
+    tol: f64,
+    /// value of the smooth part at the current parameter vector
+    fx: f64,
+}
+
+impl<G> ISTA<G> {
+    /// Constructor
+    pub fn new(prox: G) -> Self {
+        ISTA {
+            prox,
+            step: 1.0,
+            shrink: 0.5,
+            tol: 1e-8,
+            fx: f64::NAN,
+        }
+    }
+
+    /// Set initial step length (default: 1.0)
+    pub fn step(mut self, step: f64) -> Result<Self, Error> {
+        check_positive("ISTA", "step", step)?;
+        self.step = step;
+        Ok(self)
+    }
+
+    /// Set factor by which the step length is reduced during backtracking (default: 0.5)
+    pub fn shrink(mut self, shrink: f64) -> Result<Self, Error> {
+        check_shrink("ISTA", shrink)?;
+        self.shrink = shrink;
+        Ok(self)
+    }
+
+    /// Set tolerance of the norm of the gradient mapping (default: 1e-8)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        check_positive("ISTA", "tol", tol)?;
+        self.tol = tol;
+        Ok(self)
+    }
+}
+
+impl<O, G> Solver<O> for ISTA<G>
+where
+    O: ArgminOp<Output = f64>,
+    O::Param: ArgminSub<O::Param, O::Param>
+        + ArgminScaledSub<O::Param, f64, O::Param>
+        + ArgminDot<O::Param, f64>
+        + ArgminNorm<f64>,
+    G: ProximalOperator<O::Param> + SerializeAlias,
+{
+    const NAME: &'static str = "ISTA";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        self.fx = op.apply(&param)?;
+        let cost = self.fx + self.prox.value(&param)?;
+        Ok(Some(ArgminIterData::new().param(param).cost(cost)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let grad = op.gradient(&param)?;
+        let (new_param, fx, step) = prox_grad_step(
+            op,
+            &self.prox,
+            &param,
+            self.fx,
+            &grad,
+            self.step,
+            self.shrink,
+        )?;
+        self.fx = fx;
+        self.step = step;
+        let grad_map = new_param.sub(&param).norm() / step;
+        let cost = fx + self.prox.value(&new_param)?;
+        let out = ArgminIterData::new()
+            .param(new_param)
+            .cost(cost)
+            .kv(make_kv!("step" => step; "gradient_mapping" => grad_map;));
+        if grad_map <= self.tol {
+            return Ok(out.termination_reason(TerminationReason::TargetPrecisionReached));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::proximalgradient::L1;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(ista, ISTA<L1>);
+
+    #[test]
+    fn test_setters() {
+        let prox = L1::new(1.0).unwrap();
+        assert!(ISTA::new(prox.clone()).step(-1.0).is_err());
+        assert!(ISTA::new(prox.clone()).shrink(1.0).is_err());
+        assert!(ISTA::new(prox.clone()).tol(0.0).is_err());
+        assert!(ISTA::new(prox).step(0.1).is_ok());
+    }
+}
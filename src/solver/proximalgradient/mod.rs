@@ -0,0 +1,258 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Proximal gradient methods
+//!
+//! Minimize composite functions `F(x) = f(x) + g(x)` where `f` is smooth and provided via
+//! `ArgminOp` (`apply` and `gradient`) and `g` is convex but possibly nonsmooth and provided via
+//! the `ProximalOperator` trait. Each iteration takes a gradient step on `f` followed by the
+//! proximal operator of `g`:
+//!
+//! `x_{k+1} = prox_{t g}(x_k - t * \nabla f(x_k))`
+//!
+//! For `g = 0` and a fixed step length this is the `Landweber` iteration. The step length `t` is
+//! found by backtracking on the smooth part. The reported cost is `F(x)`.
+//!
+//! # References:
+//!
+//! [0] Amir Beck and Marc Teboulle (2009). A Fast Iterative Shrinkage-Thresholding Algorithm for
+//! Linear Inverse Problems. SIAM Journal on Imaging Sciences 2(1), 183-202.
+//!
+//! [1] Neal Parikh and Stephen Boyd (2014). Proximal Algorithms. Foundations and Trends in
+//! Optimization 1(3), 127-239.
+
+/// FISTA
+pub mod fista;
+/// ISTA
+pub mod ista;
+/// Proximal operators
+pub mod prox;
+
+pub use self::fista::*;
+pub use self::ista::*;
+pub use self::prox::*;
+
+use crate::prelude::*;
+
+/// Maximum number of step length reductions of the backtracking
+const MAX_BACKTRACKS: usize = 60;
+
+/// New parameter vector, value of the smooth part at the new parameter vector and step length
+type ProxStep<P> = (P, f64, f64);
+
+/// Proximal gradient step from `y` with backtracking on the smooth part.
+///
+/// The step length is reduced by `shrink` until the quadratic upper bound
+/// `f(z) <= f(y) + grad^T (z - y) + 1/(2t) ||z - y||^2` holds.
+fn prox_grad_step<O, G>(
+    op: &mut OpWrapper<O>,
+    prox: &G,
+    y: &O::Param,
+    fy: f64,
+    grad: &O::Param,
+    step: f64,
+    shrink: f64,
+) -> Result<ProxStep<O::Param>, Error>
+where
+    O: ArgminOp<Output = f64>,
+    O::Param: ArgminSub<O::Param, O::Param>
+        + ArgminScaledSub<O::Param, f64, O::Param>
+        + ArgminDot<O::Param, f64>,
+    G: ProximalOperator<O::Param>,
+{
+    let mut step = step;
+    for _ in 0..MAX_BACKTRACKS {
+        let z = prox.prox(&y.scaled_sub(&step, grad), step)?;
+        let d = z.sub(y);
+        let fz = op.apply(&z)?;
+        let bound = fy + grad.dot(&d) + d.dot(&d) / (2.0 * step);
+        if fz <= bound + std::f64::EPSILON * fy.abs() {
+            return Ok((z, fz, step));
+        }
+        step *= shrink;
+    }
+    Err(ArgminError::ConditionViolated {
+        text: "Proximal gradient: backtracking failed to find a suitable step length.".to_string(),
+    }
+    .into())
+}
+
+/// Checks the parameters shared by `ISTA` and `FISTA`
+fn check_positive(name: &str, what: &str, value: f64) -> Result<(), Error> {
+    if value <= 0.0 || !value.is_finite() {
+        return Err(ArgminError::InvalidParameter {
+            text: format!("{}: {} must be finite and > 0.", name, what),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Checks the shrink factor of the backtracking
+fn check_shrink(name: &str, shrink: f64) -> Result<(), Error> {
+    if shrink <= 0.0 || shrink >= 1.0 {
+        return Err(ArgminError::InvalidParameter {
+            text: format!("{}: shrink must be in (0, 1).", name),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serde1")]
+    use serde::{Deserialize, Serialize};
+
+    /// `f(x) = 0.5 * ||A x - b||^2`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct LeastSquares {
+        a: Vec<Vec<f64>>,
+        b: Vec<f64>,
+    }
+
+    impl LeastSquares {
+        /// `A = [[2, 0], [1, 1], [0, 1]]`, `b = [2.5, 1, 0.2]`, minimum at `[1.2, 0]`
+        fn coupled() -> Self {
+            LeastSquares {
+                a: vec![vec![2.0, 0.0], vec![1.0, 1.0], vec![0.0, 1.0]],
+                b: vec![2.5, 1.0, 0.2],
+            }
+        }
+
+        /// `A = diag(1, 0.5, 0.1)`, `b = [3, 0.1, 5]` (condition number of `A^T A`: 100)
+        fn ill_conditioned() -> Self {
+            LeastSquares {
+                a: vec![
+                    vec![1.0, 0.0, 0.0],
+                    vec![0.0, 0.5, 0.0],
+                    vec![0.0, 0.0, 0.1],
+                ],
+                b: vec![3.0, 0.1, 5.0],
+            }
+        }
+
+        fn residual(&self, p: &[f64]) -> Vec<f64> {
+            self.a
+                .iter()
+                .zip(self.b.iter())
+                .map(|(row, bi)| row.iter().zip(p.iter()).map(|(a, x)| a * x).sum::<f64>() - bi)
+                .collect()
+        }
+    }
+
+    impl ArgminOp for LeastSquares {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            let r = self.residual(p);
+            Ok(0.5 * r.iter().map(|ri| ri.powi(2)).sum::<f64>())
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            let r = self.residual(p);
+            Ok((0..p.len())
+                .map(|j| {
+                    self.a
+                        .iter()
+                        .zip(r.iter())
+                        .map(|(row, ri)| row[j] * ri)
+                        .sum()
+                })
+                .collect())
+        }
+    }
+
+    /// Runs `solver` on `op` from the origin and returns the solution and the number of
+    /// iterations
+    fn solve<S: Solver<LeastSquares>>(op: LeastSquares, solver: S) -> (Vec<f64>, u64) {
+        let n = op.a[0].len();
+        let res = Executor::new(op, solver, vec![0.0; n])
+            .max_iters(10_000)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+        (res.state.get_best_param(), res.state.get_iter())
+    }
+
+    fn assert_close(x: &[f64], expected: &[f64], tol: f64) {
+        for (a, b) in x.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < tol, "{:?} vs. {:?}", x, expected);
+        }
+    }
+
+    /// Solves the problem with the proximal operator `prox` with ISTA and FISTA and compares the
+    /// solutions to `expected`
+    fn check<G>(op: LeastSquares, prox: G, expected: &[f64]) -> (Vec<f64>, Vec<f64>)
+    where
+        G: ProximalOperator<Vec<f64>> + Clone + SerializeAlias,
+    {
+        let (ista, _) = solve(op.clone(), ISTA::new(prox.clone()));
+        assert_close(&ista, expected, 1e-6);
+        let (fista, _) = solve(op, FISTA::new(prox));
+        assert_close(&fista, expected, 1e-6);
+        (ista, fista)
+    }
+
+    #[test]
+    fn test_lasso() {
+        // KKT conditions: A^T (A x - b) = [-1, -0.2] = -lambda * [sign(x_0), s] with |s| <= 1
+        let (ista, fista) = check(LeastSquares::coupled(), L1::new(1.0).unwrap(), &[1.0, 0.0]);
+        assert!(ista[1] == 0.0 && fista[1] == 0.0);
+    }
+
+    #[test]
+    fn test_elastic_net() {
+        // 5 x_0 - 6 + l1 + l2 x_0 = 0
+        let prox = ElasticNet::new(1.0, 1.0).unwrap();
+        let (ista, fista) = check(LeastSquares::coupled(), prox, &[5.0 / 6.0, 0.0]);
+        assert!(ista[1] == 0.0 && fista[1] == 0.0);
+    }
+
+    #[test]
+    fn test_box_projection() {
+        // x_0 is at its upper bound, x_1 minimizes f(0.5, x_1)
+        let prox = BoxProjection::new(vec![-0.5, -0.5], vec![0.5, 0.5]).unwrap();
+        check(LeastSquares::coupled(), prox, &[0.5, 0.35]);
+    }
+
+    #[test]
+    fn test_simplex_projection() {
+        // minimum of f(t, 2 - t) at t = 1.36
+        let prox = SimplexProjection::new(2.0).unwrap();
+        check(LeastSquares::coupled(), prox, &[1.36, 0.64]);
+    }
+
+    #[test]
+    fn test_fista_fewer_iterations() {
+        // separable problem, the solution is x_i = soft_threshold(a_i b_i, lambda) / a_i^2
+        let op = LeastSquares::ill_conditioned();
+        let prox = L1::new(0.1).unwrap();
+        let expected = [2.9, 0.0, 40.0];
+        let ista = ISTA::new(prox.clone()).tol(1e-6).unwrap();
+        let (ista, ista_iters) = solve(op.clone(), ista);
+        let fista = FISTA::new(prox).tol(1e-6).unwrap();
+        let (fista, fista_iters) = solve(op, fista);
+        assert_close(&ista, &expected, 1e-3);
+        assert_close(&fista, &expected, 1e-3);
+        assert!(ista[1] == 0.0 && fista[1] == 0.0);
+        assert!(
+            2 * fista_iters < ista_iters,
+            "FISTA: {}, ISTA: {}",
+            fista_iters,
+            ista_iters
+        );
+    }
+}
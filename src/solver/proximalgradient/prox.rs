@@ -0,0 +1,299 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Neal Parikh and Stephen Boyd (2014). Proximal Algorithms. Foundations and Trends in
+//! Optimization 1(3), 127-239.
+//!
+//! [1] John Duchi, Shai Shalev-Shwartz, Yoram Singer and Tushar Chandra (2008). Efficient
+//! Projections onto the l1-Ball for Learning in High Dimensions. ICML 2008.
+
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+use std::iter::FromIterator;
+
+/// Tolerance used to decide whether a point is feasible for a projection
+const FEAS_TOL: f64 = 1e-10;
+
+/// Proximal operator of a (possibly nonsmooth) convex function `g`
+///
+/// The proximal operator with step length `t` is defined as
+///
+/// `prox_{t g}(x) = argmin_z g(z) + 1/(2t) ||z - x||^2`.
+///
+/// For indicator functions of convex sets the proximal operator is the projection onto the set.
+pub trait ProximalOperator<P> {
+    /// Evaluate the proximal operator at `x` with step length `t`
+    fn prox(&self, x: &P, t: f64) -> Result<P, Error>;
+
+    /// Value of `g` at `x` (infinite outside of the domain of `g`)
+    fn value(&self, x: &P) -> Result<f64, Error>;
+}
+
+/// L1 regularization `g(x) = lambda * ||x||_1`
+///
+/// The proximal operator is the soft-thresholding operator.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct L1 {
+    /// regularization parameter
+    lambda: f64,
+}
+
+impl L1 {
+    /// Constructor
+    pub fn new(lambda: f64) -> Result<Self, Error> {
+        if lambda < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "L1: lambda must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(L1 { lambda })
+    }
+}
+
+/// Soft-thresholding `sign(x) * max(|x| - threshold, 0)`
+fn soft_threshold(x: f64, threshold: f64) -> f64 {
+    x.signum() * (x.abs() - threshold).max(0.0)
+}
+
+impl<P> ProximalOperator<P> for L1
+where
+    P: FromIterator<f64>,
+    for<'a> &'a P: IntoIterator<Item = &'a f64>,
+{
+    fn prox(&self, x: &P, t: f64) -> Result<P, Error> {
+        Ok(x.into_iter()
+            .map(|&xi| soft_threshold(xi, t * self.lambda))
+            .collect())
+    }
+
+    fn value(&self, x: &P) -> Result<f64, Error> {
+        Ok(self.lambda * x.into_iter().map(|xi| xi.abs()).sum::<f64>())
+    }
+}
+
+/// Elastic-net regularization `g(x) = l1 * ||x||_1 + l2 / 2 * ||x||_2^2`
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct ElasticNet {
+    /// weight of the L1 term
+    l1: f64,
+    /// weight of the squared L2 term
+    l2: f64,
+}
+
+impl ElasticNet {
+    /// Constructor
+    pub fn new(l1: f64, l2: f64) -> Result<Self, Error> {
+        if l1 < 0.0 || l2 < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "ElasticNet: l1 and l2 must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(ElasticNet { l1, l2 })
+    }
+}
+
+impl<P> ProximalOperator<P> for ElasticNet
+where
+    P: FromIterator<f64>,
+    for<'a> &'a P: IntoIterator<Item = &'a f64>,
+{
+    fn prox(&self, x: &P, t: f64) -> Result<P, Error> {
+        let scale = 1.0 / (1.0 + t * self.l2);
+        Ok(x.into_iter()
+            .map(|&xi| scale * soft_threshold(xi, t * self.l1))
+            .collect())
+    }
+
+    fn value(&self, x: &P) -> Result<f64, Error> {
+        Ok(x.into_iter()
+            .map(|xi| self.l1 * xi.abs() + 0.5 * self.l2 * xi.powi(2))
+            .sum())
+    }
+}
+
+/// Indicator function of the box `lower <= x <= upper`
+///
+/// The proximal operator is the projection onto the box.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct BoxProjection {
+    /// lower bounds
+    lower: Vec<f64>,
+    /// upper bounds
+    upper: Vec<f64>,
+}
+
+impl BoxProjection {
+    /// Constructor
+    pub fn new(lower: Vec<f64>, upper: Vec<f64>) -> Result<Self, Error> {
+        if lower.len() != upper.len() || lower.iter().zip(upper.iter()).any(|(l, u)| l > u) {
+            return Err(ArgminError::InvalidParameter {
+                text: "BoxProjection: lower and upper must have equal length and lower <= upper."
+                    .to_string(),
+            }
+            .into());
+        }
+        Ok(BoxProjection { lower, upper })
+    }
+
+    /// Check that `x` has the dimension of the box
+    fn check_dim(&self, n: usize) -> Result<(), Error> {
+        if n != self.lower.len() {
+            return Err(ArgminError::InvalidParameter {
+                text: format!(
+                    "BoxProjection: dimension of the box is {}, got {}.",
+                    self.lower.len(),
+                    n
+                ),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl<P> ProximalOperator<P> for BoxProjection
+where
+    P: FromIterator<f64>,
+    for<'a> &'a P: IntoIterator<Item = &'a f64>,
+{
+    fn prox(&self, x: &P, _t: f64) -> Result<P, Error> {
+        self.check_dim(x.into_iter().count())?;
+        Ok(x.into_iter()
+            .zip(self.lower.iter().zip(self.upper.iter()))
+            .map(|(&xi, (&l, &u))| xi.max(l).min(u))
+            .collect())
+    }
+
+    fn value(&self, x: &P) -> Result<f64, Error> {
+        self.check_dim(x.into_iter().count())?;
+        let feasible = x
+            .into_iter()
+            .zip(self.lower.iter().zip(self.upper.iter()))
+            .all(|(&xi, (&l, &u))| xi >= l - FEAS_TOL && xi <= u + FEAS_TOL);
+        Ok(if feasible { 0.0 } else { f64::INFINITY })
+    }
+}
+
+/// Indicator function of the simplex `{x : x_i >= 0, sum_i x_i = radius}`
+///
+/// The proximal operator is the Euclidean projection onto the simplex, computed by sorting [1].
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct SimplexProjection {
+    /// sum of the entries of points in the simplex
+    radius: f64,
+}
+
+impl SimplexProjection {
+    /// Constructor
+    pub fn new(radius: f64) -> Result<Self, Error> {
+        if radius <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "SimplexProjection: radius must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(SimplexProjection { radius })
+    }
+}
+
+impl<P> ProximalOperator<P> for SimplexProjection
+where
+    P: FromIterator<f64>,
+    for<'a> &'a P: IntoIterator<Item = &'a f64>,
+{
+    fn prox(&self, x: &P, _t: f64) -> Result<P, Error> {
+        let mut u: Vec<f64> = x.into_iter().cloned().collect();
+        if u.iter().any(|ui| ui.is_nan()) {
+            return Err(ArgminError::InvalidParameter {
+                text: "SimplexProjection: cannot project NaN.".to_string(),
+            }
+            .into());
+        }
+        u.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        let mut cumsum = 0.0;
+        let mut theta = 0.0;
+        for (j, uj) in u.iter().enumerate() {
+            cumsum += uj;
+            let t = (cumsum - self.radius) / (j + 1) as f64;
+            if uj - t > 0.0 {
+                theta = t;
+            }
+        }
+        Ok(x.into_iter().map(|&xi| (xi - theta).max(0.0)).collect())
+    }
+
+    fn value(&self, x: &P) -> Result<f64, Error> {
+        let tol = FEAS_TOL * self.radius.max(1.0);
+        let mut sum = 0.0;
+        for &xi in x.into_iter() {
+            if xi < -tol {
+                return Ok(f64::INFINITY);
+            }
+            sum += xi;
+        }
+        Ok(if (sum - self.radius).abs() <= tol {
+            0.0
+        } else {
+            f64::INFINITY
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_l1() {
+        let prox = L1::new(1.0).unwrap();
+        let x = prox.prox(&vec![3.0, -0.5, -2.0], 0.5).unwrap();
+        assert_eq!(x, vec![2.5, 0.0, -1.5]);
+        assert!((prox.value(&x).unwrap() - 4.0).abs() < std::f64::EPSILON);
+        assert!(L1::new(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_elastic_net() {
+        let prox = ElasticNet::new(1.0, 2.0).unwrap();
+        let x: Vec<f64> = prox.prox(&vec![4.0, 0.5], 0.5).unwrap();
+        assert!((x[0] - 1.75).abs() < std::f64::EPSILON);
+        assert!(x[1].abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_box_projection() {
+        let prox = BoxProjection::new(vec![0.0, -1.0], vec![1.0, 1.0]).unwrap();
+        let x = prox.prox(&vec![2.0, -3.0], 1.0).unwrap();
+        assert_eq!(x, vec![1.0, -1.0]);
+        assert!(prox.value(&x).unwrap().abs() < std::f64::EPSILON);
+        assert!(prox.value(&vec![2.0, 0.0]).unwrap().is_infinite());
+        assert!(prox.prox(&vec![0.0], 1.0).is_err());
+        assert!(BoxProjection::new(vec![1.0], vec![0.0]).is_err());
+    }
+
+    #[test]
+    fn test_simplex_projection() {
+        let prox = SimplexProjection::new(1.0).unwrap();
+        let x = prox.prox(&vec![0.5, 0.5, 1.0], 1.0).unwrap();
+        assert!((x[0] - 0.1667).abs() < 1e-4);
+        assert!((x[1] - 0.1667).abs() < 1e-4);
+        assert!((x[2] - 0.6667).abs() < 1e-4);
+        assert!(prox.value(&x).unwrap().abs() < std::f64::EPSILON);
+        let x = prox.prox(&vec![2.0, -1.0], 1.0).unwrap();
+        assert_eq!(x, vec![1.0, 0.0]);
+    }
+}
@@ -0,0 +1,612 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] D. Goldfarb and A. Idnani (1983). A numerically stable dual method for solving strictly
+//! convex quadratic programs. Mathematical Programming 27, 1-33.
+//!
+//! [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::linalg;
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// Active-set method for strictly convex quadratic programs
+///
+/// Solves
+///
+/// ```text
+/// min 1/2 x^T Q x + c^T x  subject to  a_i^T x = b_i (equality),  g_j^T x <= h_j (inequality)
+/// ```
+///
+/// where `Q` (which must be positive definite) is obtained from `ArgminOp::hessian` and `c` from
+/// `ArgminOp::gradient` (which must return `Q x + c`) at the initial parameter vector. The
+/// constraint normals are of the same type as the parameter vector.
+///
+/// The dual active-set method of Goldfarb and Idnani is used: starting from the minimizer subject
+/// to the equality constraints and the constraints of the working set, the most violated
+/// inequality constraint is added to the working set in each iteration, while constraints whose
+/// multipliers would become negative are dropped. Therefore the initial parameter vector does not
+/// need to be feasible. All computations are carried out on the (small) matrix
+/// `A Q^-1 A^T` of the constraint normals, for which `Q^-1` is obtained via `ArgminInv` once.
+///
+/// The constructor takes the indices of the inequality constraints which are expected to be
+/// active at the solution, for instance the active set of the previous problem in a sequence of
+/// similar problems (warm start). Constraints of this set with negative multipliers are dropped
+/// before the first iteration. With a correct guess the solver terminates after a single
+/// iteration. The active set and the multipliers of the current iterate are available via
+/// `active_set` and `multipliers`; they are also reported in the key-value store.
+///
+/// As long as the iterate violates constraints, the reported cost is `INFINITY`. If the problem
+/// is infeasible, an error is returned.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/activeset.rs)
+///
+/// # References:
+///
+/// [0] D. Goldfarb and A. Idnani (1983). A numerically stable dual method for solving strictly
+/// convex quadratic programs. Mathematical Programming 27, 1-33.
+///
+/// [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct ActiveSetQP<P> {
+    /// normals of the equality constraints
+    eq_rows: Vec<P>,
+    /// right-hand side of the equality constraints
+    eq_rhs: Vec<f64>,
+    /// normals of the inequality constraints
+    ineq_rows: Vec<P>,
+    /// right-hand side of the inequality constraints
+    ineq_rhs: Vec<f64>,
+    /// initial guess of the active set
+    warm_start: Vec<usize>,
+    /// tolerance
+    tol: f64,
+    /// `Q^-1 c`
+    qinv_c: Option<P>,
+    /// `Q^-1 a_i` for all constraints (equalities first)
+    qinv_rows: Vec<P>,
+    /// `a_i^T Q^-1 a_j` for all constraints
+    gram: Vec<Vec<f64>>,
+    /// `a_i^T Q^-1 c` for all constraints
+    d: Vec<f64>,
+    /// right-hand sides of all constraints
+    rhs: Vec<f64>,
+    /// working set (indices of all constraints, equalities first)
+    working: Vec<usize>,
+    /// multipliers of the constraints in the working set
+    lambda: Vec<f64>,
+    /// constraint which is currently being added together with its (partial) multiplier
+    pending: Option<(usize, f64)>,
+}
+
+impl<P> ActiveSetQP<P> {
+    /// Constructor
+    ///
+    /// `active_set` contains the indices of the inequality constraints which are assumed to be
+    /// active at the solution (pass an empty vector if no guess is available).
+    pub fn new(active_set: Vec<usize>) -> Self {
+        ActiveSetQP {
+            eq_rows: vec![],
+            eq_rhs: vec![],
+            ineq_rows: vec![],
+            ineq_rhs: vec![],
+            warm_start: active_set,
+            tol: 1e-10,
+            qinv_c: None,
+            qinv_rows: vec![],
+            gram: vec![],
+            d: vec![],
+            rhs: vec![],
+            working: vec![],
+            lambda: vec![],
+            pending: None,
+        }
+    }
+
+    /// Add equality constraints `a_i^T x = b_i`
+    pub fn equality(mut self, rows: Vec<P>, rhs: Vec<f64>) -> Result<Self, Error> {
+        if rows.len() != rhs.len() {
+            return Err(ArgminError::InvalidParameter {
+                text: "ActiveSetQP: number of constraint normals and right-hand sides differ."
+                    .to_string(),
+            }
+            .into());
+        }
+        self.eq_rows.extend(rows);
+        self.eq_rhs.extend(rhs);
+        Ok(self)
+    }
+
+    /// Add inequality constraints `g_j^T x <= h_j`. Inequality constraints are indexed in the
+    /// order in which they were added.
+    pub fn inequality(mut self, rows: Vec<P>, rhs: Vec<f64>) -> Result<Self, Error> {
+        if rows.len() != rhs.len() {
+            return Err(ArgminError::InvalidParameter {
+                text: "ActiveSetQP: number of constraint normals and right-hand sides differ."
+                    .to_string(),
+            }
+            .into());
+        }
+        self.ineq_rows.extend(rows);
+        self.ineq_rhs.extend(rhs);
+        Ok(self)
+    }
+
+    /// Set tolerance for the constraint violation (default: 1e-10)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "ActiveSetQP: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Indices of the inequality constraints in the working set of the current iterate. After
+    /// termination, this is the active set of the solution and can be used to warm start a
+    /// similar problem.
+    pub fn active_set(&self) -> Vec<usize> {
+        let m_eq = self.eq_rhs.len();
+        self.working
+            .iter()
+            .filter(|&&i| i >= m_eq)
+            .map(|i| i - m_eq)
+            .collect()
+    }
+
+    /// Multipliers of the equality and inequality constraints of the current iterate, such that
+    /// `Q x + c + sum_i lambda_i a_i + sum_j mu_j g_j = 0`.
+    pub fn multipliers(&self) -> (Vec<f64>, Vec<f64>) {
+        let m_eq = self.eq_rhs.len();
+        let mut lambda = vec![0.0; m_eq];
+        let mut mu = vec![0.0; self.ineq_rhs.len()];
+        for (&i, &l) in self.working.iter().zip(self.lambda.iter()) {
+            if i < m_eq {
+                lambda[i] = l;
+            } else {
+                mu[i - m_eq] = l;
+            }
+        }
+        (lambda, mu)
+    }
+
+    /// Current multipliers of all constraints in the working set, including the pending one
+    fn weights(&self) -> Vec<(usize, f64)> {
+        self.working
+            .iter()
+            .cloned()
+            .zip(self.lambda.iter().cloned())
+            .chain(self.pending)
+            .collect()
+    }
+
+    /// Violation `a_j^T x - b_j` of constraint `j` at the current iterate
+    fn violation(&self, j: usize) -> f64 {
+        let ax: f64 = self
+            .weights()
+            .iter()
+            .map(|&(i, l)| l * self.gram[j][i])
+            .sum();
+        -self.d[j] - ax - self.rhs[j]
+    }
+
+    /// Largest violation of all constraints
+    fn max_violation(&self) -> f64 {
+        let m_eq = self.eq_rhs.len();
+        (0..self.rhs.len())
+            .map(|j| {
+                let v = self.violation(j);
+                if j < m_eq {
+                    v.abs()
+                } else {
+                    v.max(0.0)
+                }
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Submatrix of the Gram matrix for the working set
+    fn working_gram(&self) -> Vec<Vec<f64>> {
+        self.working
+            .iter()
+            .map(|&i| self.working.iter().map(|&j| self.gram[i][j]).collect())
+            .collect()
+    }
+
+    /// Multipliers of the minimizer subject to the constraints of the working set as equalities
+    fn solve_working(&mut self) -> Result<(), Error> {
+        let rhs: Vec<f64> = self
+            .working
+            .iter()
+            .map(|&i| -(self.rhs[i] + self.d[i]))
+            .collect();
+        self.lambda = linalg::solve(&self.working_gram(), &rhs)?;
+        Ok(())
+    }
+
+    /// Removes constraint at position `k` of the working set
+    fn drop_constraint(&mut self, k: usize) {
+        self.working.remove(k);
+        self.lambda.remove(k);
+    }
+
+    /// One step of the dual method for the pending constraint `p` with multiplier `t`. Returns
+    /// `true` if `p` was added to the working set.
+    fn dual_step(&mut self, p: usize, t: f64) -> Result<bool, Error> {
+        let m_eq = self.eq_rhs.len();
+        let rhs: Vec<f64> = self.working.iter().map(|&i| -self.gram[i][p]).collect();
+        let dlambda = linalg::solve(&self.working_gram(), &rhs)?;
+        // change of `a_p^T x` per unit increase of the multiplier of `p`
+        let slope = -self.gram[p][p]
+            - self
+                .working
+                .iter()
+                .zip(dlambda.iter())
+                .map(|(&i, dl)| dl * self.gram[p][i])
+                .sum::<f64>();
+
+        // largest step which keeps the multipliers of the inequalities nonnegative
+        let mut partial: Option<(usize, f64)> = None;
+        for (k, ((&i, &l), &dl)) in self
+            .working
+            .iter()
+            .zip(self.lambda.iter())
+            .zip(dlambda.iter())
+            .enumerate()
+        {
+            if i >= m_eq && dl < 0.0 {
+                let step = -l / dl;
+                if partial.map(|(_, s)| step < s).unwrap_or(true) {
+                    partial = Some((k, step));
+                }
+            }
+        }
+
+        // full step which makes `p` active
+        let full = if slope < -self.tol * self.gram[p][p] {
+            Some(self.violation(p) / -slope)
+        } else {
+            None
+        };
+
+        let step = match (full, partial) {
+            (None, None) => {
+                return Err(ArgminError::ConditionViolated {
+                    text: "ActiveSetQP: problem is infeasible.".to_string(),
+                }
+                .into())
+            }
+            (Some(f), Some((_, s))) => f.min(s),
+            (Some(f), None) => f,
+            (None, Some((_, s))) => s,
+        };
+        for (l, dl) in self.lambda.iter_mut().zip(dlambda.iter()) {
+            *l += step * dl;
+        }
+        let t = t + step;
+        match (full, partial) {
+            (Some(f), _) if f <= step => {
+                self.working.push(p);
+                self.lambda.push(t);
+                self.pending = None;
+                Ok(true)
+            }
+            (_, Some((k, _))) => {
+                self.drop_constraint(k);
+                self.pending = Some((p, t));
+                Ok(false)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Parameter vector of the current iterate: `x = -Q^-1 (c + sum_i lambda_i a_i)`
+    fn param(&self) -> P
+    where
+        P: ArgminScaledAdd<P, f64, P> + ArgminMul<f64, P>,
+    {
+        let x = self.qinv_c.as_ref().unwrap().mul(&(-1.0));
+        self.weights()
+            .iter()
+            .fold(x, |x, &(i, l)| x.scaled_add(&(-l), &self.qinv_rows[i]))
+    }
+}
+
+impl<P> ActiveSetQP<P>
+where
+    P: FromIterator<f64>,
+    for<'a> &'a P: IntoIterator<Item = &'a f64>,
+{
+    /// Add bound constraints `lower <= x <= upper` as inequality constraints (`x_i <= upper_i`
+    /// followed by `-x_i <= -lower_i`). Infinite bounds are skipped.
+    pub fn bounds(self, lower: &P, upper: &P) -> Result<Self, Error> {
+        let lower: Vec<f64> = lower.into_iter().cloned().collect();
+        let upper: Vec<f64> = upper.into_iter().cloned().collect();
+        if lower.len() != upper.len() || lower.iter().zip(upper.iter()).any(|(l, u)| l > u) {
+            return Err(ArgminError::InvalidParameter {
+                text: "ActiveSetQP: bounds must have equal length and lower <= upper.".to_string(),
+            }
+            .into());
+        }
+        let n = lower.len();
+        let unit =
+            |i: usize, s: f64| -> P { (0..n).map(|j| if i == j { s } else { 0.0 }).collect() };
+        let mut rows = vec![];
+        let mut rhs = vec![];
+        for (i, &u) in upper.iter().enumerate().filter(|(_, u)| u.is_finite()) {
+            rows.push(unit(i, 1.0));
+            rhs.push(u);
+        }
+        for (i, &l) in lower.iter().enumerate().filter(|(_, l)| l.is_finite()) {
+            rows.push(unit(i, -1.0));
+            rhs.push(-l);
+        }
+        self.inequality(rows, rhs)
+    }
+}
+
+impl<O> Solver<O> for ActiveSetQP<O::Param>
+where
+    O: ArgminOp<Output = f64>,
+    O::Param: ArgminSub<O::Param, O::Param>
+        + ArgminDot<O::Param, f64>
+        + ArgminScaledAdd<O::Param, f64, O::Param>
+        + ArgminMul<f64, O::Param>,
+    O::Hessian: ArgminInv<O::Hessian> + ArgminDot<O::Param, O::Param>,
+{
+    const NAME: &'static str = "Active-set QP";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let x0 = state.get_param();
+        let hessian = op.hessian(&x0)?;
+        let c = op.gradient(&x0)?.sub(&hessian.dot(&x0));
+        let qinv = hessian.inv()?;
+        let qinv_c = qinv.dot(&c);
+
+        let rows: Vec<&O::Param> = self.eq_rows.iter().chain(self.ineq_rows.iter()).collect();
+        self.qinv_rows = rows.iter().map(|a| qinv.dot(*a)).collect();
+        self.gram = rows
+            .iter()
+            .map(|a| self.qinv_rows.iter().map(|qa| a.dot(qa)).collect())
+            .collect();
+        self.d = rows.iter().map(|a| a.dot(&qinv_c)).collect();
+        self.rhs = self
+            .eq_rhs
+            .iter()
+            .chain(self.ineq_rhs.iter())
+            .cloned()
+            .collect();
+        self.qinv_c = Some(qinv_c);
+
+        let m_eq = self.eq_rhs.len();
+        if self.warm_start.iter().any(|&j| j >= self.ineq_rhs.len()) {
+            return Err(ArgminError::InvalidParameter {
+                text: "ActiveSetQP: index of active set exceeds number of inequality constraints."
+                    .to_string(),
+            }
+            .into());
+        }
+        self.working = (0..m_eq).collect();
+        for &j in self.warm_start.iter() {
+            if !self.working.contains(&(m_eq + j)) {
+                self.working.push(m_eq + j);
+            }
+        }
+        self.pending = None;
+
+        // drop constraints of the initial working set with negative multipliers one at a time
+        loop {
+            self.solve_working()?;
+            let most_negative = self
+                .working
+                .iter()
+                .zip(self.lambda.iter())
+                .enumerate()
+                .filter(|(_, (&i, &l))| i >= m_eq && l < 0.0)
+                .fold(None, |acc: Option<(usize, f64)>, (k, (_, &l))| match acc {
+                    Some((_, best)) if best <= l => acc,
+                    _ => Some((k, l)),
+                });
+            match most_negative {
+                Some((k, _)) => self.drop_constraint(k),
+                None => break,
+            }
+        }
+
+        let param = self.param();
+        let cost = if self.max_violation() > self.tol {
+            std::f64::INFINITY
+        } else {
+            op.apply(&param)?
+        };
+        Ok(Some(ArgminIterData::new().param(param).cost(cost)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let m_eq = self.eq_rhs.len();
+        let (p, t) = match self.pending {
+            Some(pending) => pending,
+            None => {
+                // most violated inequality constraint
+                let violated = (m_eq..self.rhs.len())
+                    .filter(|j| !self.working.contains(j))
+                    .map(|j| (j, self.violation(j)))
+                    .filter(|&(j, v)| v > self.tol * (1.0 + self.rhs[j].abs()))
+                    .fold(None, |acc: Option<(usize, f64)>, (j, v)| match acc {
+                        Some((_, best)) if best >= v => acc,
+                        _ => Some((j, v)),
+                    });
+                match violated {
+                    Some((p, _)) => (p, 0.0),
+                    None => {
+                        let param = self.param();
+                        let cost = op.apply(&param)?;
+                        return Ok(ArgminIterData::new()
+                            .param(param)
+                            .cost(cost)
+                            .kv(make_kv!("active_set" => self.active_set();))
+                            .termination_reason(TerminationReason::TargetPrecisionReached));
+                    }
+                }
+            }
+        };
+        self.pending = Some((p, t));
+        let added = self.dual_step(p, t)?;
+
+        let param = self.param();
+        let violation = self.max_violation();
+        let cost = if violation > self.tol {
+            std::f64::INFINITY
+        } else {
+            op.apply(&param)?
+        };
+        Ok(ArgminIterData::new().param(param).cost(cost).kv(make_kv!(
+            "active_set" => self.active_set();
+            "constraint" => p - m_eq;
+            "added" => added;
+            "violation" => violation;
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(activeset, ActiveSetQP<Vec<f64>>);
+
+    #[cfg(feature = "ndarrayl")]
+    mod ndarray_tests {
+        use super::*;
+        use ndarray::{array, Array1, Array2};
+
+        /// 1/2 x^T Q x + c^T x
+        #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+        #[derive(Clone, Default)]
+        struct Quadratic {
+            q: Array2<f64>,
+            c: Array1<f64>,
+        }
+
+        impl ArgminOp for Quadratic {
+            type Param = Array1<f64>;
+            type Output = f64;
+            type Hessian = Array2<f64>;
+            type Jacobian = ();
+
+            fn apply(&self, p: &Array1<f64>) -> Result<f64, Error> {
+                Ok(0.5 * p.dot(&self.q.dot(p)) + self.c.dot(p))
+            }
+
+            fn gradient(&self, p: &Array1<f64>) -> Result<Array1<f64>, Error> {
+                Ok(self.q.dot(p) + &self.c)
+            }
+
+            fn hessian(&self, _p: &Array1<f64>) -> Result<Array2<f64>, Error> {
+                Ok(self.q.clone())
+            }
+        }
+
+        /// Nocedal & Wright, Example 16.4: solution [1.4, 1.7] with active constraint 0
+        fn example(active_set: Vec<usize>) -> (Array1<f64>, u64, Vec<f64>) {
+            let op = Quadratic {
+                q: array![[2.0, 0.0], [0.0, 2.0]],
+                c: array![-2.0, -5.0],
+            };
+            let solver = ActiveSetQP::new(active_set)
+                .inequality(
+                    vec![array![-1.0, 2.0], array![1.0, 2.0], array![1.0, -2.0]],
+                    vec![2.0, 6.0, 2.0],
+                )
+                .unwrap()
+                .bounds(&array![0.0, 0.0], &array![std::f64::INFINITY, 10.0])
+                .unwrap();
+            let mut op = OpWrapper::new(&op);
+            let mut solver = solver;
+            let mut state = IterState::new(array![2.0, 0.0]);
+            let data = solver.init(&mut op, &state).unwrap().unwrap();
+            state.param(data.get_param().unwrap());
+            let mut iters = 0;
+            loop {
+                let data = solver.next_iter(&mut op, &state).unwrap();
+                state.param(data.get_param().unwrap());
+                iters += 1;
+                if data.get_termination_reason().is_some() {
+                    break;
+                }
+            }
+            (state.get_param(), iters, solver.multipliers().1)
+        }
+
+        #[test]
+        fn test_cold_start() {
+            let (x, _, mu) = example(vec![]);
+            assert!((x[0] - 1.4).abs() < 1e-10);
+            assert!((x[1] - 1.7).abs() < 1e-10);
+            assert!((mu[0] - 0.8).abs() < 1e-10);
+            assert!(mu[1..].iter().all(|m| m.abs() < 1e-10));
+        }
+
+        #[test]
+        fn test_warm_start() {
+            let (x, iters, _) = example(vec![0]);
+            assert!((x[0] - 1.4).abs() < 1e-10);
+            assert!((x[1] - 1.7).abs() < 1e-10);
+            assert_eq!(iters, 1);
+            // wrong guesses are corrected
+            let (x, _, _) = example(vec![2, 4]);
+            assert!((x[0] - 1.4).abs() < 1e-10);
+            assert!((x[1] - 1.7).abs() < 1e-10);
+        }
+
+        #[test]
+        fn test_equality_and_infeasible() {
+            let op = Quadratic {
+                q: array![[1.0, 0.0], [0.0, 1.0]],
+                c: array![0.0, 0.0],
+            };
+            let solver = ActiveSetQP::new(vec![])
+                .equality(vec![array![1.0, 1.0]], vec![1.0])
+                .unwrap()
+                .inequality(vec![array![-1.0, 0.0]], vec![-0.8])
+                .unwrap();
+            let res = Executor::new(op.clone(), solver, array![0.0, 0.0])
+                .max_iters(10)
+                .run()
+                .unwrap();
+            let x = res.state.get_best_param();
+            assert!((x[0] - 0.8).abs() < 1e-10);
+            assert!((x[1] - 0.2).abs() < 1e-10);
+
+            let solver = ActiveSetQP::new(vec![])
+                .inequality(vec![array![1.0, 1.0], array![-1.0, -1.0]], vec![1.0, -2.0])
+                .unwrap();
+            assert!(Executor::new(op, solver, array![0.0, 0.0])
+                .max_iters(10)
+                .run()
+                .is_err());
+        }
+    }
+}
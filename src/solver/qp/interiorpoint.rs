@@ -0,0 +1,446 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] Sanjay Mehrotra (1992). On the Implementation of a Primal-Dual Interior Point Method.
+//! SIAM Journal on Optimization 2(4), 575-601.
+
+use crate::linalg;
+use crate::prelude::*;
+use ndarray::{Array1, Array2, Axis};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Fraction of the maximum step to the boundary which is taken
+const STEP_FRACTION: f64 = 0.99;
+
+/// Search direction `(dx, dy, dz, ds)`
+type Direction = (Array1<f64>, Array1<f64>, Array1<f64>, Array1<f64>);
+
+/// Mehrotra predictor-corrector interior-point method for convex quadratic programs
+///
+/// Solves
+///
+/// ```text
+/// min 1/2 x^T Q x + c^T x  subject to  A x = b,  G x <= h
+/// ```
+///
+/// where `Q` is obtained from `ArgminOp::hessian` and `c` from `ArgminOp::gradient` (which must
+/// return `Q x + c`) at the initial parameter vector. The inequality constraints are turned into
+/// `G x + s = h` with slack variables `s >= 0`. In each iteration an affine scaling (predictor)
+/// direction is computed, from which the centering parameter `sigma` is chosen, followed by a
+/// corrector direction. The initial parameter vector does not need to be feasible.
+///
+/// The duality gap `s^T z` and the (infinity norms of the) primal and dual residuals are reported
+/// in the key-value store as `gap`, `primal_residual` and `dual_residual`. The solver terminates
+/// once all three drop below `tol` (relative to the magnitude of the problem data). The
+/// multipliers of the equality and inequality constraints are available via `multipliers`. As long
+/// as the iterates violate the constraints, the reported cost is `INFINITY`.
+///
+/// Requires the `ndarrayl` feature.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/interiorpoint.rs)
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+///
+/// [1] Sanjay Mehrotra (1992). On the Implementation of a Primal-Dual Interior Point Method.
+/// SIAM Journal on Optimization 2(4), 575-601.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct InteriorPointQP {
+    /// equality constraints `A x = b`
+    equality: Option<(Array2<f64>, Array1<f64>)>,
+    /// inequality constraints `G x <= h`
+    inequality: Option<(Array2<f64>, Array1<f64>)>,
+    /// tolerance
+    tol: f64,
+    /// Q
+    q: Array2<f64>,
+    /// c
+    c: Array1<f64>,
+    /// A
+    a: Array2<f64>,
+    /// b
+    b: Array1<f64>,
+    /// G
+    g: Array2<f64>,
+    /// h
+    h: Array1<f64>,
+    /// multipliers of the equality constraints
+    y: Array1<f64>,
+    /// multipliers of the inequality constraints
+    z: Array1<f64>,
+    /// slack variables
+    s: Array1<f64>,
+}
+
+impl Default for InteriorPointQP {
+    fn default() -> Self {
+        InteriorPointQP::new()
+    }
+}
+
+impl InteriorPointQP {
+    /// Constructor
+    pub fn new() -> Self {
+        InteriorPointQP {
+            equality: None,
+            inequality: None,
+            tol: 1e-8,
+            q: Array2::zeros((0, 0)),
+            c: Array1::zeros(0),
+            a: Array2::zeros((0, 0)),
+            b: Array1::zeros(0),
+            g: Array2::zeros((0, 0)),
+            h: Array1::zeros(0),
+            y: Array1::zeros(0),
+            z: Array1::zeros(0),
+            s: Array1::zeros(0),
+        }
+    }
+
+    /// Set equality constraints `A x = b`
+    pub fn equality(mut self, a: Array2<f64>, b: Array1<f64>) -> Result<Self, Error> {
+        if a.nrows() != b.len() {
+            return Err(ArgminError::InvalidParameter {
+                text: "InteriorPointQP: A must have as many rows as b has entries.".to_string(),
+            }
+            .into());
+        }
+        self.equality = Some((a, b));
+        Ok(self)
+    }
+
+    /// Set inequality constraints `G x <= h`
+    pub fn inequality(mut self, g: Array2<f64>, h: Array1<f64>) -> Result<Self, Error> {
+        if g.nrows() != h.len() {
+            return Err(ArgminError::InvalidParameter {
+                text: "InteriorPointQP: G must have as many rows as h has entries.".to_string(),
+            }
+            .into());
+        }
+        self.inequality = Some((g, h));
+        Ok(self)
+    }
+
+    /// Set tolerance of the duality gap and the residuals (default: 1e-8)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "InteriorPointQP: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Multipliers of the equality and the inequality constraints of the current iterate
+    pub fn multipliers(&self) -> (&Array1<f64>, &Array1<f64>) {
+        (&self.y, &self.z)
+    }
+
+    /// Scale of the primal residual
+    fn primal_scale(&self) -> f64 {
+        1.0 + norm_inf(&self.b).max(norm_inf(&self.h))
+    }
+
+    /// Cost function value of `x` if `x` satisfies the constraints (within `tol`), `INFINITY`
+    /// otherwise. This prevents infeasible iterates from being reported as the best parameter
+    /// vector.
+    fn feasible_cost<O>(&self, op: &mut OpWrapper<O>, x: &Array1<f64>) -> Result<f64, Error>
+    where
+        O: ArgminOp<Param = Array1<f64>, Output = f64>,
+    {
+        let violation = norm_inf(&(self.a.dot(x) - &self.b))
+            .max((self.g.dot(x) - &self.h).fold(0.0, |acc, v| acc.max(*v)));
+        if violation > self.tol * self.primal_scale() {
+            return Ok(std::f64::INFINITY);
+        }
+        op.apply(x)
+    }
+
+    /// Residuals `(Q x + c + A^T y + G^T z, A x - b, G x + s - h)`
+    fn residuals(&self, x: &Array1<f64>) -> (Array1<f64>, Array1<f64>, Array1<f64>) {
+        let rd = self.q.dot(x)
+            + &self.c
+            + self.a.view().reversed_axes().dot(&self.y)
+            + self.g.view().reversed_axes().dot(&self.z);
+        let re = self.a.dot(x) - &self.b;
+        let ri = self.g.dot(x) + &self.s - &self.h;
+        (rd, re, ri)
+    }
+
+    /// Solve the Newton system for the right-hand side of the complementarity `rsz` by
+    /// eliminating `ds` and `dz` and solving the remaining symmetric indefinite system in `dx` and
+    /// `dy`.
+    fn direction(
+        &self,
+        rd: &Array1<f64>,
+        re: &Array1<f64>,
+        ri: &Array1<f64>,
+        rsz: &Array1<f64>,
+    ) -> Result<Direction, Error> {
+        let n = self.q.nrows();
+        let p = self.a.nrows();
+        let w = &self.z / &self.s;
+        let gw = &self.g * &w.view().insert_axis(Axis(1));
+        let k11 = &self.q + &self.g.view().reversed_axes().dot(&gw);
+
+        let mut kkt = vec![vec![0.0; n + p]; n + p];
+        for (i, row) in kkt.iter_mut().enumerate().take(n) {
+            for (j, v) in k11.row(i).iter().enumerate() {
+                row[j] = *v;
+            }
+            for (j, v) in self.a.column(i).iter().enumerate() {
+                row[n + j] = *v;
+            }
+        }
+        for (i, row) in kkt.iter_mut().skip(n).enumerate() {
+            for (j, v) in self.a.row(i).iter().enumerate() {
+                row[j] = *v;
+            }
+        }
+
+        let rhs_x = -rd
+            + &self
+                .g
+                .view()
+                .reversed_axes()
+                .dot(&((rsz - &(&self.z * ri)) / &self.s));
+        let rhs: Vec<f64> = rhs_x
+            .iter()
+            .chain(re.iter().map(|v| -v).collect::<Vec<f64>>().iter())
+            .cloned()
+            .collect();
+        let sol = linalg::solve(&kkt, &rhs)?;
+
+        let dx = Array1::from(sol[..n].to_vec());
+        let dy = Array1::from(sol[n..].to_vec());
+        let gdx = self.g.dot(&dx);
+        let dz = (-rsz + &(&self.z * ri) + &(&self.z * &gdx)) / &self.s;
+        let ds = -ri - &gdx;
+        Ok((dx, dy, dz, ds))
+    }
+}
+
+/// Largest `alpha <= 1` such that `v + alpha * dv >= 0`
+fn max_step(v: &Array1<f64>, dv: &Array1<f64>) -> f64 {
+    v.iter()
+        .zip(dv.iter())
+        .filter(|(_, &d)| d < 0.0)
+        .map(|(&vi, &d)| -vi / d)
+        .fold(1.0, f64::min)
+}
+
+/// Infinity norm
+fn norm_inf(v: &Array1<f64>) -> f64 {
+    v.iter().fold(0.0, |acc, x| acc.max(x.abs()))
+}
+
+impl<O> Solver<O> for InteriorPointQP
+where
+    O: ArgminOp<Param = Array1<f64>, Output = f64, Hessian = Array2<f64>>,
+{
+    const NAME: &'static str = "Interior-point QP";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let x = state.get_param();
+        let n = x.len();
+        self.q = op.hessian(&x)?;
+        self.c = op.gradient(&x)? - self.q.dot(&x);
+        let (a, b) = self
+            .equality
+            .clone()
+            .unwrap_or_else(|| (Array2::zeros((0, n)), Array1::zeros(0)));
+        let (g, h) = self
+            .inequality
+            .clone()
+            .unwrap_or_else(|| (Array2::zeros((0, n)), Array1::zeros(0)));
+        if self.q.shape() != [n, n] || a.ncols() != n || g.ncols() != n {
+            return Err(ArgminError::InvalidParameter {
+                text:
+                    "InteriorPointQP: dimensions of Q, A and G do not match the parameter vector."
+                        .to_string(),
+            }
+            .into());
+        }
+        self.s = (&h - &g.dot(&x)).mapv(|v| v.max(1.0));
+        self.z = Array1::ones(h.len());
+        self.y = Array1::zeros(b.len());
+        self.a = a;
+        self.b = b;
+        self.g = g;
+        self.h = h;
+        let cost = self.feasible_cost(op, &x)?;
+        Ok(Some(ArgminIterData::new().param(x).cost(cost)))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let mut x = state.get_param();
+        let m = self.s.len();
+        let (rd, re, ri) = self.residuals(&x);
+
+        let alpha = if m == 0 {
+            // equality constrained QP: a single Newton step solves the problem
+            let (dx, dy, _, _) = self.direction(&rd, &re, &ri, &Array1::zeros(0))?;
+            x += &dx;
+            self.y += &dy;
+            1.0
+        } else {
+            let mu = self.s.dot(&self.z) / m as f64;
+
+            // predictor
+            let rsz = &self.s * &self.z;
+            let (_, _, dz_aff, ds_aff) = self.direction(&rd, &re, &ri, &rsz)?;
+            let alpha_aff = max_step(&self.s, &ds_aff).min(max_step(&self.z, &dz_aff));
+            let mu_aff = (&self.s + &(&ds_aff * alpha_aff))
+                .dot(&(&self.z + &(&dz_aff * alpha_aff)))
+                / m as f64;
+            let sigma = (mu_aff / mu).powi(3);
+
+            // corrector
+            let rsz = rsz + &(&ds_aff * &dz_aff) - sigma * mu;
+            let (dx, dy, dz, ds) = self.direction(&rd, &re, &ri, &rsz)?;
+            let alpha =
+                (STEP_FRACTION * max_step(&self.s, &ds).min(max_step(&self.z, &dz))).min(1.0);
+
+            x += &(&dx * alpha);
+            self.y += &(&dy * alpha);
+            self.z += &(&dz * alpha);
+            self.s += &(&ds * alpha);
+            alpha
+        };
+
+        let (rd, re, ri) = self.residuals(&x);
+        let primal_res = norm_inf(&re).max(norm_inf(&ri));
+        let dual_res = norm_inf(&rd);
+        let gap = self.s.dot(&self.z);
+        let cost = self.feasible_cost(op, &x)?;
+
+        let dual_scale = 1.0 + norm_inf(&self.c);
+        let converged = primal_res <= self.tol * self.primal_scale()
+            && dual_res <= self.tol * dual_scale
+            && gap <= self.tol * (1.0 + cost.abs());
+
+        let out = ArgminIterData::new().param(x).cost(cost).kv(make_kv!(
+            "gap" => gap;
+            "primal_residual" => primal_res;
+            "dual_residual" => dual_res;
+            "alpha" => alpha;
+        ));
+        if converged {
+            return Ok(out.termination_reason(TerminationReason::TargetPrecisionReached));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+    use ndarray::array;
+
+    test_trait_impl!(interiorpoint, InteriorPointQP);
+
+    /// 1/2 x^T Q x + c^T x
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Quadratic {
+        q: Array2<f64>,
+        c: Array1<f64>,
+    }
+
+    impl ArgminOp for Quadratic {
+        type Param = Array1<f64>;
+        type Output = f64;
+        type Hessian = Array2<f64>;
+        type Jacobian = ();
+
+        fn apply(&self, p: &Array1<f64>) -> Result<f64, Error> {
+            Ok(0.5 * p.dot(&self.q.dot(p)) + self.c.dot(p))
+        }
+
+        fn gradient(&self, p: &Array1<f64>) -> Result<Array1<f64>, Error> {
+            Ok(self.q.dot(p) + &self.c)
+        }
+
+        fn hessian(&self, _p: &Array1<f64>) -> Result<Array2<f64>, Error> {
+            Ok(self.q.clone())
+        }
+    }
+
+    #[test]
+    fn test_inequality_constrained() {
+        // Nocedal & Wright, Example 16.4: solution [1.4, 1.7]
+        let op = Quadratic {
+            q: array![[2.0, 0.0], [0.0, 2.0]],
+            c: array![-2.0, -5.0],
+        };
+        let g = array![
+            [-1.0, 2.0],
+            [1.0, 2.0],
+            [1.0, -2.0],
+            [-1.0, 0.0],
+            [0.0, -1.0]
+        ];
+        let h = array![2.0, 6.0, 2.0, 0.0, 0.0];
+        let solver = InteriorPointQP::new().inequality(g, h).unwrap();
+        let res = Executor::new(op, solver, array![2.0, 0.0])
+            .max_iters(50)
+            .run()
+            .unwrap();
+        let x = res.state.get_param();
+        assert!((x[0] - 1.4).abs() < 1e-6);
+        assert!((x[1] - 1.7).abs() < 1e-6);
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+    }
+
+    #[test]
+    fn test_equality_constrained() {
+        let op = Quadratic {
+            q: array![[1.0, 0.0], [0.0, 1.0]],
+            c: array![0.0, 0.0],
+        };
+        let solver = InteriorPointQP::new()
+            .equality(array![[1.0, 1.0]], array![1.0])
+            .unwrap();
+        let res = Executor::new(op, solver, array![0.0, 0.0])
+            .max_iters(10)
+            .run()
+            .unwrap();
+        let x = res.state.get_param();
+        assert!((x[0] - 0.5).abs() < 1e-10);
+        assert!((x[1] - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_dimensions() {
+        assert!(InteriorPointQP::new()
+            .equality(array![[1.0, 1.0]], array![1.0, 2.0])
+            .is_err());
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Solvers for convex quadratic programs
+//!
+//! ```text
+//! min 1/2 x^T Q x + c^T x  subject to  A x = b,  G x <= h
+//! ```
+//!
+//! The objective is provided via `ArgminOp`: `hessian` returns `Q` and `gradient` returns
+//! `Q x + c`. The linear constraints are passed to the solvers directly.
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+/// Active-set method
+pub mod activeset;
+/// Mehrotra predictor-corrector interior-point method
+#[cfg(feature = "ndarrayl")]
+pub mod interiorpoint;
+
+pub use self::activeset::*;
+#[cfg(feature = "ndarrayl")]
+pub use self::interiorpoint::*;
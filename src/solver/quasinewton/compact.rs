@@ -0,0 +1,439 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] Richard H. Byrd, Jorge Nocedal and Robert B. Schnabel (1994). Representations of
+//! quasi-Newton matrices and their use in limited memory methods. Mathematical Programming 63,
+//! 129-156.
+
+use crate::linalg;
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Limited memory BFGS matrix in compact representation
+///
+/// Stores the last `m` pairs `s_i = x_{i+1} - x_i` and `y_i = g_{i+1} - g_i` and provides products
+/// of the L-BFGS approximation of the Hessian `B` and of its inverse `H` with vectors, using the
+/// compact representations
+///
+/// ```text
+/// B = theta * I - W M W^T,  W = [Y, theta * S],  M^{-1} = [[-D, L^T], [L, theta * S^T S]]
+///
+/// H = gamma * I + [S, gamma * Y] [[R^{-T} (D + gamma * Y^T Y) R^{-1}, -R^{-T}], [-R^{-1}, 0]]
+///                                [S, gamma * Y]^T
+/// ```
+///
+/// where `D`, `L` and `R` are the diagonal, the strictly lower triangular and the upper triangular
+/// part of `S^T Y`, `theta = y^T y / s^T y` of the most recent pair and `gamma = 1 / theta`. Apart
+/// from the stored pairs, only matrices of size `2m x 2m` are required.
+///
+/// `LBFGSMatrix` implements `ArgminDot` (computing `B * v`) and can therefore be used as Hessian
+/// (approximation) in trust region subproblem solvers such as `Steihaug` or `GLTR`.
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+///
+/// [1] Richard H. Byrd, Jorge Nocedal and Robert B. Schnabel (1994). Representations of
+/// quasi-Newton matrices and their use in limited memory methods. Mathematical Programming 63,
+/// 129-156.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default)]
+pub struct LBFGSMatrix<P> {
+    /// memory size
+    m: usize,
+    /// s_i
+    s: VecDeque<P>,
+    /// y_i
+    y: VecDeque<P>,
+    /// S^T Y
+    sy: Vec<Vec<f64>>,
+    /// S^T S
+    ss: Vec<Vec<f64>>,
+    /// Y^T Y
+    yy: Vec<Vec<f64>>,
+    /// theta
+    theta: f64,
+    /// M
+    m_mat: Vec<Vec<f64>>,
+}
+
+impl<P> LBFGSMatrix<P>
+where
+    P: Clone + ArgminDot<P, f64> + ArgminScaledAdd<P, f64, P> + ArgminMul<f64, P>,
+{
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// `m`: number of stored pairs
+    pub fn new(m: usize) -> Result<Self, Error> {
+        if m == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "LBFGSMatrix: m must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(LBFGSMatrix {
+            m,
+            s: VecDeque::with_capacity(m),
+            y: VecDeque::with_capacity(m),
+            sy: vec![],
+            ss: vec![],
+            yy: vec![],
+            theta: 1.0,
+            m_mat: vec![],
+        })
+    }
+
+    /// Number of stored pairs
+    pub fn len(&self) -> usize {
+        self.s.len()
+    }
+
+    /// Returns `true` if no pairs are stored (`B = H = I`)
+    pub fn is_empty(&self) -> bool {
+        self.s.is_empty()
+    }
+
+    /// Scaling `theta` of the initial matrix `B_0 = theta * I`
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    /// Adds the pair `s`, `y` and discards the oldest pair if more than `m` pairs are stored.
+    ///
+    /// Pairs which violate the curvature condition `s^T y > 0` are skipped, in which case `false`
+    /// is returned.
+    pub fn update(&mut self, s: P, y: P) -> Result<bool, Error> {
+        let sy: f64 = s.dot(&y);
+        let yy: f64 = y.dot(&y);
+        if sy <= std::f64::EPSILON * yy.max(std::f64::MIN_POSITIVE) {
+            return Ok(false);
+        }
+        if self.s.len() == self.m {
+            self.s.pop_front();
+            self.y.pop_front();
+            for mat in [&mut self.sy, &mut self.ss, &mut self.yy].iter_mut() {
+                mat.remove(0);
+                for row in mat.iter_mut() {
+                    row.remove(0);
+                }
+            }
+        }
+        self.s.push_back(s);
+        self.y.push_back(y);
+
+        // new row and column of the Gram matrices
+        let k = self.s.len();
+        let (s_new, y_new) = (&self.s[k - 1], &self.y[k - 1]);
+        let grow = |mat: &mut Vec<Vec<f64>>, row: Vec<f64>, col: Vec<f64>| {
+            for (r, c) in mat.iter_mut().zip(col) {
+                r.push(c);
+            }
+            mat.push(row);
+        };
+        let sy_row: Vec<f64> = self.y.iter().map(|yj| s_new.dot(yj)).collect();
+        let sy_col: Vec<f64> = self.s.iter().take(k - 1).map(|si| si.dot(y_new)).collect();
+        let ss_row: Vec<f64> = self.s.iter().map(|sj| s_new.dot(sj)).collect();
+        let yy_row: Vec<f64> = self.y.iter().map(|yj| y_new.dot(yj)).collect();
+        grow(&mut self.sy, sy_row, sy_col);
+        grow(&mut self.ss, ss_row.clone(), ss_row);
+        grow(&mut self.yy, yy_row.clone(), yy_row);
+
+        self.theta = yy / sy;
+
+        // M^{-1} = [[-D, L^T], [L, theta * S^T S]]
+        let mut m_inv = vec![vec![0.0; 2 * k]; 2 * k];
+        for i in 0..k {
+            m_inv[i][i] = -self.sy[i][i];
+            for j in 0..k {
+                m_inv[k + i][k + j] = self.theta * self.ss[i][j];
+                if i > j {
+                    m_inv[k + i][j] = self.sy[i][j];
+                    m_inv[j][k + i] = self.sy[i][j];
+                }
+            }
+        }
+        let cols = (0..2 * k)
+            .map(|j| {
+                let e: Vec<f64> = (0..2 * k).map(|i| if i == j { 1.0 } else { 0.0 }).collect();
+                linalg::solve(&m_inv, &e)
+            })
+            .collect::<Result<Vec<Vec<f64>>, Error>>()?;
+        self.m_mat = (0..2 * k)
+            .map(|i| (0..2 * k).map(|j| cols[j][i]).collect())
+            .collect();
+        Ok(true)
+    }
+
+    /// Computes `B * v`
+    pub fn bv(&self, v: &P) -> P {
+        let k = self.s.len();
+        // u = W^T v
+        let u: Vec<f64> = self
+            .y
+            .iter()
+            .map(|yi| yi.dot(v))
+            .chain(self.s.iter().map(|si| self.theta * si.dot(v)))
+            .collect();
+        let z: Vec<f64> = self
+            .m_mat
+            .iter()
+            .map(|row| row.iter().zip(u.iter()).map(|(a, b)| a * b).sum())
+            .collect();
+        (0..k).fold(v.mul(&self.theta), |acc, i| {
+            acc.scaled_add(&(-z[i]), &self.y[i])
+                .scaled_add(&(-self.theta * z[k + i]), &self.s[i])
+        })
+    }
+
+    /// Computes `H * v = B^{-1} * v`
+    pub fn hv(&self, v: &P) -> P {
+        let k = self.s.len();
+        let gamma = 1.0 / self.theta;
+        // lower triangular R^T
+        let rt: Vec<Vec<f64>> = (0..k)
+            .map(|i| {
+                (0..k)
+                    .map(|j| if j <= i { self.sy[j][i] } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+        let a: Vec<f64> = self.s.iter().map(|si| si.dot(v)).collect();
+        let b: Vec<f64> = self.y.iter().map(|yi| yi.dot(v)).collect();
+        // c = R^{-1} a
+        let c = linalg::backward_substitution(&rt, &a);
+        // top = R^{-T} ((D + gamma * Y^T Y) c - gamma * b)
+        let rhs: Vec<f64> = (0..k)
+            .map(|i| {
+                self.sy[i][i] * c[i]
+                    + gamma
+                        * self.yy[i]
+                            .iter()
+                            .zip(c.iter())
+                            .map(|(p, q)| p * q)
+                            .sum::<f64>()
+                    - gamma * b[i]
+            })
+            .collect();
+        let top = linalg::forward_substitution(&rt, &rhs);
+        (0..k).fold(v.mul(&gamma), |acc, i| {
+            acc.scaled_add(&top[i], &self.s[i])
+                .scaled_add(&(-gamma * c[i]), &self.y[i])
+        })
+    }
+}
+
+impl LBFGSMatrix<Vec<f64>> {
+    /// Computes `(Z^T B Z)^{-1} * r`, where the columns of `Z` are the unit vectors of the
+    /// variables in `free`, using the Sherman-Morrison-Woodbury formula
+    ///
+    /// ```text
+    /// (Z^T B Z)^{-1} = gamma * I + gamma^2 * W_F (I - gamma * M W_F^T W_F)^{-1} M W_F^T
+    /// ```
+    ///
+    /// with `W_F = Z^T W`. Only a system of size `2m x 2m` needs to be solved.
+    pub(crate) fn reduced_hv(&self, free: &[usize], r: &[f64]) -> Result<Vec<f64>, Error> {
+        let gamma = 1.0 / self.theta;
+        if self.is_empty() {
+            return Ok(r.iter().map(|ri| gamma * ri).collect());
+        }
+        let dot =
+            |a: &[f64], b: &[f64]| -> f64 { a.iter().zip(b.iter()).map(|(p, q)| p * q).sum() };
+        // columns of W_F = [Y_F, theta * S_F]
+        let wf: Vec<Vec<f64>> = self
+            .y
+            .iter()
+            .map(|yi| free.iter().map(|&i| yi[i]).collect())
+            .chain(
+                self.s
+                    .iter()
+                    .map(|si| free.iter().map(|&i| self.theta * si[i]).collect()),
+            )
+            .collect();
+        let k2 = wf.len();
+        let wtw: Vec<Vec<f64>> = wf
+            .iter()
+            .map(|wi| wf.iter().map(|wj| dot(wi, wj)).collect())
+            .collect();
+        // N = I - gamma * M W_F^T W_F
+        let n_mat: Vec<Vec<f64>> = (0..k2)
+            .map(|i| {
+                (0..k2)
+                    .map(|j| {
+                        let mw: f64 = (0..k2).map(|l| self.m_mat[i][l] * wtw[l][j]).sum();
+                        if i == j {
+                            1.0 - gamma * mw
+                        } else {
+                            -gamma * mw
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let wtr: Vec<f64> = wf.iter().map(|wi| dot(wi, r)).collect();
+        let rhs: Vec<f64> = self.m_mat.iter().map(|row| dot(row, &wtr)).collect();
+        let w = linalg::solve(&n_mat, &rhs)?;
+        Ok(r.iter()
+            .enumerate()
+            .map(|(k, rk)| {
+                gamma * rk
+                    + gamma
+                        * gamma
+                        * wf.iter()
+                            .zip(w.iter())
+                            .map(|(c, wi)| c[k] * wi)
+                            .sum::<f64>()
+            })
+            .collect())
+    }
+}
+
+impl<P> ArgminDot<P, P> for LBFGSMatrix<P>
+where
+    P: Clone + ArgminDot<P, f64> + ArgminScaledAdd<P, f64, P> + ArgminMul<f64, P>,
+{
+    fn dot(&self, other: &P) -> P {
+        self.bv(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(lbfgs_matrix, LBFGSMatrix<Vec<f64>>);
+
+    fn pairs() -> Vec<(Vec<f64>, Vec<f64>)> {
+        vec![
+            (vec![1.0, 0.5, -0.2], vec![2.0, 0.3, 0.1]),
+            (vec![-0.3, 1.0, 0.4], vec![0.1, 1.5, 0.6]),
+            (vec![0.2, -0.1, 1.0], vec![0.3, 0.2, 3.0]),
+            (vec![0.5, 0.5, 0.5], vec![1.0, 0.9, 1.7]),
+        ]
+    }
+
+    fn mat_vec(b: &[Vec<f64>], v: &[f64]) -> Vec<f64> {
+        b.iter()
+            .map(|row| row.iter().zip(v.iter()).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    /// Dense BFGS updates of `B_0 = theta * I` with the last `m` pairs
+    fn dense_bfgs(pairs: &[(Vec<f64>, Vec<f64>)], theta: f64) -> Vec<Vec<f64>> {
+        let n = pairs[0].0.len();
+        let mut b: Vec<Vec<f64>> = (0..n)
+            .map(|i| (0..n).map(|j| if i == j { theta } else { 0.0 }).collect())
+            .collect();
+        for (s, y) in pairs {
+            let bs = mat_vec(&b, s);
+            let sbs: f64 = s.iter().zip(bs.iter()).map(|(a, b)| a * b).sum();
+            let sy: f64 = s.iter().zip(y.iter()).map(|(a, b)| a * b).sum();
+            for i in 0..n {
+                for j in 0..n {
+                    b[i][j] += -bs[i] * bs[j] / sbs + y[i] * y[j] / sy;
+                }
+            }
+        }
+        b
+    }
+
+    #[test]
+    fn test_compact_representation() {
+        let mut mat = LBFGSMatrix::new(3).unwrap();
+        for (s, y) in pairs() {
+            assert!(mat.update(s, y).unwrap());
+        }
+        assert_eq!(mat.len(), 3);
+        let dense = dense_bfgs(&pairs()[1..], mat.theta());
+
+        let v = vec![0.7, -1.3, 0.4];
+        let bv = mat.bv(&v);
+        for (a, b) in bv.iter().zip(mat_vec(&dense, &v).iter()) {
+            assert!((a - b).abs() < 1e-10);
+        }
+        let hbv = mat.hv(&bv);
+        for (a, b) in hbv.iter().zip(v.iter()) {
+            assert!((a - b).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_reduced_hv() {
+        let mut mat = LBFGSMatrix::new(3).unwrap();
+        for (s, y) in pairs() {
+            mat.update(s, y).unwrap();
+        }
+        let dense = dense_bfgs(&pairs()[1..], mat.theta());
+        // (Z^T B Z) restricted to the variables 0 and 2
+        let free = [0, 2];
+        let reduced: Vec<Vec<f64>> = free
+            .iter()
+            .map(|&i| free.iter().map(|&j| dense[i][j]).collect())
+            .collect();
+        let r = vec![0.3, -1.1];
+        let x = mat.reduced_hv(&free, &r).unwrap();
+        for (a, b) in mat_vec(&reduced, &x).iter().zip(r.iter()) {
+            assert!((a - b).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_curvature_condition() {
+        let mut mat: LBFGSMatrix<Vec<f64>> = LBFGSMatrix::new(3).unwrap();
+        assert!(!mat.update(vec![1.0, 0.0], vec![-1.0, 0.0]).unwrap());
+        assert!(mat.is_empty());
+        let v = vec![1.0, 2.0];
+        assert_eq!(mat.bv(&v), v);
+        assert_eq!(mat.hv(&v), v);
+        assert!(LBFGSMatrix::<Vec<f64>>::new(0).is_err());
+    }
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Model {}
+
+    impl ArgminOp for Model {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = LBFGSMatrix<Vec<f64>>;
+        type Jacobian = ();
+    }
+
+    #[test]
+    fn test_trust_region_subproblem() {
+        // with a large radius, Steihaug computes the quasi-Newton step -H g
+        let mut mat = LBFGSMatrix::new(5).unwrap();
+        for (s, y) in pairs() {
+            mat.update(s, y).unwrap();
+        }
+        let g = vec![1.0, -2.0, 0.5];
+        let expected = mat.hv(&g);
+        let mut solver = crate::solver::trustregion::Steihaug::new()
+            .epsilon(1e-12)
+            .unwrap();
+        solver.set_radius(100.0);
+        let res = Executor::new(Model {}, solver, vec![0.0; 3])
+            .grad(g)
+            .hessian(mat)
+            .max_iters(10)
+            .run()
+            .unwrap();
+        for (a, b) in res.state.get_param().iter().zip(expected.iter()) {
+            assert!((a + b).abs() < 1e-8);
+        }
+    }
+}
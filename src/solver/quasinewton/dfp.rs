@@ -11,11 +11,15 @@
 //! Springer. ISBN 0-387-30303-0.
 
 use crate::prelude::*;
+use crate::solver::quasinewton::{DFPUpdate, QuasiNewton};
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 
 /// DFP method
 ///
+/// Quasi-Newton method with `DFPUpdate`. The update is skipped if the curvature condition is
+/// violated. See `QuasiNewton` for details.
+///
 /// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/dfp.rs)
 ///
 /// # References:
@@ -25,47 +29,33 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 pub struct DFP<L, H> {
-    /// Inverse Hessian
-    inv_hessian: H,
-    /// line search
-    linesearch: L,
+    /// quasi-Newton method with DFP update
+    qn: QuasiNewton<L, H, DFPUpdate>,
 }
 
 impl<L, H> DFP<L, H> {
     /// Constructor
     pub fn new(init_inverse_hessian: H, linesearch: L) -> Self {
         DFP {
-            inv_hessian: init_inverse_hessian,
-            linesearch,
+            qn: QuasiNewton::new(init_inverse_hessian, linesearch, DFPUpdate::new())
+                .scale_initial_hessian(false),
         }
     }
+
+    /// Scale the initial inverse Hessian with `s^T y / y^T y` before the first update
+    /// (default: false)
+    pub fn scale_initial_hessian(mut self, scale: bool) -> Self {
+        self.qn = self.qn.scale_initial_hessian(scale);
+        self
+    }
 }
 
 impl<O, L, H> Solver<O> for DFP<L, H>
 where
     O: ArgminOp<Output = f64, Hessian = H>,
-    O::Param: Clone
-        + Default
-        + SerializeAlias
-        + ArgminSub<O::Param, O::Param>
-        + ArgminDot<O::Param, f64>
-        + ArgminDot<O::Param, O::Hessian>
-        + ArgminScaledAdd<O::Param, f64, O::Param>
-        + ArgminNorm<f64>
-        + ArgminMul<f64, O::Param>
-        + ArgminTranspose,
-    O::Hessian: Clone
-        + Default
-        + SerializeAlias
-        + DeserializeOwnedAlias
-        + ArgminSub<O::Hessian, O::Hessian>
-        + ArgminDot<O::Param, O::Param>
-        + ArgminDot<O::Hessian, O::Hessian>
-        + ArgminAdd<O::Hessian, O::Hessian>
-        + ArgminMul<f64, O::Hessian>
-        + ArgminTranspose
-        + ArgminEye,
-    L: Clone + ArgminLineSearch<O::Param> + Solver<OpWrapper<O>>,
+    QuasiNewton<L, H, DFPUpdate>: Solver<O>,
+    L: SerializeAlias,
+    H: SerializeAlias,
 {
     const NAME: &'static str = "DFP";
 
@@ -74,12 +64,7 @@ where
         op: &mut OpWrapper<O>,
         state: &IterState<O>,
     ) -> Result<Option<ArgminIterData<O>>, Error> {
-        let param = state.get_param();
-        let cost = op.apply(&param)?;
-        let grad = op.gradient(&param)?;
-        Ok(Some(
-            ArgminIterData::new().param(param).cost(cost).grad(grad),
-        ))
+        self.qn.init(op, state)
     }
 
     fn next_iter(
@@ -87,62 +72,11 @@ where
         op: &mut OpWrapper<O>,
         state: &IterState<O>,
     ) -> Result<ArgminIterData<O>, Error> {
-        let param = state.get_param();
-        let cost = state.get_cost();
-        let prev_grad = if let Some(grad) = state.get_grad() {
-            grad
-        } else {
-            op.gradient(&param)?
-        };
-        let p = self.inv_hessian.dot(&prev_grad).mul(&(-1.0));
-
-        self.linesearch.set_search_direction(p);
-
-        let ArgminResult {
-            operator: line_op,
-            state:
-                IterState {
-                    param: xk1,
-                    cost: next_cost,
-                    ..
-                },
-        } = Executor::new(
-            OpWrapper::new_from_op(&op),
-            self.linesearch.clone(),
-            param.clone(),
-        )
-        .grad(prev_grad.clone())
-        .cost(cost)
-        .ctrlc(false)
-        .run()?;
-
-        // take care of function eval counts
-        op.consume_op(line_op);
-
-        let grad = op.gradient(&xk1)?;
-        let yk = grad.sub(&prev_grad);
-
-        let sk = xk1.sub(&param);
-
-        let yksk: f64 = yk.dot(&sk);
-
-        let sksk: O::Hessian = sk.dot(&sk);
-
-        let tmp3: O::Param = self.inv_hessian.dot(&yk);
-        let tmp4: f64 = tmp3.dot(&yk);
-        let tmp3: O::Hessian = tmp3.dot(&tmp3);
-        let tmp3: O::Hessian = tmp3.mul(&(1.0f64 / tmp4));
-
-        self.inv_hessian = self.inv_hessian.sub(&tmp3).add(&sksk.mul(&(1.0f64 / yksk)));
-
-        Ok(ArgminIterData::new().param(xk1).cost(next_cost).grad(grad))
+        self.qn.next_iter(op, state)
     }
 
     fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
-        if state.get_grad().unwrap().norm() < std::f64::EPSILON.sqrt() {
-            return TerminationReason::TargetPrecisionReached;
-        }
-        TerminationReason::NotTerminated
+        self.qn.terminate(state)
     }
 }
 
@@ -11,6 +11,7 @@
 //! Springer. ISBN 0-387-30303-0.
 
 use crate::prelude::*;
+use crate::solver::quasinewton::LBFGSMatrix;
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
@@ -20,7 +21,10 @@ use std::fmt::Debug;
 ///
 /// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/lbfgs.rs)
 ///
-/// TODO: Implement compact representation of BFGS updating (Nocedal/Wright p.230)
+/// The search direction is computed with the two-loop recursion. Products of the L-BFGS
+/// approximation of the Hessian (or its inverse) with arbitrary vectors, for instance in trust
+/// region subproblems, are provided by the compact representation in `LBFGSMatrix`, which can be
+/// obtained from the current memory via `memory`.
 ///
 /// # References:
 ///
@@ -51,6 +55,21 @@ impl<L, P> LBFGS<L, P> {
     }
 }
 
+impl<L, P> LBFGS<L, P>
+where
+    P: Clone + ArgminDot<P, f64> + ArgminScaledAdd<P, f64, P> + ArgminMul<f64, P>,
+{
+    /// Returns the stored pairs `s_i`, `y_i` as `LBFGSMatrix`. Pairs which violate the curvature
+    /// condition are skipped.
+    pub fn memory(&self) -> Result<LBFGSMatrix<P>, Error> {
+        let mut mat = LBFGSMatrix::new(self.m.max(1))?;
+        for (s, y) in self.s.iter().zip(self.y.iter()) {
+            mat.update(s.clone(), y.clone())?;
+        }
+        Ok(mat)
+    }
+}
+
 impl<O, L, P> Solver<O> for LBFGS<L, P>
 where
     O: ArgminOp<Param = P, Output = f64>,
@@ -93,35 +112,7 @@ where
         let prev_grad = state.get_grad().unwrap();
         // .unwrap_or_else(|| op.gradient(&param).unwrap());
 
-        let gamma: f64 = if let (Some(ref sk), Some(ref yk)) = (self.s.back(), self.y.back()) {
-            sk.dot(*yk) / yk.dot(*yk)
-        } else {
-            1.0
-        };
-
-        // L-BFGS two-loop recursion
-        let mut q = prev_grad.clone();
-        let cur_m = self.s.len();
-        let mut alpha: Vec<f64> = vec![0.0; cur_m];
-        let mut rho: Vec<f64> = vec![0.0; cur_m];
-        for (i, (ref sk, ref yk)) in self.s.iter().rev().zip(self.y.iter().rev()).enumerate() {
-            let sk = *sk;
-            let yk = *yk;
-            let yksk: f64 = yk.dot(sk);
-            let rho_t = 1.0 / yksk;
-            let skq: f64 = sk.dot(&q);
-            let alpha_t = skq.mul(&rho_t);
-            q = q.sub(&yk.mul(&alpha_t));
-            rho[cur_m - i - 1] = rho_t;
-            alpha[cur_m - i - 1] = alpha_t;
-        }
-        let mut r = q.mul(&gamma);
-        for (i, (ref sk, ref yk)) in self.s.iter().zip(self.y.iter()).enumerate() {
-            let sk = *sk;
-            let yk = *yk;
-            let beta = yk.dot(&r).mul(&rho[i]);
-            r = r.add(&sk.mul(&(alpha[i] - beta)));
-        }
+        let (r, gamma) = two_loop_recursion(&self.s, &self.y, &prev_grad);
 
         self.linesearch.set_search_direction(r.mul(&-1.0));
 
@@ -175,6 +166,46 @@ where
     }
 }
 
+/// L-BFGS two-loop recursion
+///
+/// Computes `H * g`, where `H` is the L-BFGS approximation of the inverse Hessian defined by the
+/// pairs `s` and `y` and the initial matrix `gamma * I` with `gamma = s^T y / y^T y` of the most
+/// recent pair. Returns `H * g` and `gamma`.
+pub(crate) fn two_loop_recursion<P>(s: &VecDeque<P>, y: &VecDeque<P>, g: &P) -> (P, f64)
+where
+    P: Clone + ArgminSub<P, P> + ArgminAdd<P, P> + ArgminDot<P, f64> + ArgminMul<f64, P>,
+{
+    let gamma: f64 = if let (Some(ref sk), Some(ref yk)) = (s.back(), y.back()) {
+        sk.dot(*yk) / yk.dot(*yk)
+    } else {
+        1.0
+    };
+
+    let mut q = g.clone();
+    let cur_m = s.len();
+    let mut alpha: Vec<f64> = vec![0.0; cur_m];
+    let mut rho: Vec<f64> = vec![0.0; cur_m];
+    for (i, (ref sk, ref yk)) in s.iter().rev().zip(y.iter().rev()).enumerate() {
+        let sk = *sk;
+        let yk = *yk;
+        let yksk: f64 = yk.dot(sk);
+        let rho_t = 1.0 / yksk;
+        let skq: f64 = sk.dot(&q);
+        let alpha_t = skq.mul(&rho_t);
+        q = q.sub(&yk.mul(&alpha_t));
+        rho[cur_m - i - 1] = rho_t;
+        alpha[cur_m - i - 1] = alpha_t;
+    }
+    let mut r = q.mul(&gamma);
+    for (i, (ref sk, ref yk)) in s.iter().zip(y.iter()).enumerate() {
+        let sk = *sk;
+        let yk = *yk;
+        let beta = yk.dot(&r).mul(&rho[i]);
+        r = r.add(&sk.mul(&(alpha[i] - beta)));
+    }
+    (r, gamma)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,4 +215,24 @@ mod tests {
     type Operator = MinimalNoOperator;
 
     test_trait_impl!(lbfgs, LBFGS<Operator, MoreThuenteLineSearch<Operator>>);
+
+    #[test]
+    fn test_memory() {
+        let mut solver: LBFGS<MoreThuenteLineSearch<Vec<f64>>, Vec<f64>> =
+            LBFGS::new(MoreThuenteLineSearch::new(), 3);
+        assert!(solver.memory().unwrap().is_empty());
+        solver.s.push_back(vec![1.0, 0.5, -0.2]);
+        solver.y.push_back(vec![2.0, 0.3, 0.1]);
+        solver.s.push_back(vec![-0.3, 1.0, 0.4]);
+        solver.y.push_back(vec![0.1, 1.5, 0.6]);
+
+        let mat = solver.memory().unwrap();
+        assert_eq!(mat.len(), 2);
+        let g = vec![0.7, -1.3, 0.4];
+        let (hg, gamma) = two_loop_recursion(&solver.s, &solver.y, &g);
+        assert!((gamma * mat.theta() - 1.0).abs() < 1e-12);
+        for (a, b) in mat.hv(&g).iter().zip(hg.iter()) {
+            assert!((a - b).abs() < 1e-10);
+        }
+    }
 }
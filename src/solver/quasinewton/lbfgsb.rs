@@ -0,0 +1,614 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Richard H. Byrd, Peihuang Lu, Jorge Nocedal and Ciyou Zhu (1995). A Limited Memory
+//! Algorithm for Bound Constrained Optimization. SIAM J. Sci. Comput. 16(5), 1190-1208.
+//! DOI: https://doi.org/10.1137/0916069
+//!
+//! [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::prelude::*;
+use crate::solver::quasinewton::LBFGSMatrix;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// L-BFGS-B method
+///
+/// Limited memory BFGS for bound constrained problems of the form `min f(x)` subject to
+/// `l <= x <= u`. In each iteration, the generalized Cauchy point along the projected steepest
+/// descent path is computed, followed by a minimization of the quadratic model over the
+/// variables which are not at a bound. Bounds may be infinite. The initial parameter vector is
+/// projected onto the feasible box.
+///
+/// The cost function is never evaluated outside of the box. The line search is not aware of the
+/// bounds and is therefore only used if no bound restricts the step along the search direction.
+/// Otherwise the step is found by backtracking from the largest feasible step (at most 1) until
+/// the sufficient decrease condition holds. If no such step is found, the method terminates with
+/// `TerminationReason::Aborted`.
+///
+/// Convergence is declared once the infinity norm of the projected gradient drops below `pgtol`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/lbfgsb.rs)
+///
+/// # References:
+///
+/// [0] Richard H. Byrd, Peihuang Lu, Jorge Nocedal and Ciyou Zhu (1995). A Limited Memory
+/// Algorithm for Bound Constrained Optimization. SIAM J. Sci. Comput. 16(5), 1190-1208.
+/// DOI: https://doi.org/10.1137/0916069
+///
+/// [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct LBFGSB<L> {
+    /// line search
+    linesearch: L,
+    /// lower bounds
+    lower: Vec<f64>,
+    /// upper bounds
+    upper: Vec<f64>,
+    /// tolerance for the infinity norm of the projected gradient
+    pgtol: f64,
+    /// L-BFGS approximation of the Hessian
+    b: LBFGSMatrix<Vec<f64>>,
+}
+
+impl<L> LBFGSB<L> {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `linesearch`: line search
+    /// * `m`: number of stored correction pairs
+    /// * `lower`: lower bounds (may contain `std::f64::NEG_INFINITY`)
+    /// * `upper`: upper bounds (may contain `std::f64::INFINITY`)
+    pub fn new(linesearch: L, m: usize, lower: Vec<f64>, upper: Vec<f64>) -> Result<Self, Error> {
+        if lower.len() != upper.len() {
+            return Err(ArgminError::InvalidParameter {
+                text: "L-BFGS-B: lower and upper bounds must have the same length.".to_string(),
+            }
+            .into());
+        }
+        if lower.iter().zip(upper.iter()).any(|(l, u)| l > u) {
+            return Err(ArgminError::InvalidParameter {
+                text: "L-BFGS-B: lower bounds must not be larger than upper bounds.".to_string(),
+            }
+            .into());
+        }
+        Ok(LBFGSB {
+            linesearch,
+            lower,
+            upper,
+            pgtol: 1e-5,
+            b: LBFGSMatrix::new(m)?,
+        })
+    }
+
+    /// Set tolerance for the infinity norm of the projected gradient
+    pub fn pgtol(mut self, pgtol: f64) -> Result<Self, Error> {
+        if pgtol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "L-BFGS-B: pgtol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.pgtol = pgtol;
+        Ok(self)
+    }
+
+    /// Project `x` onto the feasible box
+    fn project(&self, x: &[f64]) -> Vec<f64> {
+        x.iter()
+            .zip(self.lower.iter().zip(self.upper.iter()))
+            .map(|(xi, (l, u))| xi.max(*l).min(*u))
+            .collect()
+    }
+
+    /// Infinity norm of the projected gradient `P(x - g) - x`
+    fn projected_gradient_norm(&self, x: &[f64], g: &[f64]) -> f64 {
+        let xg: Vec<f64> = x.iter().zip(g.iter()).map(|(xi, gi)| xi - gi).collect();
+        self.project(&xg)
+            .iter()
+            .zip(x.iter())
+            .map(|(p, xi)| (p - xi).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Largest `alpha` such that `x + alpha * d` is feasible
+    fn max_step(&self, x: &[f64], d: &[f64]) -> f64 {
+        x.iter()
+            .zip(d.iter())
+            .zip(self.lower.iter().zip(self.upper.iter()))
+            .map(|((xi, di), (l, u))| {
+                if *di > 0.0 {
+                    (u - xi) / di
+                } else if *di < 0.0 {
+                    (l - xi) / di
+                } else {
+                    std::f64::INFINITY
+                }
+            })
+            .fold(std::f64::INFINITY, f64::min)
+    }
+
+    /// Backtracking from the step `alpha` along the descent direction `d` until the sufficient
+    /// decrease condition holds. Returns `None` if no such step is found.
+    fn feasible_step<O>(
+        &self,
+        op: &mut OpWrapper<O>,
+        x: &[f64],
+        cost: f64,
+        g: &[f64],
+        d: &[f64],
+        mut alpha: f64,
+    ) -> Result<Option<(Vec<f64>, f64)>, Error>
+    where
+        O: ArgminOp<Param = Vec<f64>, Output = f64>,
+    {
+        let slope = dot(g, d);
+        for _ in 0..50 {
+            let xt: Vec<f64> = x
+                .iter()
+                .zip(d.iter())
+                .map(|(xi, di)| xi + alpha * di)
+                .collect();
+            // removes rounding errors at the bounds
+            let xt = self.project(&xt);
+            let ft = op.apply(&xt)?;
+            if ft <= cost + 1e-4 * alpha * slope {
+                return Ok(Some((xt, ft)));
+            }
+            alpha *= 0.5;
+        }
+        Ok(None)
+    }
+
+    /// Computes the generalized Cauchy point, i.e. the first local minimizer of the quadratic
+    /// model along the projected steepest descent path `P(x - t * g)`.
+    #[allow(clippy::needless_range_loop)]
+    fn cauchy_point(&self, x: &[f64], g: &[f64]) -> Vec<f64> {
+        let n = x.len();
+        // breakpoints
+        let t: Vec<f64> = (0..n)
+            .map(|i| {
+                if g[i] < 0.0 {
+                    (x[i] - self.upper[i]) / g[i]
+                } else if g[i] > 0.0 {
+                    (x[i] - self.lower[i]) / g[i]
+                } else {
+                    std::f64::INFINITY
+                }
+            })
+            .collect();
+        let mut d: Vec<f64> = (0..n)
+            .map(|i| if t[i] <= 0.0 { 0.0 } else { -g[i] })
+            .collect();
+        let mut order: Vec<usize> = (0..n).filter(|&i| t[i] > 0.0).collect();
+        order.sort_by(|&a, &b| t[a].partial_cmp(&t[b]).unwrap());
+
+        let mut xc = x.to_vec();
+        // z = xc - x
+        let mut z = vec![0.0; n];
+        let mut t_old = 0.0;
+
+        for &bp in order.iter() {
+            let f1: f64 = dot(g, &d) + dot(&self.b.bv(&z), &d);
+            let f2: f64 = dot(&d, &self.b.bv(&d));
+            if f1 >= 0.0 {
+                return xc;
+            }
+            let dt_min = if f2 > 0.0 {
+                -f1 / f2
+            } else {
+                std::f64::INFINITY
+            };
+            let dt = t[bp] - t_old;
+            if dt_min < dt {
+                for i in 0..n {
+                    xc[i] += dt_min * d[i];
+                }
+                return xc;
+            }
+            if dt.is_infinite() {
+                // only variables without bounds are left
+                break;
+            }
+            for i in 0..n {
+                z[i] += dt * d[i];
+                xc[i] = x[i] + z[i];
+            }
+            xc[bp] = if d[bp] > 0.0 {
+                self.upper[bp]
+            } else {
+                self.lower[bp]
+            };
+            z[bp] = xc[bp] - x[bp];
+            d[bp] = 0.0;
+            t_old = t[bp];
+        }
+
+        // remaining segment (only unbounded variables move)
+        let f1: f64 = dot(g, &d) + dot(&self.b.bv(&z), &d);
+        let f2: f64 = dot(&d, &self.b.bv(&d));
+        if f1 < 0.0 && f2 > 0.0 {
+            let dt_min = -f1 / f2;
+            for i in 0..n {
+                xc[i] += dt_min * d[i];
+            }
+        }
+        xc
+    }
+
+    /// Minimizes the quadratic model over the variables which are free at the Cauchy point `xc`
+    /// and truncates the result to the feasible box (direct primal method).
+    fn subspace_minimization(&self, x: &[f64], g: &[f64], xc: &[f64]) -> Result<Vec<f64>, Error> {
+        let n = x.len();
+        let free: Vec<usize> = (0..n)
+            .filter(|&i| xc[i] > self.lower[i] && xc[i] < self.upper[i])
+            .collect();
+        if free.is_empty() {
+            return Ok(xc.to_vec());
+        }
+
+        // reduced gradient of the model at xc
+        let z: Vec<f64> = xc.iter().zip(x.iter()).map(|(a, b)| a - b).collect();
+        let bz = self.b.bv(&z);
+        let r: Vec<f64> = free.iter().map(|&i| g[i] + bz[i]).collect();
+
+        let du = self.b.reduced_hv(&free, &r)?;
+
+        // truncate step to the feasible box
+        let mut alpha: f64 = 1.0;
+        for (k, &i) in free.iter().enumerate() {
+            let step = -du[k];
+            if step > 0.0 {
+                alpha = alpha.min((self.upper[i] - xc[i]) / step);
+            } else if step < 0.0 {
+                alpha = alpha.min((self.lower[i] - xc[i]) / step);
+            }
+        }
+
+        let mut xbar = xc.to_vec();
+        for (k, &i) in free.iter().enumerate() {
+            xbar[i] -= alpha * du[k];
+        }
+        Ok(xbar)
+    }
+}
+
+impl<O, L> Solver<O> for LBFGSB<L>
+where
+    O: ArgminOp<Param = Vec<f64>, Output = f64>,
+    O::Hessian: Clone + SerializeAlias + DeserializeOwnedAlias + Default,
+    L: Clone + ArgminLineSearch<O::Param> + Solver<OpWrapper<O>>,
+{
+    const NAME: &'static str = "L-BFGS-B";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        if param.len() != self.lower.len() {
+            return Err(ArgminError::InvalidParameter {
+                text: "L-BFGS-B: dimension of bounds and initial parameter vector differ."
+                    .to_string(),
+            }
+            .into());
+        }
+        let param = self.project(&param);
+        let cost = op.apply(&param)?;
+        let grad = op.gradient(&param)?;
+        let pg_norm = self.projected_gradient_norm(&param, &grad);
+        Ok(Some(
+            ArgminIterData::new()
+                .param(param)
+                .cost(cost)
+                .grad(grad)
+                .kv(make_kv!("pg_norm" => pg_norm;)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let cur_cost = state.get_cost();
+        let prev_grad = state.get_grad().unwrap();
+
+        let xc = self.cauchy_point(&param, &prev_grad);
+        let xbar = self.subspace_minimization(&param, &prev_grad, &xc)?;
+        let theta = self.b.theta();
+
+        let mut direction: Vec<f64> = xbar.iter().zip(param.iter()).map(|(a, b)| a - b).collect();
+        // fall back to the Cauchy point if the subspace step is not a descent direction
+        if dot(&direction, &prev_grad) >= 0.0 {
+            direction = xc.iter().zip(param.iter()).map(|(a, b)| a - b).collect();
+        }
+
+        let max_step = self.max_step(&param, &direction);
+        let (xk1, next_cost) = if max_step.is_infinite() {
+            self.linesearch.set_search_direction(direction);
+
+            // Run solver
+            let ArgminResult {
+                operator: line_op,
+                state:
+                    IterState {
+                        param: xk1,
+                        cost: next_cost,
+                        ..
+                    },
+            } = Executor::new(
+                OpWrapper::new_from_op(op),
+                self.linesearch.clone(),
+                param.clone(),
+            )
+            .grad(prev_grad.clone())
+            .cost(cur_cost)
+            .ctrlc(false)
+            .run()?;
+
+            // take care of function eval counts
+            op.consume_op(line_op);
+
+            (xk1, next_cost)
+        } else {
+            match self.feasible_step(
+                op,
+                &param,
+                cur_cost,
+                &prev_grad,
+                &direction,
+                max_step.min(1.0),
+            )? {
+                Some(step) => step,
+                None => {
+                    let pg_norm = self.projected_gradient_norm(&param, &prev_grad);
+                    return Ok(ArgminIterData::new()
+                        .param(param)
+                        .cost(cur_cost)
+                        .grad(prev_grad)
+                        .kv(make_kv!("pg_norm" => pg_norm;
+                                     "theta" => theta;
+                                     "pair_accepted" => false;))
+                        .termination_reason(TerminationReason::Aborted));
+                }
+            }
+        };
+
+        let grad = op.gradient(&xk1)?;
+
+        let sk: Vec<f64> = xk1.sub(&param);
+        let yk: Vec<f64> = grad.sub(&prev_grad);
+
+        // Only correction pairs which satisfy the curvature condition are stored
+        let pair_accepted = self.b.update(sk, yk)?;
+
+        let pg_norm = self.projected_gradient_norm(&xk1, &grad);
+
+        Ok(ArgminIterData::new()
+            .param(xk1)
+            .cost(next_cost)
+            .grad(grad)
+            .kv(make_kv!("pg_norm" => pg_norm;
+                         "theta" => theta;
+                         "pair_accepted" => pair_accepted;)))
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        let param = state.get_param();
+        let grad = state.get_grad().unwrap();
+        if self.projected_gradient_norm(&param, &grad) < self.pgtol {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        if (state.get_prev_cost() - state.get_cost()).abs() < std::f64::EPSILON {
+            return TerminationReason::NoChangeInCost;
+        }
+        TerminationReason::NotTerminated
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(lbfgsb, LBFGSB<MoreThuenteLineSearch<Vec<f64>>>);
+
+    #[test]
+    fn test_lbfgsb_bounds_mismatch() {
+        let res = LBFGSB::new(
+            MoreThuenteLineSearch::<Vec<f64>>::new(),
+            5,
+            vec![0.0, 0.0],
+            vec![1.0],
+        );
+        assert!(res.is_err());
+    }
+
+    /// `f(x) = (x_0 - 3)^2 + 10 (x_1 + 1)^2 + x_0 x_1`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Quadratic {}
+
+    impl ArgminOp for Quadratic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok((p[0] - 3.0).powi(2) + 10.0 * (p[1] + 1.0).powi(2) + p[0] * p[1])
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![2.0 * (p[0] - 3.0) + p[1], 20.0 * (p[1] + 1.0) + p[0]])
+        }
+    }
+
+    /// `Quadratic` restricted to the unit box
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct InBox {}
+
+    impl InBox {
+        fn check(p: &[f64]) -> Result<(), Error> {
+            if p.iter().any(|x| *x < 0.0 || *x > 1.0) {
+                return Err(ArgminError::ConditionViolated {
+                    text: format!("evaluated outside of the box at {:?}", p),
+                }
+                .into());
+            }
+            Ok(())
+        }
+    }
+
+    impl ArgminOp for InBox {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            InBox::check(p)?;
+            Quadratic {}.apply(p)
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            InBox::check(p)?;
+            Quadratic {}.gradient(p)
+        }
+    }
+
+    #[test]
+    fn test_lbfgsb_active_bounds() {
+        // the unconstrained minimizer lies outside of the box, both bounds are active at the
+        // constrained minimizer (1, 0). Starting close to the bounds, the full steps leave the
+        // box. `InBox` returns an error if it is evaluated outside of the box.
+        let solver = LBFGSB::new(
+            MoreThuenteLineSearch::new(),
+            5,
+            vec![0.0, 0.0],
+            vec![1.0, 1.0],
+        )
+        .unwrap();
+        let res = Executor::new(InBox {}, solver, vec![0.9, 0.1])
+            .max_iters(50)
+            .run()
+            .unwrap();
+        let param = res.state.get_best_param();
+        assert!((param[0] - 1.0).abs() < 1e-8);
+        assert!(param[1].abs() < 1e-8);
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+    }
+
+    #[test]
+    fn test_lbfgsb_rosenbrock_active_bound() {
+        // x_0 <= 0.5 is active at the constrained minimizer (0.5, 0.25)
+        let solver = LBFGSB::new(
+            MoreThuenteLineSearch::new(),
+            7,
+            vec![std::f64::NEG_INFINITY, std::f64::NEG_INFINITY],
+            vec![0.5, std::f64::INFINITY],
+        )
+        .unwrap();
+        let res = Executor::new(Rosenbrock {}, solver, vec![-1.2, 1.0])
+            .max_iters(200)
+            .run()
+            .unwrap();
+        let param = res.state.get_best_param();
+        assert!((param[0] - 0.5).abs() < 1e-6);
+        assert!((param[1] - 0.25).abs() < 1e-6);
+        assert!(param[0] <= 0.5);
+    }
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Rosenbrock {}
+
+    impl ArgminOp for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(crate::testfunctions::rosenbrock_2d(p, 1.0, 100.0))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(crate::testfunctions::rosenbrock_2d_derivative(
+                p, 1.0, 100.0,
+            ))
+        }
+    }
+
+    /// `f(x) = (x_0 - 0.5)^2` with the wrong sign of the gradient
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct WrongGradient {}
+
+    impl ArgminOp for WrongGradient {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok((p[0] - 0.5).powi(2))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![-2.0 * (p[0] - 0.5)])
+        }
+    }
+
+    #[test]
+    fn test_lbfgsb_backtracking_failure() {
+        // the cost increases along the search direction, no step is accepted
+        let solver = LBFGSB::new(MoreThuenteLineSearch::new(), 5, vec![0.0], vec![1.0]).unwrap();
+        let res = Executor::new(WrongGradient {}, solver, vec![0.2])
+            .max_iters(10)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::Aborted
+        );
+        assert_eq!(res.state.get_param(), vec![0.2]);
+        assert_eq!(res.state.get_iter(), 1);
+    }
+
+    #[test]
+    fn test_lbfgsb_projected_gradient_norm() {
+        let solver = LBFGSB::new(
+            MoreThuenteLineSearch::<Vec<f64>>::new(),
+            5,
+            vec![0.0, 0.0],
+            vec![1.0, 1.0],
+        )
+        .unwrap();
+        // gradient pushes against the active lower bound of the first variable
+        let pg = solver.projected_gradient_norm(&[0.0, 0.5], &[2.0, -0.25]);
+        assert!((pg - 0.25).abs() < 1e-12);
+    }
+}
@@ -15,13 +15,23 @@
 //! Springer. ISBN 0-387-30303-0.
 
 pub mod bfgs;
+pub mod compact;
 pub mod dfp;
 pub mod lbfgs;
+pub mod lbfgsb;
+pub mod owlqn;
+pub mod quasinewton_method;
 pub mod sr1;
 pub mod sr1_trustregion;
+pub mod update;
 
 pub use self::bfgs::*;
+pub use self::compact::*;
 pub use self::dfp::*;
 pub use self::lbfgs::*;
+pub use self::lbfgsb::*;
+pub use self::owlqn::*;
+pub use self::quasinewton_method::*;
 pub use self::sr1::*;
 pub use self::sr1_trustregion::*;
+pub use self::update::*;
@@ -0,0 +1,546 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Galen Andrew and Jianfeng Gao (2007). Scalable Training of L1-Regularized Log-Linear
+//! Models. Proceedings of the 24th International Conference on Machine Learning, 33-40.
+//! DOI: https://doi.org/10.1145/1273496.1273501
+//!
+//! [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::prelude::*;
+use crate::solver::quasinewton::lbfgs::two_loop_recursion;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::iter::FromIterator;
+
+/// Orthant-Wise Limited-memory Quasi-Newton (OWL-QN) method
+///
+/// Minimizes `F(x) = f(x) + sum_i w_i |x_i|` where `f` is the smooth function defined by the
+/// operator (`apply` and `gradient`) and `w_i >= 0` are the L1 weights of the individual
+/// coordinates (a weight of zero leaves the coordinate unregularized).
+///
+/// In each iteration, the pseudo-gradient of `F` is computed and the L-BFGS two-loop recursion
+/// (with pairs built from the gradients of `f` only) yields the search direction. Components of the
+/// direction which disagree in sign with the negative pseudo-gradient are set to zero. A
+/// backtracking line search is performed on `F` where each trial point is projected onto the
+/// orthant of the current iterate (coordinates which would change sign are set to zero). This
+/// produces exact zeros in the solution.
+///
+/// The cost reported in the state is `F(x)` and the gradient is the pseudo-gradient of `F`.
+/// Convergence is declared once the infinity norm of the pseudo-gradient drops below `pgtol`. If
+/// the line search finds no point with sufficient decrease within `max_backtracking` steps, the
+/// method terminates with `TerminationReason::Aborted`.
+///
+/// The parameter vector can be of any type which can be iterated over and collected from `f64`
+/// values (e.g. `Vec<f64>` or `ndarray::Array1<f64>`).
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/owlqn.rs)
+///
+/// # References:
+///
+/// [0] Galen Andrew and Jianfeng Gao (2007). Scalable Training of L1-Regularized Log-Linear
+/// Models. Proceedings of the 24th International Conference on Machine Learning, 33-40.
+/// DOI: https://doi.org/10.1145/1273496.1273501
+///
+/// [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct OWLQN {
+    /// m
+    m: usize,
+    /// L1 weights
+    l1_weights: Vec<f64>,
+    /// tolerance for the infinity norm of the pseudo-gradient
+    pgtol: f64,
+    /// sufficient decrease parameter of the backtracking line search
+    c1: f64,
+    /// maximum number of backtracking steps
+    max_backtracking: u64,
+    /// gradient of the smooth part at the current iterate
+    grad: Vec<f64>,
+    /// s_{k-1}
+    s: VecDeque<Vec<f64>>,
+    /// y_{k-1}
+    y: VecDeque<Vec<f64>>,
+}
+
+impl OWLQN {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `m`: number of stored correction pairs
+    /// * `l1_weights`: weights of the L1 penalty for each coordinate (must be `>= 0`)
+    pub fn new(m: usize, l1_weights: Vec<f64>) -> Result<Self, Error> {
+        if l1_weights.iter().any(|w| *w < 0.0 || w.is_nan()) {
+            return Err(ArgminError::InvalidParameter {
+                text: "OWL-QN: L1 weights must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(OWLQN {
+            m,
+            l1_weights,
+            pgtol: 1e-5,
+            c1: 1e-4,
+            max_backtracking: 50,
+            grad: vec![],
+            s: VecDeque::with_capacity(m),
+            y: VecDeque::with_capacity(m),
+        })
+    }
+
+    /// Set tolerance for the infinity norm of the pseudo-gradient (default: 1e-5)
+    pub fn pgtol(mut self, pgtol: f64) -> Result<Self, Error> {
+        if pgtol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "OWL-QN: pgtol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.pgtol = pgtol;
+        Ok(self)
+    }
+
+    /// Set sufficient decrease parameter `c1` of the backtracking line search (default: 1e-4)
+    pub fn c1(mut self, c1: f64) -> Result<Self, Error> {
+        if c1 <= 0.0 || c1 >= 1.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "OWL-QN: c1 must be in (0, 1).".to_string(),
+            }
+            .into());
+        }
+        self.c1 = c1;
+        Ok(self)
+    }
+
+    /// Set maximum number of backtracking steps of the line search (default: 50)
+    pub fn max_backtracking(mut self, iters: u64) -> Self {
+        self.max_backtracking = iters;
+        self
+    }
+
+    /// L1 penalty `sum_i w_i |x_i|`
+    fn l1_norm(&self, x: &[f64]) -> f64 {
+        x.iter()
+            .zip(self.l1_weights.iter())
+            .map(|(xi, w)| w * xi.abs())
+            .sum()
+    }
+
+    /// Pseudo-gradient of `F` at `x`, where `g` is the gradient of the smooth part
+    fn pseudo_gradient(&self, x: &[f64], g: &[f64]) -> Vec<f64> {
+        x.iter()
+            .zip(g.iter())
+            .zip(self.l1_weights.iter())
+            .map(|((xi, gi), w)| {
+                if *xi > 0.0 {
+                    gi + w
+                } else if *xi < 0.0 {
+                    gi - w
+                } else if gi + w < 0.0 {
+                    gi + w
+                } else if gi - w > 0.0 {
+                    gi - w
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+/// Sets the components of `x` which are not in the orthant defined by `xi` to zero
+fn project_orthant(x: &[f64], xi: &[f64]) -> Vec<f64> {
+    x.iter()
+        .zip(xi.iter())
+        .map(|(a, b)| if a * b > 0.0 { *a } else { 0.0 })
+        .collect()
+}
+
+impl<O> Solver<O> for OWLQN
+where
+    O: ArgminOp<Output = f64>,
+    O::Param: FromIterator<f64>,
+    for<'a> &'a O::Param: IntoIterator<Item = &'a f64>,
+{
+    const NAME: &'static str = "OWL-QN";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        let x = to_vec(&param);
+        if x.len() != self.l1_weights.len() {
+            return Err(ArgminError::InvalidParameter {
+                text: "OWL-QN: dimension of L1 weights and initial parameter vector differ."
+                    .to_string(),
+            }
+            .into());
+        }
+        let cost = op.apply(&param)? + self.l1_norm(&x);
+        self.grad = to_vec(&op.gradient(&param)?);
+        let pg = self.pseudo_gradient(&x, &self.grad);
+        Ok(Some(
+            ArgminIterData::new()
+                .param(param)
+                .cost(cost)
+                .grad(pg.into_iter().collect()),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = to_vec(&state.get_param());
+        let cur_cost = state.get_cost();
+        let pg = to_vec(&state.get_grad().unwrap());
+
+        // search direction, restricted to the orthant of steepest descent
+        let (r, gamma) = two_loop_recursion(&self.s, &self.y, &pg);
+        let direction: Vec<f64> = r
+            .iter()
+            .zip(pg.iter())
+            .map(|(ri, pgi)| if ri * pgi > 0.0 { -ri } else { 0.0 })
+            .collect();
+
+        // orthant of the current iterate
+        let orthant: Vec<f64> = param
+            .iter()
+            .zip(pg.iter())
+            .map(|(x, g)| if *x != 0.0 { x.signum() } else { -g.signum() })
+            .collect();
+
+        // backtracking line search on F with projection onto the orthant
+        let mut alpha = if self.s.is_empty() {
+            1.0 / pg.norm()
+        } else {
+            1.0
+        };
+        let mut accepted = None;
+        for _ in 0..self.max_backtracking {
+            let trial = project_orthant(&param.scaled_add(&alpha, &direction), &orthant);
+            let cost = op.apply(&trial.iter().cloned().collect())? + self.l1_norm(&trial);
+            let decrease: f64 = pg.dot(&trial.sub(&param));
+            if cost <= cur_cost + self.c1 * decrease {
+                accepted = Some((trial, cost));
+                break;
+            }
+            alpha *= 0.5;
+        }
+
+        let (xk1, next_cost) = match accepted {
+            Some(res) => res,
+            None => {
+                // no sufficient decrease: stay at the current iterate and stop
+                return Ok(ArgminIterData::new()
+                    .param(param.into_iter().collect())
+                    .cost(cur_cost)
+                    .grad(pg.into_iter().collect())
+                    .kv(make_kv!("gamma" => gamma;
+                                 "alpha" => 0.0;
+                                 "pair_accepted" => false;
+                                 "line_search_failed" => true;))
+                    .termination_reason(TerminationReason::Aborted));
+            }
+        };
+
+        let xk1_param: O::Param = xk1.iter().cloned().collect();
+        let grad = to_vec(&op.gradient(&xk1_param)?);
+
+        let sk: Vec<f64> = xk1.sub(&param);
+        let yk: Vec<f64> = grad.sub(&self.grad);
+
+        // Only store correction pairs which satisfy the curvature condition
+        let skyk: f64 = sk.dot(&yk);
+        let ykyk: f64 = yk.dot(&yk);
+        let pair_accepted = skyk > std::f64::EPSILON * ykyk;
+        if pair_accepted {
+            if self.s.len() >= self.m {
+                self.s.pop_front();
+                self.y.pop_front();
+            }
+            self.s.push_back(sk);
+            self.y.push_back(yk);
+        }
+
+        let pg = self.pseudo_gradient(&xk1, &grad);
+        self.grad = grad;
+
+        Ok(ArgminIterData::new()
+            .param(xk1_param)
+            .cost(next_cost)
+            .grad(pg.into_iter().collect())
+            .kv(make_kv!("gamma" => gamma;
+                         "alpha" => alpha;
+                         "pair_accepted" => pair_accepted;
+                         "line_search_failed" => false;)))
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        let pg_norm = state
+            .get_grad()
+            .unwrap()
+            .into_iter()
+            .map(|g| g.abs())
+            .fold(0.0, f64::max);
+        if pg_norm < self.pgtol {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        if (state.get_prev_cost() - state.get_cost()).abs() < std::f64::EPSILON {
+            return TerminationReason::NoChangeInCost;
+        }
+        TerminationReason::NotTerminated
+    }
+}
+
+/// Copies the coordinates of `x` into a `Vec<f64>`
+fn to_vec<P>(x: &P) -> Vec<f64>
+where
+    for<'a> &'a P: IntoIterator<Item = &'a f64>,
+{
+    x.into_iter().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(owlqn, OWLQN);
+
+    /// `f(x) = 0.5 * ||x - c||^2`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Quadratic {
+        c: Vec<f64>,
+    }
+
+    impl ArgminOp for Quadratic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(0.5 * p.sub(&self.c).norm().powi(2))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(p.sub(&self.c))
+        }
+    }
+
+    #[test]
+    fn test_soft_thresholding() {
+        // the solution is sign(c_i) * max(|c_i| - w_i, 0)
+        let op = Quadratic {
+            c: vec![3.0, -0.5, 1.0, -2.0],
+        };
+        let solver = OWLQN::new(5, vec![1.0, 1.0, 0.0, 0.5]).unwrap();
+        let res = Executor::new(op, solver, vec![0.5, 0.5, 0.5, 0.5])
+            .max_iters(100)
+            .run()
+            .unwrap();
+        let x = res.state.get_best_param();
+        let expected = [2.0, 0.0, 1.0, -1.5];
+        for (a, b) in x.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+        assert!(x[1] == 0.0);
+    }
+
+    /// Negative log-likelihood of a logistic regression model on a synthetic data set where only
+    /// the first two features are informative
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct LogisticRegression {
+        features: Vec<Vec<f64>>,
+        labels: Vec<f64>,
+    }
+
+    impl LogisticRegression {
+        fn new(samples: usize, n: usize) -> Self {
+            let features: Vec<Vec<f64>> = (0..samples)
+                .map(|i| (0..n).map(|j| ((i * (j + 3) + j) as f64).sin()).collect())
+                .collect();
+            let labels = features
+                .iter()
+                .map(|x| {
+                    if 2.0 * x[0] - 3.0 * x[1] > 0.0 {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })
+                .collect();
+            LogisticRegression { features, labels }
+        }
+    }
+
+    impl ArgminOp for LogisticRegression {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, w: &Vec<f64>) -> Result<f64, Error> {
+            Ok(self
+                .features
+                .iter()
+                .zip(self.labels.iter())
+                .map(|(x, y)| {
+                    let z: f64 = x.dot(w);
+                    (1.0 + z.exp()).ln() - y * z
+                })
+                .sum())
+        }
+
+        fn gradient(&self, w: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            let mut grad = vec![0.0; w.len()];
+            for (x, y) in self.features.iter().zip(self.labels.iter()) {
+                let z: f64 = x.dot(w);
+                grad = grad.scaled_add(&(1.0 / (1.0 + (-z).exp()) - y), x);
+            }
+            Ok(grad)
+        }
+    }
+
+    #[test]
+    fn test_l1_logistic_regression() {
+        const W: f64 = 10.0;
+        let op = LogisticRegression::new(200, 10);
+        let solver = OWLQN::new(7, vec![W; 10]).unwrap().pgtol(1e-6).unwrap();
+        let res = Executor::new(op.clone(), solver.clone(), vec![0.0; 10])
+            .max_iters(500)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::TargetPrecisionReached
+        );
+        let x = res.state.get_best_param();
+        // the informative features are kept, the others are exactly zero
+        assert!(x[0] > 0.0 && x[1] < 0.0);
+        let zeros = x.iter().filter(|xi| **xi == 0.0).count();
+        assert!(zeros >= 5, "{:?}", x);
+        // optimality: |g_i| <= w_i for zero coordinates, g_i = -w_i sign(x_i) otherwise
+        let g = op.gradient(&x).unwrap();
+        for (xi, gi) in x.iter().zip(g.iter()) {
+            if *xi == 0.0 {
+                assert!(gi.abs() <= W);
+            } else {
+                assert!((gi + W * xi.signum()).abs() < 1e-6);
+            }
+        }
+    }
+
+    /// Returns the negative of the gradient of `0.5 * ||x||^2`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct WrongGradient {}
+
+    impl ArgminOp for WrongGradient {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(0.5 * p.norm().powi(2))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(p.mul(&(-1.0)))
+        }
+    }
+
+    #[test]
+    fn test_backtracking_failure() {
+        // the cost increases along the search direction, no step is accepted
+        let solver = OWLQN::new(5, vec![0.0, 0.0]).unwrap();
+        let res = Executor::new(WrongGradient {}, solver, vec![1.0, 2.0])
+            .max_iters(10)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            TerminationReason::Aborted
+        );
+        assert_eq!(res.state.get_param(), vec![1.0, 2.0]);
+        assert_eq!(res.state.get_iter(), 1);
+    }
+
+    #[test]
+    fn test_pseudo_gradient() {
+        let solver = OWLQN::new(5, vec![1.0, 1.0, 1.0, 1.0]).unwrap();
+        let pg = solver.pseudo_gradient(&[1.0, 0.0, 0.0, 0.0], &[0.5, -2.0, 3.0, 0.5]);
+        assert_eq!(pg, vec![1.5, -1.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_parameters() {
+        assert!(OWLQN::new(5, vec![1.0, -1.0]).is_err());
+        assert!(OWLQN::new(5, vec![1.0]).unwrap().pgtol(0.0).is_err());
+        assert!(OWLQN::new(5, vec![1.0]).unwrap().c1(1.0).is_err());
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    mod ndarray_tests {
+        use super::*;
+        use ndarray::{array, Array1};
+
+        /// `f(x) = 0.5 * ||x - c||^2`
+        #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+        #[derive(Clone, Default)]
+        struct Quadratic {
+            c: Array1<f64>,
+        }
+
+        impl ArgminOp for Quadratic {
+            type Param = Array1<f64>;
+            type Output = f64;
+            type Hessian = ();
+            type Jacobian = ();
+
+            fn apply(&self, p: &Array1<f64>) -> Result<f64, Error> {
+                Ok(0.5 * (p - &self.c).mapv(|x| x.powi(2)).sum())
+            }
+
+            fn gradient(&self, p: &Array1<f64>) -> Result<Array1<f64>, Error> {
+                Ok(p - &self.c)
+            }
+        }
+
+        #[test]
+        fn test_soft_thresholding() {
+            let op = Quadratic {
+                c: array![3.0, -0.5, 1.0, -2.0],
+            };
+            let solver = OWLQN::new(5, vec![1.0, 1.0, 0.0, 0.5]).unwrap();
+            let res = Executor::new(op, solver, array![0.5, 0.5, 0.5, 0.5])
+                .max_iters(100)
+                .run()
+                .unwrap();
+            let x = res.state.get_best_param();
+            let expected = [2.0, 0.0, 1.0, -1.5];
+            for (a, b) in x.iter().zip(expected.iter()) {
+                assert!((a - b).abs() < 1e-6);
+            }
+            assert!(x[1] == 0.0);
+        }
+    }
+}
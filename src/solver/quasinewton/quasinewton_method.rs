@@ -0,0 +1,295 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::prelude::*;
+use crate::solver::quasinewton::QuasiNewtonUpdate;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Quasi-Newton line search method with an exchangeable update of the inverse Hessian
+/// approximation (see the `update` module for the available update methods).
+///
+/// In each iteration, a line search along `p = -H g` is performed and `H` is updated with the
+/// step `s` and the change of the gradient `y`. The following safeguards are in place:
+///
+/// * Updates which require the curvature condition are skipped if
+///   `s^T y <= curvature_tol * ||s|| ||y||`.
+/// * If `p` is not a descent direction (which may happen for updates that do not preserve
+///   positive definiteness such as SR1), `H` is reset to the identity.
+/// * Optionally (`scale_initial_hessian`), `H_0` is scaled by `s^T y / y^T y` before the first
+///   update (Nocedal/Wright eq. 6.20).
+///
+/// The key-value store reports whether the inverse Hessian was updated (`hessian_update`) or reset
+/// (`reset`) as well as the curvature `s^T y`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/quasinewton.rs)
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct QuasiNewton<L, H, U> {
+    /// Inverse Hessian
+    inv_hessian: H,
+    /// line search
+    linesearch: L,
+    /// update method
+    pub(super) update: U,
+    /// scale the initial inverse Hessian before the first update
+    scale_initial_hessian: bool,
+    /// initial inverse Hessian has not been scaled yet
+    scaling_pending: bool,
+    /// tolerance of the curvature condition
+    curvature_tol: f64,
+}
+
+impl<L, H, U> QuasiNewton<L, H, U> {
+    /// Constructor
+    pub fn new(init_inverse_hessian: H, linesearch: L, update: U) -> Self {
+        QuasiNewton {
+            inv_hessian: init_inverse_hessian,
+            linesearch,
+            update,
+            scale_initial_hessian: true,
+            scaling_pending: true,
+            curvature_tol: 1e-8,
+        }
+    }
+
+    /// Scale the initial inverse Hessian with `s^T y / y^T y` before the first update
+    /// (default: true)
+    pub fn scale_initial_hessian(mut self, scale: bool) -> Self {
+        self.scale_initial_hessian = scale;
+        self.scaling_pending = scale;
+        self
+    }
+
+    /// Set tolerance of the curvature condition `s^T y > curvature_tol * ||s|| ||y||`
+    /// (default: 1e-8)
+    pub fn curvature_tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "QuasiNewton: curvature_tol must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.curvature_tol = tol;
+        Ok(self)
+    }
+}
+
+impl<O, L, H, U> Solver<O> for QuasiNewton<L, H, U>
+where
+    O: ArgminOp<Output = f64, Hessian = H>,
+    O::Param: Debug
+        + Clone
+        + Default
+        + SerializeAlias
+        + ArgminSub<O::Param, O::Param>
+        + ArgminDot<O::Param, f64>
+        + ArgminNorm<f64>
+        + ArgminMul<f64, O::Param>,
+    O::Hessian: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminDot<O::Param, O::Param>
+        + ArgminMul<f64, O::Hessian>
+        + ArgminEye,
+    L: Clone + ArgminLineSearch<O::Param> + Solver<OpWrapper<O>>,
+    U: QuasiNewtonUpdate<O::Param, O::Hessian>,
+{
+    const NAME: &'static str = "Quasi-Newton";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        let cost = op.apply(&param)?;
+        let grad = op.gradient(&param)?;
+        Ok(Some(
+            ArgminIterData::new().param(param).cost(cost).grad(grad),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let cost = state.get_cost();
+        let prev_grad = if let Some(grad) = state.get_grad() {
+            grad
+        } else {
+            op.gradient(&param)?
+        };
+
+        let mut p = self.inv_hessian.dot(&prev_grad).mul(&(-1.0));
+
+        // safeguard against inverse Hessian approximations which are not positive definite
+        let gp: f64 = prev_grad.dot(&p);
+        let reset = gp >= 0.0 || gp.is_nan();
+        if reset {
+            self.inv_hessian = self.inv_hessian.eye_like();
+            self.scaling_pending = self.scale_initial_hessian;
+            p = prev_grad.mul(&(-1.0));
+        }
+
+        self.linesearch.set_search_direction(p.clone());
+
+        // Run solver
+        let ArgminResult {
+            operator: line_op,
+            state:
+                IterState {
+                    param: xk1,
+                    cost: next_cost,
+                    ..
+                },
+        } = Executor::new(
+            OpWrapper::new_from_op(op),
+            self.linesearch.clone(),
+            param.clone(),
+        )
+        .grad(prev_grad.clone())
+        .cost(cost)
+        .ctrlc(false)
+        .run()?;
+
+        // take care of function eval counts
+        op.consume_op(line_op);
+
+        let grad = op.gradient(&xk1)?;
+        let yk = grad.sub(&prev_grad);
+        let sk = xk1.sub(&param);
+
+        let yksk: f64 = yk.dot(&sk);
+        let curvature = yksk > self.curvature_tol * sk.norm() * yk.norm();
+
+        // s = alpha * p = -alpha * H g, therefore B s = -alpha * g
+        let pp: f64 = p.dot(&p);
+        let mut alpha: f64 = sk.dot(&p) / pp;
+
+        if self.scaling_pending && curvature {
+            let ykyk: f64 = yk.dot(&yk);
+            let gamma = yksk / ykyk;
+            self.inv_hessian = self.inv_hessian.mul(&gamma);
+            alpha /= gamma;
+            self.scaling_pending = false;
+        }
+
+        let hessian_update = if curvature || !self.update.requires_positive_curvature() {
+            let bs = prev_grad.mul(&(-alpha));
+            match self.update.update(&self.inv_hessian, &sk, &yk, &bs)? {
+                Some(inv_hessian) => {
+                    self.inv_hessian = inv_hessian;
+                    self.scaling_pending = false;
+                    true
+                }
+                None => false,
+            }
+        } else {
+            false
+        };
+
+        Ok(ArgminIterData::new()
+            .param(xk1)
+            .cost(next_cost)
+            .grad(grad)
+            .kv(make_kv!(
+                "curvature" => yksk;
+                "hessian_update" => hessian_update;
+                "reset" => reset;
+            )))
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        if state.get_grad().unwrap().norm() < std::f64::EPSILON.sqrt() {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        if (state.get_prev_cost() - state.get_cost()).abs() < std::f64::EPSILON {
+            return TerminationReason::NoChangeInCost;
+        }
+        TerminationReason::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::quasinewton::{BFGSUpdate, DampedBFGSUpdate, SR1Update};
+    use crate::test_trait_impl;
+    use crate::testfunctions::{rosenbrock_2d, rosenbrock_2d_derivative};
+
+    type Operator = MinimalNoOperator;
+
+    test_trait_impl!(
+        quasinewton,
+        QuasiNewton<Operator, MoreThuenteLineSearch<Operator>, BFGSUpdate>
+    );
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Rosenbrock {}
+
+    impl ArgminOp for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(rosenbrock_2d(p, 1.0, 100.0))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(rosenbrock_2d_derivative(p, 1.0, 100.0))
+        }
+    }
+
+    fn run<U>(update: U) -> ArgminResult<Rosenbrock>
+    where
+        U: QuasiNewtonUpdate<Vec<f64>, Vec<Vec<f64>>> + Clone,
+    {
+        let linesearch = MoreThuenteLineSearch::new();
+        let solver = QuasiNewton::new(Vec::<Vec<f64>>::eye(2), linesearch, update);
+        Executor::new(Rosenbrock {}, solver, vec![-1.2, 1.0])
+            .max_iters(200)
+            .run()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rosenbrock() {
+        for res in &[
+            run(BFGSUpdate::new()),
+            run(DampedBFGSUpdate::new()),
+            run(SR1Update::new()),
+        ] {
+            let x = res.state.get_best_param();
+            assert!((x[0] - 1.0).abs() < 1e-4);
+            assert!((x[1] - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_curvature_tol() {
+        let solver = QuasiNewton::new((), (), BFGSUpdate::new());
+        assert!(solver.curvature_tol(-1.0).is_err());
+    }
+}
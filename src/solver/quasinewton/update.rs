@@ -0,0 +1,375 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Quasi-Newton update methods
+//!
+//! Update formulas for the approximation `H` of the inverse Hessian, used by `QuasiNewton` (and
+//! thereby by `BFGS`, `DFP` and `SR1`). Each update is computed from the step `s = x_{k+1} - x_k`
+//! and the change of the gradient `y = g_{k+1} - g_k`. All formulas assume `H` to be symmetric.
+//!
+//! * `BFGSUpdate`: Broyden-Fletcher-Goldfarb-Shanno update
+//! * `DFPUpdate`: Davidon-Fletcher-Powell update
+//! * `BroydenUpdate`: convex combination of BFGS and DFP (restricted Broyden class)
+//! * `SR1Update`: symmetric rank-one update with skipping rule
+//! * `DampedBFGSUpdate`: BFGS update with Powell's damping
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Update of the inverse Hessian approximation of a quasi-Newton method
+pub trait QuasiNewtonUpdate<P, H>: SerializeAlias {
+    /// Returns `true` if the update must be skipped when the curvature condition `s^T y > 0` is
+    /// violated (default). Updates which handle negative curvature themselves return `false`.
+    fn requires_positive_curvature(&self) -> bool {
+        true
+    }
+
+    /// Computes the updated inverse Hessian approximation from `inv_hessian`, `s` and `y`.
+    ///
+    /// `bs` is the product `B s` of the Hessian approximation `B = inv_hessian^{-1}` with `s`.
+    /// Returns `None` if the update is skipped.
+    fn update(&self, inv_hessian: &H, s: &P, y: &P, bs: &P) -> Result<Option<H>, Error>;
+}
+
+/// `H + ((rho^2 y^T H y + rho) s s^T - rho (s (H y)^T + (H y) s^T))` with `rho = 1 / (y^T s)`,
+/// which equals `(I - rho s y^T) H (I - rho y s^T) + rho s s^T` for symmetric `H`.
+fn bfgs<P, H>(h: &H, s: &P, y: &P) -> H
+where
+    P: ArgminDot<P, f64> + ArgminDot<P, H>,
+    H: ArgminDot<P, P> + ArgminAdd<H, H> + ArgminSub<H, H> + ArgminMul<f64, H>,
+{
+    let hy: P = h.dot(y);
+    let sy: f64 = s.dot(y);
+    let rho = 1.0 / sy;
+    let yhy: f64 = y.dot(&hy);
+    let shy: H = s.dot(&hy);
+    let hys: H = hy.dot(s);
+    let ss: H = s.dot(s);
+    h.sub(&shy.add(&hys).mul(&rho))
+        .add(&ss.mul(&(rho * rho * yhy + rho)))
+}
+
+/// `H - (H y) (H y)^T / (y^T H y) + s s^T / (y^T s)`
+fn dfp<P, H>(h: &H, s: &P, y: &P) -> H
+where
+    P: ArgminDot<P, f64> + ArgminDot<P, H>,
+    H: ArgminDot<P, P> + ArgminAdd<H, H> + ArgminSub<H, H> + ArgminMul<f64, H>,
+{
+    let hy: P = h.dot(y);
+    let yhy: f64 = y.dot(&hy);
+    let sy: f64 = s.dot(y);
+    let hyhy: H = hy.dot(&hy);
+    let ss: H = s.dot(s);
+    h.sub(&hyhy.mul(&(1.0 / yhy))).add(&ss.mul(&(1.0 / sy)))
+}
+
+/// Broyden-Fletcher-Goldfarb-Shanno (BFGS) update
+///
+/// `H_{k+1} = (I - rho s y^T) H_k (I - rho y s^T) + rho s s^T` with `rho = 1 / (y^T s)`
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct BFGSUpdate {}
+
+impl BFGSUpdate {
+    /// Constructor
+    pub fn new() -> Self {
+        BFGSUpdate {}
+    }
+}
+
+impl<P, H> QuasiNewtonUpdate<P, H> for BFGSUpdate
+where
+    P: ArgminDot<P, f64> + ArgminDot<P, H>,
+    H: ArgminDot<P, P> + ArgminAdd<H, H> + ArgminSub<H, H> + ArgminMul<f64, H>,
+{
+    fn update(&self, inv_hessian: &H, s: &P, y: &P, _bs: &P) -> Result<Option<H>, Error> {
+        Ok(Some(bfgs(inv_hessian, s, y)))
+    }
+}
+
+/// Davidon-Fletcher-Powell (DFP) update
+///
+/// `H_{k+1} = H_k - H_k y y^T H_k / (y^T H_k y) + s s^T / (y^T s)`
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct DFPUpdate {}
+
+impl DFPUpdate {
+    /// Constructor
+    pub fn new() -> Self {
+        DFPUpdate {}
+    }
+}
+
+impl<P, H> QuasiNewtonUpdate<P, H> for DFPUpdate
+where
+    P: ArgminDot<P, f64> + ArgminDot<P, H>,
+    H: ArgminDot<P, P> + ArgminAdd<H, H> + ArgminSub<H, H> + ArgminMul<f64, H>,
+{
+    fn update(&self, inv_hessian: &H, s: &P, y: &P, _bs: &P) -> Result<Option<H>, Error> {
+        Ok(Some(dfp(inv_hessian, s, y)))
+    }
+}
+
+/// Restricted Broyden class
+///
+/// `H_{k+1} = (1 - phi) H_{k+1}^{BFGS} + phi H_{k+1}^{DFP}` with `0 <= phi <= 1`, which results in
+/// the BFGS update for `phi = 0` and in the DFP update for `phi = 1`. Note that `phi` parametrizes
+/// the family of updates of the inverse Hessian. Apart from the end points, it does not coincide
+/// with the parameter of the Broyden class of updates of the Hessian in [0].
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Default, Copy, Clone, PartialEq, PartialOrd, Debug)]
+pub struct BroydenUpdate {
+    /// phi
+    phi: f64,
+}
+
+impl BroydenUpdate {
+    /// Constructor
+    pub fn new(phi: f64) -> Result<Self, Error> {
+        if !(0.0..=1.0).contains(&phi) {
+            return Err(ArgminError::InvalidParameter {
+                text: "BroydenUpdate: phi must be between 0 and 1.".to_string(),
+            }
+            .into());
+        }
+        Ok(BroydenUpdate { phi })
+    }
+}
+
+impl<P, H> QuasiNewtonUpdate<P, H> for BroydenUpdate
+where
+    P: ArgminDot<P, f64> + ArgminDot<P, H>,
+    H: ArgminDot<P, P> + ArgminAdd<H, H> + ArgminSub<H, H> + ArgminMul<f64, H>,
+{
+    fn update(&self, inv_hessian: &H, s: &P, y: &P, _bs: &P) -> Result<Option<H>, Error> {
+        let h_bfgs = bfgs(inv_hessian, s, y).mul(&(1.0 - self.phi));
+        let h_dfp = dfp(inv_hessian, s, y).mul(&self.phi);
+        Ok(Some(h_bfgs.add(&h_dfp)))
+    }
+}
+
+/// Symmetric rank-one (SR1) update
+///
+/// `H_{k+1} = H_k + (s - H_k y) (s - H_k y)^T / ((s - H_k y)^T y)`
+///
+/// The update does not require the curvature condition to hold. It is skipped if
+/// `|(s - H_k y)^T y| < r ||y|| ||s - H_k y||`.
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+pub struct SR1Update {
+    /// parameter for skipping rule
+    r: f64,
+}
+
+impl Default for SR1Update {
+    fn default() -> Self {
+        SR1Update { r: 1e-8 }
+    }
+}
+
+impl SR1Update {
+    /// Constructor
+    pub fn new() -> Self {
+        SR1Update::default()
+    }
+
+    /// Set r (default: 1e-8)
+    pub fn r(mut self, r: f64) -> Result<Self, Error> {
+        if !(0.0..=1.0).contains(&r) {
+            Err(ArgminError::InvalidParameter {
+                text: "SR1: r must be between 0 and 1.".to_string(),
+            }
+            .into())
+        } else {
+            self.r = r;
+            Ok(self)
+        }
+    }
+}
+
+impl<P, H> QuasiNewtonUpdate<P, H> for SR1Update
+where
+    P: ArgminDot<P, f64> + ArgminDot<P, H> + ArgminSub<P, P> + ArgminNorm<f64>,
+    H: ArgminDot<P, P> + ArgminAdd<H, H> + ArgminMul<f64, H>,
+{
+    fn requires_positive_curvature(&self) -> bool {
+        false
+    }
+
+    fn update(&self, inv_hessian: &H, s: &P, y: &P, _bs: &P) -> Result<Option<H>, Error> {
+        let u: P = s.sub(&inv_hessian.dot(y));
+        let uy: f64 = u.dot(y);
+        if uy.abs() < self.r * y.norm() * u.norm() || uy == 0.0 {
+            return Ok(None);
+        }
+        let uu: H = u.dot(&u);
+        Ok(Some(inv_hessian.add(&uu.mul(&(1.0 / uy)))))
+    }
+}
+
+/// BFGS update with Powell's damping
+///
+/// If `s^T y < 0.2 s^T B s`, `y` is replaced by `r = theta y + (1 - theta) B s` with
+/// `theta = 0.8 s^T B s / (s^T B s - s^T y)` before the BFGS update is applied. This ensures
+/// `s^T r >= 0.2 s^T B s > 0` and therefore a positive definite approximation even if the
+/// curvature condition is violated.
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0. (Procedure 18.2)
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct DampedBFGSUpdate {}
+
+impl DampedBFGSUpdate {
+    /// Constructor
+    pub fn new() -> Self {
+        DampedBFGSUpdate {}
+    }
+}
+
+impl<P, H> QuasiNewtonUpdate<P, H> for DampedBFGSUpdate
+where
+    P: ArgminDot<P, f64> + ArgminDot<P, H> + ArgminMul<f64, P> + ArgminScaledAdd<P, f64, P>,
+    H: ArgminDot<P, P> + ArgminAdd<H, H> + ArgminSub<H, H> + ArgminMul<f64, H>,
+{
+    fn requires_positive_curvature(&self) -> bool {
+        false
+    }
+
+    fn update(&self, inv_hessian: &H, s: &P, y: &P, bs: &P) -> Result<Option<H>, Error> {
+        let sbs: f64 = s.dot(bs);
+        if sbs <= 0.0 {
+            return Ok(None);
+        }
+        let sy: f64 = s.dot(y);
+        if sy >= 0.2 * sbs {
+            return Ok(Some(bfgs(inv_hessian, s, y)));
+        }
+        let theta = 0.8 * sbs / (sbs - sy);
+        let r = y.mul(&theta).scaled_add(&(1.0 - theta), bs);
+        Ok(Some(bfgs(inv_hessian, s, &r)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(bfgs_update, BFGSUpdate);
+    test_trait_impl!(dfp_update, DFPUpdate);
+    test_trait_impl!(broyden_update, BroydenUpdate);
+    test_trait_impl!(sr1_update, SR1Update);
+    test_trait_impl!(damped_bfgs_update, DampedBFGSUpdate);
+
+    fn h0() -> Vec<Vec<f64>> {
+        vec![vec![2.0, 0.5], vec![0.5, 1.0]]
+    }
+
+    /// B = H^{-1} of `h0`
+    fn b0() -> Vec<Vec<f64>> {
+        let det = 2.0 - 0.25;
+        vec![vec![1.0 / det, -0.5 / det], vec![-0.5 / det, 2.0 / det]]
+    }
+
+    fn assert_secant(h: &[Vec<f64>], s: &[f64], y: &[f64]) {
+        for i in 0..2 {
+            let hy = h[i][0] * y[0] + h[i][1] * y[1];
+            assert!((hy - s[i]).abs() < 1e-12);
+            assert!((h[i][1 - i] - h[1 - i][i]).abs() < 1e-12);
+        }
+    }
+
+    fn update<U: QuasiNewtonUpdate<Vec<f64>, Vec<Vec<f64>>>>(
+        u: U,
+        s: &Vec<f64>,
+        y: &Vec<f64>,
+    ) -> Option<Vec<Vec<f64>>> {
+        let bs = b0().dot(s);
+        u.update(&h0(), s, y, &bs).unwrap()
+    }
+
+    #[test]
+    fn test_secant_equation() {
+        let s = vec![0.3, -0.2];
+        let y = vec![1.0, 0.4];
+        assert_secant(&update(BFGSUpdate::new(), &s, &y).unwrap(), &s, &y);
+        assert_secant(&update(DFPUpdate::new(), &s, &y).unwrap(), &s, &y);
+        assert_secant(
+            &update(BroydenUpdate::new(0.3).unwrap(), &s, &y).unwrap(),
+            &s,
+            &y,
+        );
+        assert_secant(&update(SR1Update::new(), &s, &y).unwrap(), &s, &y);
+        assert_secant(&update(DampedBFGSUpdate::new(), &s, &y).unwrap(), &s, &y);
+    }
+
+    #[test]
+    fn test_broyden_end_points() {
+        let s = vec![0.3, -0.2];
+        let y = vec![1.0, 0.4];
+        let bfgs = update(BFGSUpdate::new(), &s, &y).unwrap();
+        let dfp = update(DFPUpdate::new(), &s, &y).unwrap();
+        let b0 = update(BroydenUpdate::new(0.0).unwrap(), &s, &y).unwrap();
+        let b1 = update(BroydenUpdate::new(1.0).unwrap(), &s, &y).unwrap();
+        for i in 0..2 {
+            for j in 0..2 {
+                assert!((bfgs[i][j] - b0[i][j]).abs() < 1e-12);
+                assert!((dfp[i][j] - b1[i][j]).abs() < 1e-12);
+            }
+        }
+        assert!(BroydenUpdate::new(1.5).is_err());
+    }
+
+    #[test]
+    fn test_damped_bfgs_negative_curvature() {
+        // s^T y < 0: the damped update is positive definite
+        let s = vec![0.3, -0.2];
+        let y = vec![-1.0, 0.4];
+        let h = update(DampedBFGSUpdate::new(), &s, &y).unwrap();
+        assert!(h[0][0] > 0.0);
+        assert!(h[0][0] * h[1][1] - h[0][1] * h[1][0] > 0.0);
+    }
+
+    #[test]
+    fn test_sr1_skip() {
+        // s = H y: the denominator vanishes and the update is skipped
+        let y = vec![1.0, 0.4];
+        let s = h0().dot(&y);
+        assert!(update(SR1Update::new(), &s, &y).is_none());
+        assert!(SR1Update::new().r(2.0).is_err());
+    }
+}
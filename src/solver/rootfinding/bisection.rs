@@ -0,0 +1,195 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+//! Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+//! ISBN 0-521-88068-8.
+
+use super::{check_factor, check_params, init_bracket};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// Bisection method
+///
+/// Halves the bracketing interval in every iteration. Slow, but guaranteed to converge.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/rootfinding.rs)
+///
+/// # References:
+///
+/// [0] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+/// Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+/// ISBN 0-521-88068-8.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct Bisection {
+    /// required absolute accuracy
+    tol: f64,
+    /// expansion factor of the automatic bracketing
+    expand: Option<f64>,
+    /// lower end of current interval
+    a: f64,
+    /// upper end of current interval
+    b: f64,
+    /// function value at `a`
+    fa: f64,
+    /// function value at `b`
+    fb: f64,
+}
+
+impl Bisection {
+    /// Constructor
+    /// The values `min` and `max` must bracket the root of the function unless `expand_bracket`
+    /// is used. The parameter `tol` specifies the width of the final interval.
+    pub fn new(min: f64, max: f64, tol: f64) -> Result<Self, Error> {
+        check_params("Bisection", min, max, tol)?;
+        Ok(Bisection {
+            tol,
+            expand: None,
+            a: min,
+            b: max,
+            fa: f64::NAN,
+            fb: f64::NAN,
+        })
+    }
+
+    /// Expand the initial interval by `factor` until it brackets a root
+    pub fn expand_bracket(mut self, factor: f64) -> Result<Self, Error> {
+        check_factor("Bisection", factor)?;
+        self.expand = Some(factor);
+        Ok(self)
+    }
+}
+
+impl<O> Solver<O> for Bisection
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    const NAME: &'static str = "Bisection";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // Bisection maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let br = init_bracket(op, self.a, self.b, self.expand)?;
+        self.a = br.a;
+        self.b = br.b;
+        self.fa = br.fa;
+        self.fb = br.fb;
+        let (x, fx) = if self.fa.abs() < self.fb.abs() {
+            (self.a, self.fa)
+        } else {
+            (self.b, self.fb)
+        };
+        Ok(Some(ArgminIterData::new().param(x).cost(fx.abs()).kv(
+            make_kv!("a" => self.a;
+                     "b" => self.b;
+                     "expansions" => br.history.len() - 1;
+                     "bracket_history" => br.history;),
+        )))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // Bisection maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let m = 0.5 * (self.a + self.b);
+        let fm = op.apply(&m)?;
+        if fm * self.fa > 0.0 {
+            self.a = m;
+            self.fa = fm;
+        } else {
+            self.b = m;
+            self.fb = fm;
+        }
+        let mut out = ArgminIterData::new()
+            .param(m)
+            .cost(fm.abs())
+            .kv(make_kv!("a" => self.a; "b" => self.b;));
+        if self.b - self.a <= self.tol || fm == 0.0 {
+            out = out.termination_reason(TerminationReason::TargetPrecisionReached);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{is_bracket_error, solve, Cubic, ROOT};
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(bisection, Bisection);
+
+    #[test]
+    fn test_root() {
+        let (x, reason, _) = solve(Bisection::new(2.0, 3.0, 1e-10).unwrap()).unwrap();
+        assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+        assert!((x - ROOT).abs() <= 1e-10);
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let (x, reason, coarse_iters) = solve(Bisection::new(2.0, 3.0, 1e-3).unwrap()).unwrap();
+        assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+        assert!((x - ROOT).abs() <= 1e-3);
+        let (_, _, fine_iters) = solve(Bisection::new(2.0, 3.0, 1e-12).unwrap()).unwrap();
+        assert!(coarse_iters < fine_iters);
+    }
+
+    #[test]
+    fn test_invalid_bracket() {
+        assert!(Bisection::new(3.0, 2.0, 1e-10).is_err());
+        let err = solve(Bisection::new(3.0, 4.0, 1e-10).unwrap()).unwrap_err();
+        assert!(is_bracket_error(&err));
+    }
+
+    #[test]
+    fn test_expand_bracket() {
+        let mut solver = Bisection::new(3.0, 4.0, 1e-10)
+            .unwrap()
+            .expand_bracket(1.6)
+            .unwrap();
+        let mut op = OpWrapper::new(&Cubic {});
+        let data = solver.init(&mut op, &IterState::new(0.0)).unwrap().unwrap();
+        let kv = data.get_kv();
+        let get = |key: &str| kv.kv.iter().find(|(k, _)| *k == key).unwrap().1.clone();
+        assert_eq!(get("expansions"), "1");
+        assert_eq!(get("bracket_history"), "[(3.0, 4.0), (1.4, 4.0)]");
+        let (x, _, _) = solve(
+            Bisection::new(3.0, 4.0, 1e-10)
+                .unwrap()
+                .expand_bracket(1.6)
+                .unwrap(),
+        )
+        .unwrap();
+        assert!((x - ROOT).abs() <= 1e-10);
+
+        // without a sign change, all intervals tried are part of the error
+        let err = solve(
+            Bisection::new(3.0, 4.0, 1e-10)
+                .unwrap()
+                .expand_bracket(1e-3)
+                .unwrap(),
+        )
+        .unwrap_err();
+        assert!(is_bracket_error(&err));
+        let err = err
+            .downcast_ref::<crate::solver::bracketing::RootBracketError>()
+            .unwrap();
+        assert_eq!(err.history.len(), 51);
+        assert_eq!(err.history[0], (3.0, 4.0));
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] M. Dowell and P. Jarratt (1971). A modified regula falsi method for computing the root of
+//! an equation. BIT Numerical Mathematics 11, 168–174.
+//!
+//! [1] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+//! Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+//! ISBN 0-521-88068-8.
+
+use super::{check_factor, check_params, init_bracket};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// Illinois method
+///
+/// Regula falsi (false position) method with the Illinois modification: whenever an end of the
+/// bracketing interval is retained, its function value is halved. This avoids the one-sided
+/// convergence of plain regula falsi and results in superlinear convergence.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/rootfinding.rs)
+///
+/// # References:
+///
+/// [0] M. Dowell and P. Jarratt (1971). A modified regula falsi method for computing the root of
+/// an equation. BIT Numerical Mathematics 11, 168–174.
+///
+/// [1] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+/// Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+/// ISBN 0-521-88068-8.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct Illinois {
+    /// required absolute accuracy
+    tol: f64,
+    /// expansion factor of the automatic bracketing
+    expand: Option<f64>,
+    /// end of current interval which was retained
+    a: f64,
+    /// end of current interval which was updated last
+    b: f64,
+    /// (possibly scaled) function value at `a`
+    fa: f64,
+    /// function value at `b`
+    fb: f64,
+}
+
+impl Illinois {
+    /// Constructor
+    /// The values `min` and `max` must bracket the root of the function unless `expand_bracket`
+    /// is used. The parameter `tol` specifies the width of the final interval.
+    pub fn new(min: f64, max: f64, tol: f64) -> Result<Self, Error> {
+        check_params("Illinois", min, max, tol)?;
+        Ok(Illinois {
+            tol,
+            expand: None,
+            a: min,
+            b: max,
+            fa: f64::NAN,
+            fb: f64::NAN,
+        })
+    }
+
+    /// Expand the initial interval by `factor` until it brackets a root
+    pub fn expand_bracket(mut self, factor: f64) -> Result<Self, Error> {
+        check_factor("Illinois", factor)?;
+        self.expand = Some(factor);
+        Ok(self)
+    }
+}
+
+impl<O> Solver<O> for Illinois
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    const NAME: &'static str = "Illinois";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // Illinois maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let br = init_bracket(op, self.a, self.b, self.expand)?;
+        self.a = br.a;
+        self.b = br.b;
+        self.fa = br.fa;
+        self.fb = br.fb;
+        let (x, fx) = if self.fa.abs() < self.fb.abs() {
+            (self.a, self.fa)
+        } else {
+            (self.b, self.fb)
+        };
+        Ok(Some(ArgminIterData::new().param(x).cost(fx.abs()).kv(
+            make_kv!("a" => self.a;
+                     "b" => self.b;
+                     "expansions" => br.history.len() - 1;
+                     "bracket_history" => br.history;),
+        )))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // Illinois maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let c = (self.a * self.fb - self.b * self.fa) / (self.fb - self.fa);
+        let fc = op.apply(&c)?;
+        if fc * self.fb < 0.0 {
+            self.a = self.b;
+            self.fa = self.fb;
+        } else {
+            self.fa *= 0.5;
+        }
+        self.b = c;
+        self.fb = fc;
+        let mut out = ArgminIterData::new()
+            .param(c)
+            .cost(fc.abs())
+            .kv(make_kv!("a" => self.a.min(self.b); "b" => self.a.max(self.b);));
+        if (self.b - self.a).abs() <= self.tol || fc == 0.0 {
+            out = out.termination_reason(TerminationReason::TargetPrecisionReached);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{is_bracket_error, solve, ROOT};
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(illinois, Illinois);
+
+    #[test]
+    fn test_root() {
+        let (x, reason, _) = solve(Illinois::new(2.0, 3.0, 1e-10).unwrap()).unwrap();
+        assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+        assert!((x - ROOT).abs() <= 1e-10);
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let (x, reason, coarse_iters) = solve(Illinois::new(2.0, 3.0, 1e-3).unwrap()).unwrap();
+        assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+        assert!((x - ROOT).abs() <= 1e-3);
+        let (_, _, fine_iters) = solve(Illinois::new(2.0, 3.0, 1e-12).unwrap()).unwrap();
+        assert!(coarse_iters < fine_iters);
+    }
+
+    #[test]
+    fn test_invalid_bracket() {
+        assert!(Illinois::new(3.0, 2.0, 1e-10).is_err());
+        let err = solve(Illinois::new(3.0, 4.0, 1e-10).unwrap()).unwrap_err();
+        assert!(is_bracket_error(&err));
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Root finding methods for one dimensional problems
+//!
+//! All methods require an interval `[min, max]` in which the function changes sign. If
+//! `expand_bracket` is set, the interval is expanded automatically until it brackets a root (see
+//! `bracketing::expand_root_bracket`). Otherwise, or if no sign change can be found, a
+//! `RootBracketError` is returned, which contains all intervals tried. The cost is the absolute
+//! function value and the current bracket is reported in the `kv` of each iteration. The `kv` of
+//! the initialization additionally contains the number of expansions and all intervals tried
+//! (`bracket_history`).
+//!
+//! # References:
+//!
+//! [0] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+//! Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+//! ISBN 0-521-88068-8.
+
+/// Bisection method
+pub mod bisection;
+/// Illinois method
+pub mod illinois;
+/// Ridders' method
+pub mod ridders;
+/// Safeguarded Newton method
+pub mod rtsafe;
+
+pub use self::bisection::*;
+pub use self::illinois::*;
+pub use self::ridders::*;
+pub use self::rtsafe::*;
+
+use crate::prelude::*;
+use crate::solver::bracketing::{expand_root_bracket, RootBracket, RootBracketError};
+
+/// Maximum number of expansions of the initial interval
+const MAX_EXPANSIONS: u64 = 50;
+
+/// Checks the initial interval of a root finder and expands it if `expand` is set
+fn init_bracket<O>(
+    op: &mut OpWrapper<O>,
+    min: f64,
+    max: f64,
+    expand: Option<f64>,
+) -> Result<RootBracket, Error>
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    if let Some(factor) = expand {
+        return expand_root_bracket(op, min, max, factor, MAX_EXPANSIONS);
+    }
+    let fa = op.apply(&min)?;
+    let fb = op.apply(&max)?;
+    if fa * fb > 0.0 {
+        return Err(RootBracketError {
+            a: min,
+            b: max,
+            history: vec![(min, max)],
+        }
+        .into());
+    }
+    Ok(RootBracket {
+        a: min,
+        b: max,
+        fa,
+        fb,
+        history: vec![(min, max)],
+    })
+}
+
+/// Checks the parameters shared by all root finders
+fn check_params(name: &str, min: f64, max: f64, tol: f64) -> Result<(), Error> {
+    if min >= max || tol <= 0.0 {
+        return Err(ArgminError::InvalidParameter {
+            text: format!(
+                "{}: min must be smaller than max and tol must be > 0.",
+                name
+            ),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Checks the expansion factor shared by all root finders
+fn check_factor(name: &str, factor: f64) -> Result<(), Error> {
+    if factor <= 0.0 {
+        return Err(ArgminError::InvalidParameter {
+            text: format!("{}: expansion factor must be > 0.", name),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serde1")]
+    use serde::{Deserialize, Serialize};
+
+    /// `f(x) = x^3 - 2x - 5`, which has a single real root at `ROOT`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    pub(super) struct Cubic {}
+
+    pub(super) const ROOT: f64 = 2.094_551_481_542_326_5;
+
+    impl ArgminOp for Cubic {
+        type Param = f64;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+
+        fn apply(&self, p: &f64) -> Result<f64, Error> {
+            Ok(p.powi(3) - 2.0 * p - 5.0)
+        }
+
+        fn gradient(&self, p: &f64) -> Result<f64, Error> {
+            Ok(3.0 * p.powi(2) - 2.0)
+        }
+    }
+
+    /// Runs `solver` on `Cubic` and returns the final estimate, the termination reason and the
+    /// number of iterations
+    pub(super) fn solve<S: Solver<Cubic>>(
+        solver: S,
+    ) -> Result<(f64, TerminationReason, u64), Error> {
+        let res = Executor::new(Cubic {}, solver, 0.0).max_iters(200).run()?;
+        Ok((
+            res.state.get_param(),
+            res.state.get_termination_reason(),
+            res.state.get_iter(),
+        ))
+    }
+
+    /// Checks whether `e` was caused by an interval which does not bracket a root
+    pub(super) fn is_bracket_error(e: &Error) -> bool {
+        e.downcast_ref::<RootBracketError>().is_some()
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] C. Ridders (1979). A new algorithm for computing a single root of a real continuous
+//! function. IEEE Transactions on Circuits and Systems 26(11), 979-980.
+//!
+//! [1] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+//! Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+//! ISBN 0-521-88068-8.
+
+use super::{check_factor, check_params, init_bracket};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// Ridders' method
+///
+/// Evaluates the function at the midpoint of the bracketing interval and applies the regula falsi
+/// method to an exponentially rescaled function. Requires two function evaluations per iteration
+/// and converges quadratically while always keeping the root bracketed.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/rootfinding.rs)
+///
+/// # References:
+///
+/// [0] C. Ridders (1979). A new algorithm for computing a single root of a real continuous
+/// function. IEEE Transactions on Circuits and Systems 26(11), 979-980.
+///
+/// [1] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+/// Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+/// ISBN 0-521-88068-8.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct Ridders {
+    /// required absolute accuracy
+    tol: f64,
+    /// expansion factor of the automatic bracketing
+    expand: Option<f64>,
+    /// lower end of current interval
+    a: f64,
+    /// upper end of current interval
+    b: f64,
+    /// function value at `a`
+    fa: f64,
+    /// function value at `b`
+    fb: f64,
+    /// previous estimate of the root
+    x: f64,
+}
+
+impl Ridders {
+    /// Constructor
+    /// The values `min` and `max` must bracket the root of the function unless `expand_bracket`
+    /// is used. The parameter `tol` specifies the width of the final interval.
+    pub fn new(min: f64, max: f64, tol: f64) -> Result<Self, Error> {
+        check_params("Ridders", min, max, tol)?;
+        Ok(Ridders {
+            tol,
+            expand: None,
+            a: min,
+            b: max,
+            fa: f64::NAN,
+            fb: f64::NAN,
+            x: f64::NAN,
+        })
+    }
+
+    /// Expand the initial interval by `factor` until it brackets a root
+    pub fn expand_bracket(mut self, factor: f64) -> Result<Self, Error> {
+        check_factor("Ridders", factor)?;
+        self.expand = Some(factor);
+        Ok(self)
+    }
+}
+
+impl<O> Solver<O> for Ridders
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    const NAME: &'static str = "Ridders";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // Ridders maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let br = init_bracket(op, self.a, self.b, self.expand)?;
+        self.a = br.a;
+        self.b = br.b;
+        self.fa = br.fa;
+        self.fb = br.fb;
+        self.x = 0.5 * (self.a + self.b);
+        let (x, fx) = if self.fa.abs() < self.fb.abs() {
+            (self.a, self.fa)
+        } else {
+            (self.b, self.fb)
+        };
+        Ok(Some(ArgminIterData::new().param(x).cost(fx.abs()).kv(
+            make_kv!("a" => self.a;
+                     "b" => self.b;
+                     "expansions" => br.history.len() - 1;
+                     "bracket_history" => br.history;),
+        )))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // Ridders maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let m = 0.5 * (self.a + self.b);
+        let fm = op.apply(&m)?;
+        let s = (fm.powi(2) - self.fa * self.fb).sqrt();
+        let (x, fx) = if s == 0.0 {
+            (m, fm)
+        } else {
+            let x = m + (m - self.a) * (self.fa - self.fb).signum() * fm / s;
+            (x, op.apply(&x)?)
+        };
+        // keep the smallest interval which still brackets the root
+        if fm * fx < 0.0 {
+            if m < x {
+                self.a = m;
+                self.fa = fm;
+                self.b = x;
+                self.fb = fx;
+            } else {
+                self.a = x;
+                self.fa = fx;
+                self.b = m;
+                self.fb = fm;
+            }
+        } else if self.fa * fx < 0.0 {
+            self.b = x;
+            self.fb = fx;
+        } else {
+            self.a = x;
+            self.fa = fx;
+        }
+        let mut out = ArgminIterData::new()
+            .param(x)
+            .cost(fx.abs())
+            .kv(make_kv!("a" => self.a; "b" => self.b;));
+        let dx = (x - self.x).abs();
+        self.x = x;
+        if dx <= self.tol || self.b - self.a <= self.tol || fx == 0.0 {
+            out = out.termination_reason(TerminationReason::TargetPrecisionReached);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{is_bracket_error, solve, ROOT};
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(ridders, Ridders);
+
+    #[test]
+    fn test_root() {
+        let (x, reason, _) = solve(Ridders::new(2.0, 3.0, 1e-10).unwrap()).unwrap();
+        assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+        assert!((x - ROOT).abs() <= 1e-10);
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let (x, reason, coarse_iters) = solve(Ridders::new(2.0, 3.0, 1e-3).unwrap()).unwrap();
+        assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+        assert!((x - ROOT).abs() <= 1e-3);
+        let (_, _, fine_iters) = solve(Ridders::new(2.0, 3.0, 1e-12).unwrap()).unwrap();
+        assert!(coarse_iters < fine_iters);
+    }
+
+    #[test]
+    fn test_invalid_bracket() {
+        assert!(Ridders::new(3.0, 2.0, 1e-10).is_err());
+        let err = solve(Ridders::new(3.0, 4.0, 1e-10).unwrap()).unwrap_err();
+        assert!(is_bracket_error(&err));
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+//! Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+//! ISBN 0-521-88068-8.
+
+use super::{check_factor, check_params, init_bracket};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// Safeguarded Newton method (rtsafe)
+///
+/// Takes Newton steps using the derivative provided by `ArgminOp::gradient`, but falls back to
+/// bisection whenever the Newton step would leave the bracketing interval or does not reduce the
+/// interval fast enough.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/rootfinding.rs)
+///
+/// # References:
+///
+/// [0] William H. Press, Saul A. Teukolsky, William T. Vetterling and Brian P. Flannery (2007).
+/// Numerical Recipes: The Art of Scientific Computing. Cambridge University Press.
+/// ISBN 0-521-88068-8.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct NewtonSafe {
+    /// required absolute accuracy
+    tol: f64,
+    /// expansion factor of the automatic bracketing
+    expand: Option<f64>,
+    /// end of current interval with negative function value
+    xl: f64,
+    /// end of current interval with positive function value
+    xh: f64,
+    /// current estimate of the root
+    x: f64,
+    /// function value at `x`
+    f: f64,
+    /// derivative at `x`
+    df: f64,
+    /// last step
+    dx: f64,
+    /// step before the last one
+    dxold: f64,
+}
+
+impl NewtonSafe {
+    /// Constructor
+    /// The values `min` and `max` must bracket the root of the function unless `expand_bracket`
+    /// is used. The parameter `tol` specifies the required accuracy of the root.
+    pub fn new(min: f64, max: f64, tol: f64) -> Result<Self, Error> {
+        check_params("NewtonSafe", min, max, tol)?;
+        Ok(NewtonSafe {
+            tol,
+            expand: None,
+            xl: min,
+            xh: max,
+            x: f64::NAN,
+            f: f64::NAN,
+            df: f64::NAN,
+            dx: f64::NAN,
+            dxold: f64::NAN,
+        })
+    }
+
+    /// Expand the initial interval by `factor` until it brackets a root
+    pub fn expand_bracket(mut self, factor: f64) -> Result<Self, Error> {
+        check_factor("NewtonSafe", factor)?;
+        self.expand = Some(factor);
+        Ok(self)
+    }
+}
+
+impl<O> Solver<O> for NewtonSafe
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    const NAME: &'static str = "Safeguarded Newton method";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // NewtonSafe maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let br = init_bracket(op, self.xl, self.xh, self.expand)?;
+        if br.fa < 0.0 {
+            self.xl = br.a;
+            self.xh = br.b;
+        } else {
+            self.xl = br.b;
+            self.xh = br.a;
+        }
+        self.x = 0.5 * (br.a + br.b);
+        self.dxold = (br.b - br.a).abs();
+        self.dx = self.dxold;
+        self.f = op.apply(&self.x)?;
+        self.df = op.gradient(&self.x)?;
+        Ok(Some(
+            ArgminIterData::new()
+                .param(self.x)
+                .cost(self.f.abs())
+                .kv(make_kv!(
+                    "a" => br.a;
+                    "b" => br.b;
+                    "expansions" => br.history.len() - 1;
+                    "bracket_history" => br.history;
+                )),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        // NewtonSafe maintains its own state
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let out_of_range =
+            ((self.x - self.xh) * self.df - self.f) * ((self.x - self.xl) * self.df - self.f) > 0.0;
+        let too_slow = (2.0 * self.f).abs() > (self.dxold * self.df).abs();
+        let newton_step = !(out_of_range || too_slow);
+        self.dxold = self.dx;
+        if newton_step {
+            self.dx = self.f / self.df;
+            self.x -= self.dx;
+        } else {
+            self.dx = 0.5 * (self.xh - self.xl);
+            self.x = self.xl + self.dx;
+        }
+        self.f = op.apply(&self.x)?;
+        self.df = op.gradient(&self.x)?;
+        if self.f < 0.0 {
+            self.xl = self.x;
+        } else {
+            self.xh = self.x;
+        }
+        let mut out = ArgminIterData::new()
+            .param(self.x)
+            .cost(self.f.abs())
+            .kv(make_kv!(
+                "a" => self.xl.min(self.xh);
+                "b" => self.xl.max(self.xh);
+                "newton_step" => newton_step;
+            ));
+        if self.dx.abs() <= self.tol || self.f == 0.0 {
+            out = out.termination_reason(TerminationReason::TargetPrecisionReached);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{is_bracket_error, solve, ROOT};
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(newtonsafe, NewtonSafe);
+
+    #[test]
+    fn test_root() {
+        let (x, reason, _) = solve(NewtonSafe::new(2.0, 3.0, 1e-10).unwrap()).unwrap();
+        assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+        assert!((x - ROOT).abs() <= 1e-10);
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let (x, reason, coarse_iters) = solve(NewtonSafe::new(2.0, 3.0, 1e-3).unwrap()).unwrap();
+        assert_eq!(reason, TerminationReason::TargetPrecisionReached);
+        assert!((x - ROOT).abs() <= 1e-3);
+        let (_, _, fine_iters) = solve(NewtonSafe::new(2.0, 3.0, 1e-12).unwrap()).unwrap();
+        assert!(coarse_iters < fine_iters);
+    }
+
+    #[test]
+    fn test_invalid_bracket() {
+        assert!(NewtonSafe::new(3.0, 2.0, 1e-10).is_err());
+        let err = solve(NewtonSafe::new(3.0, 4.0, 1e-10).unwrap()).unwrap_err();
+        assert!(is_bracket_error(&err));
+    }
+}
@@ -0,0 +1,529 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Sequential quadratic programming
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] M. J. D. Powell (1978). A fast algorithm for nonlinearly constrained optimization
+//! calculations. Numerical Analysis, Lecture Notes in Mathematics 630, 144-157.
+
+use crate::constraints::{check_rows, infeasibility};
+use crate::prelude::*;
+use crate::solver::qp::ActiveSetQP;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+/// Sufficient decrease parameter of the merit line search
+const ARMIJO: f64 = 1e-4;
+
+/// Smallest step length of the merit line search
+const MIN_STEP: f64 = 1e-10;
+
+/// Sequential quadratic programming (SQP)
+///
+/// Solves problems with equality constraints `h(x) = 0` and inequality constraints `g(x) <= 0`
+/// (see `ArgminConstraints`). In each iteration the quadratic program
+///
+/// ```text
+/// min 1/2 p^T B p + grad f(x)^T p  subject to  h(x) + J_h(x) p = 0,  g(x) + J_g(x) p <= 0
+/// ```
+///
+/// is solved with `ActiveSetQP`, warm started with the active set of the previous iteration. `B`
+/// approximates the Hessian of the Lagrangian `f(x) + lambda^T h(x) + mu^T g(x)` and is updated
+/// with Powell's damped BFGS formula, which keeps it positive definite. The step length is
+/// determined by a backtracking line search on the l1 merit function
+/// `f(x) + nu (||h(x)||_1 + ||max(g(x), 0)||_1)`, where the penalty parameter `nu` is increased
+/// as necessary to make `p` a descent direction.
+///
+/// The solver terminates once the KKT conditions (stationarity of the Lagrangian, feasibility and
+/// complementarity) are satisfied up to `tol`. Their residuals and the multipliers `lambda_eq`
+/// and `lambda_ineq` are reported in the key-value store; the multipliers are also available via
+/// `multipliers`. As for `AugmentedLagrangian`, the cost is only reported for iterates which
+/// violate the constraints by at most `tol`, all other iterates have infinite cost.
+///
+/// If the linearized constraints are inconsistent, the error of the QP solver is returned.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/sqp.rs)
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+///
+/// [1] M. J. D. Powell (1978). A fast algorithm for nonlinearly constrained optimization
+/// calculations. Numerical Analysis, Lecture Notes in Mathematics 630, 144-157.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct SQP<H> {
+    /// approximation of the Hessian of the Lagrangian
+    hessian: H,
+    /// tolerance of the KKT conditions
+    tol: f64,
+    /// maximum number of iterations of the QP solver
+    max_qp_iters: u64,
+    /// penalty parameter of the merit function
+    penalty: f64,
+    /// multipliers of the equality constraints
+    lambda_eq: Vec<f64>,
+    /// multipliers of the inequality constraints
+    lambda_ineq: Vec<f64>,
+    /// active set of the previous QP subproblem
+    active_set: Vec<usize>,
+    /// cost function value of the current iterate
+    cost: f64,
+}
+
+impl<H> SQP<H> {
+    /// Constructor
+    pub fn new(init_hessian: H) -> Self {
+        SQP {
+            hessian: init_hessian,
+            tol: 1e-8,
+            max_qp_iters: 1000,
+            penalty: 0.0,
+            lambda_eq: vec![],
+            lambda_ineq: vec![],
+            active_set: vec![],
+            cost: f64::INFINITY,
+        }
+    }
+
+    /// Set tolerance of the KKT conditions (default: 1e-8)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "SQP: tol must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Set maximum number of iterations of the QP solver (default: 1000)
+    pub fn max_qp_iters(mut self, iters: u64) -> Self {
+        self.max_qp_iters = iters;
+        self
+    }
+
+    /// Multipliers of the equality and the inequality constraints of the current iterate
+    pub fn multipliers(&self) -> (&[f64], &[f64]) {
+        (&self.lambda_eq, &self.lambda_ineq)
+    }
+}
+
+/// l1 norm of the violation of the constraints `h(x) = 0` and `g(x) <= 0`
+fn l1_violation(h: &[f64], g: &[f64]) -> f64 {
+    h.iter().map(|hi| hi.abs()).sum::<f64>() + g.iter().map(|gj| gj.max(0.0)).sum::<f64>()
+}
+
+/// Gradient of the Lagrangian `grad f + sum_i lambda_i grad h_i + sum_j mu_j grad g_j`
+fn lagrangian_gradient<P>(
+    grad: &P,
+    jac_eq: &[P],
+    jac_ineq: &[P],
+    lambda_eq: &[f64],
+    lambda_ineq: &[f64],
+) -> P
+where
+    P: Clone + ArgminScaledAdd<P, f64, P>,
+{
+    jac_eq
+        .iter()
+        .zip(lambda_eq.iter())
+        .chain(jac_ineq.iter().zip(lambda_ineq.iter()))
+        .fold(grad.clone(), |acc, (row, l)| acc.scaled_add(l, row))
+}
+
+/// Constraint values and Jacobians `(h, J_h, g, J_g)`
+type Linearization<P> = (Vec<f64>, Vec<P>, Vec<f64>, Vec<P>);
+
+/// Constraint values and Jacobians at `param`
+fn linearize<O: ArgminConstraints>(
+    op: &OpWrapper<O>,
+    param: &O::Param,
+) -> Result<Linearization<O::Param>, Error> {
+    let problem = op.op.as_ref().unwrap();
+    let h = problem.eq_constraints(param)?;
+    let jac_eq = problem.eq_jacobian(param)?;
+    let g = problem.ineq_constraints(param)?;
+    let jac_ineq = problem.ineq_jacobian(param)?;
+    check_rows("SQP", &h, &jac_eq)?;
+    check_rows("SQP", &g, &jac_ineq)?;
+    Ok((h, jac_eq, g, jac_ineq))
+}
+
+impl<O> Solver<O> for SQP<O::Hessian>
+where
+    O: ArgminConstraints<Output = f64>,
+    O::Param: ArgminSub<O::Param, O::Param>
+        + ArgminAdd<O::Param, O::Param>
+        + ArgminDot<O::Param, f64>
+        + ArgminDot<O::Param, O::Hessian>
+        + ArgminScaledAdd<O::Param, f64, O::Param>
+        + ArgminMul<f64, O::Param>
+        + ArgminNorm<f64>
+        + ArgminZeroLike,
+    O::Hessian: ArgminDot<O::Param, O::Param>
+        + ArgminInv<O::Hessian>
+        + ArgminAdd<O::Hessian, O::Hessian>
+        + ArgminSub<O::Hessian, O::Hessian>
+        + ArgminMul<f64, O::Hessian>,
+{
+    const NAME: &'static str = "SQP";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        let (h, _, g, _) = linearize(op, &param)?;
+        self.lambda_eq = vec![0.0; h.len()];
+        self.lambda_ineq = vec![0.0; g.len()];
+        self.cost = op.apply(&param)?;
+        let grad = op.gradient(&param)?;
+        let violation = infeasibility(&h, &g);
+        let cost = if violation <= self.tol {
+            self.cost
+        } else {
+            f64::INFINITY
+        };
+        Ok(Some(
+            ArgminIterData::new()
+                .param(param)
+                .cost(cost)
+                .grad(grad)
+                .kv(make_kv!("violation" => violation;)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let grad = state.get_grad().unwrap();
+        let (h, jac_eq, g, jac_ineq) = linearize(op, &param)?;
+
+        // QP subproblem
+        let mut qp_op = OpWrapper::new_move(SQPSubproblem {
+            hessian: self.hessian.clone(),
+            grad: grad.clone(),
+        });
+        let mut qp = ActiveSetQP::new(self.active_set.clone())
+            .equality(jac_eq.clone(), h.iter().map(|hi| -hi).collect())?
+            .inequality(jac_ineq.clone(), g.iter().map(|gj| -gj).collect())?;
+        let mut qp_state = IterState::new(grad.zero_like());
+        if let Some(data) = qp.init(&mut qp_op, &qp_state)? {
+            qp_state.param(data.get_param().unwrap());
+        }
+        let mut converged = false;
+        for _ in 0..self.max_qp_iters {
+            let data = qp.next_iter(&mut qp_op, &qp_state)?;
+            qp_state.param(data.get_param().unwrap());
+            if data.get_termination_reason().is_some() {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            return Err(ArgminError::ConditionViolated {
+                text: "SQP: QP subproblem did not converge.".to_string(),
+            }
+            .into());
+        }
+        let p = qp_state.get_param();
+        let (qp_lambda_eq, qp_lambda_ineq) = qp.multipliers();
+        self.active_set = qp.active_set();
+
+        // penalty parameter of the merit function
+        let violation = l1_violation(&h, &g);
+        let gp: f64 = grad.dot(&p);
+        let bp = self.hessian.dot(&p);
+        let pbp: f64 = p.dot(&bp);
+        let mut required = qp_lambda_eq
+            .iter()
+            .chain(qp_lambda_ineq.iter())
+            .fold(0.0, |acc: f64, l| acc.max(l.abs()));
+        if violation > 0.0 {
+            required = required.max((gp + 0.5 * pbp.max(0.0)) / (0.5 * violation));
+        }
+        if self.penalty < required {
+            self.penalty = 1.1 * required;
+        }
+
+        // backtracking line search on the l1 merit function
+        let merit = self.cost + self.penalty * violation;
+        let slope = gp - self.penalty * violation;
+        let mut alpha = 1.0;
+        let (new_param, new_cost) = loop {
+            let trial = param.scaled_add(&alpha, &p);
+            let cost = op.apply(&trial)?;
+            let (h_trial, _, g_trial, _) = linearize(op, &trial)?;
+            let trial_merit = cost + self.penalty * l1_violation(&h_trial, &g_trial);
+            if trial_merit <= merit + ARMIJO * alpha * slope {
+                break (trial, cost);
+            }
+            alpha *= 0.5;
+            if alpha < MIN_STEP {
+                return Err(ArgminError::ConditionViolated {
+                    text: "SQP: line search on merit function failed.".to_string(),
+                }
+                .into());
+            }
+        };
+
+        // multiplier update
+        for (l, l_qp) in self.lambda_eq.iter_mut().zip(qp_lambda_eq.iter()) {
+            *l += alpha * (l_qp - *l);
+        }
+        for (l, l_qp) in self.lambda_ineq.iter_mut().zip(qp_lambda_ineq.iter()) {
+            *l += alpha * (l_qp - *l);
+        }
+
+        // damped BFGS update of the Hessian of the Lagrangian
+        let new_grad = op.gradient(&new_param)?;
+        let (new_h, new_jac_eq, new_g, new_jac_ineq) = linearize(op, &new_param)?;
+        let new_lagrangian_grad = lagrangian_gradient(
+            &new_grad,
+            &new_jac_eq,
+            &new_jac_ineq,
+            &self.lambda_eq,
+            &self.lambda_ineq,
+        );
+        let lagrangian_grad = lagrangian_gradient(
+            &grad,
+            &jac_eq,
+            &jac_ineq,
+            &self.lambda_eq,
+            &self.lambda_ineq,
+        );
+        let s = new_param.sub(&param);
+        let y = new_lagrangian_grad.sub(&lagrangian_grad);
+        let bs = self.hessian.dot(&s);
+        let sbs: f64 = s.dot(&bs);
+        let sy: f64 = s.dot(&y);
+        if sbs > 0.0 {
+            let theta = if sy >= 0.2 * sbs {
+                1.0
+            } else {
+                0.8 * sbs / (sbs - sy)
+            };
+            let r = y.mul(&theta).add(&bs.mul(&(1.0 - theta)));
+            let sr: f64 = s.dot(&r);
+            let bssb: O::Hessian = bs.dot(&bs);
+            let rr: O::Hessian = r.dot(&r);
+            self.hessian = self
+                .hessian
+                .sub(&bssb.mul(&(1.0 / sbs)))
+                .add(&rr.mul(&(1.0 / sr)));
+        }
+
+        // KKT conditions
+        let stationarity = new_lagrangian_grad.norm();
+        let violation = infeasibility(&new_h, &new_g);
+        let complementarity = new_g
+            .iter()
+            .zip(self.lambda_ineq.iter())
+            .map(|(gj, mu)| (gj * mu).abs())
+            .fold(0.0, f64::max);
+        self.cost = new_cost;
+        let cost = if violation <= self.tol {
+            new_cost
+        } else {
+            f64::INFINITY
+        };
+
+        let out = ArgminIterData::new()
+            .param(new_param)
+            .cost(cost)
+            .grad(new_grad)
+            .kv(make_kv!(
+                "stationarity" => stationarity;
+                "violation" => violation;
+                "complementarity" => complementarity;
+                "step_length" => alpha;
+                "penalty" => self.penalty;
+                "lambda_eq" => self.lambda_eq.clone();
+                "lambda_ineq" => self.lambda_ineq.clone();
+            ));
+        if stationarity <= self.tol && violation <= self.tol && complementarity <= self.tol {
+            return Ok(out.termination_reason(TerminationReason::TargetPrecisionReached));
+        }
+        Ok(out)
+    }
+}
+
+/// Quadratic model `1/2 p^T B p + grad^T p` of a step `p`. This is the operator passed to the QP
+/// solver of `SQP`.
+#[doc(hidden)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+pub struct SQPSubproblem<P, H> {
+    /// approximation of the Hessian of the Lagrangian
+    hessian: H,
+    /// gradient of the cost function
+    grad: P,
+}
+
+impl<P, H> ArgminOp for SQPSubproblem<P, H>
+where
+    P: Clone
+        + Send
+        + Sync
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminAdd<P, P>
+        + ArgminDot<P, f64>,
+    H: Clone + Send + Sync + SerializeAlias + DeserializeOwnedAlias + ArgminDot<P, P>,
+{
+    type Param = P;
+    type Output = f64;
+    type Hessian = H;
+    type Jacobian = ();
+
+    fn apply(&self, p: &P) -> Result<f64, Error> {
+        let gp: f64 = self.grad.dot(p);
+        let pbp: f64 = p.dot(&self.hessian.dot(p));
+        Ok(gp + 0.5 * pbp)
+    }
+
+    fn gradient(&self, p: &P) -> Result<P, Error> {
+        Ok(self.hessian.dot(p).add(&self.grad))
+    }
+
+    fn hessian(&self, _p: &P) -> Result<H, Error> {
+        Ok(self.hessian.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(sqp, SQP<Vec<Vec<f64>>>);
+
+    #[cfg(feature = "ndarrayl")]
+    mod ndarray_tests {
+        use super::*;
+        use ndarray::{array, Array1, Array2};
+
+        /// min x1 + x2 subject to x1^2 + x2^2 = 2 and x1 >= -0.5 (optionally)
+        #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+        #[derive(Clone, Default)]
+        struct Circle {
+            bounded: bool,
+        }
+
+        impl ArgminOp for Circle {
+            type Param = Array1<f64>;
+            type Output = f64;
+            type Hessian = Array2<f64>;
+            type Jacobian = ();
+
+            fn apply(&self, p: &Array1<f64>) -> Result<f64, Error> {
+                Ok(p[0] + p[1])
+            }
+
+            fn gradient(&self, _p: &Array1<f64>) -> Result<Array1<f64>, Error> {
+                Ok(array![1.0, 1.0])
+            }
+        }
+
+        impl ArgminConstraints for Circle {
+            fn eq_constraints(&self, p: &Array1<f64>) -> Result<Vec<f64>, Error> {
+                Ok(vec![p[0].powi(2) + p[1].powi(2) - 2.0])
+            }
+
+            fn eq_jacobian(&self, p: &Array1<f64>) -> Result<Vec<Array1<f64>>, Error> {
+                Ok(vec![array![2.0 * p[0], 2.0 * p[1]]])
+            }
+
+            fn ineq_constraints(&self, p: &Array1<f64>) -> Result<Vec<f64>, Error> {
+                Ok(if self.bounded {
+                    vec![-p[0] - 0.5]
+                } else {
+                    vec![]
+                })
+            }
+
+            fn ineq_jacobian(&self, _p: &Array1<f64>) -> Result<Vec<Array1<f64>>, Error> {
+                Ok(if self.bounded {
+                    vec![array![-1.0, 0.0]]
+                } else {
+                    vec![]
+                })
+            }
+        }
+
+        fn run(bounded: bool, init: Array1<f64>) -> (Array1<f64>, SQP<Array2<f64>>) {
+            let mut op = OpWrapper::new(&Circle { bounded });
+            let mut solver = SQP::new(Array2::eye(2));
+            let mut state = IterState::new(init);
+            let data = solver.init(&mut op, &state).unwrap().unwrap();
+            state.param(data.get_param().unwrap());
+            state.grad(data.get_grad().unwrap());
+            for _ in 0..100 {
+                let data = solver.next_iter(&mut op, &state).unwrap();
+                state.param(data.get_param().unwrap());
+                state.grad(data.get_grad().unwrap());
+                if data.get_termination_reason().is_some() {
+                    return (state.get_param(), solver);
+                }
+            }
+            panic!("SQP did not converge");
+        }
+
+        #[test]
+        fn test_equality_constrained() {
+            let (x, solver) = run(false, array![-0.5, -1.5]);
+            assert!((x[0] + 1.0).abs() < 1e-6);
+            assert!((x[1] + 1.0).abs() < 1e-6);
+            assert!((solver.multipliers().0[0] - 0.5).abs() < 1e-6);
+        }
+
+        #[test]
+        fn test_inequality_constrained() {
+            // solution (-0.5, -sqrt(1.75))
+            let (x, solver) = run(true, array![0.5, -1.0]);
+            let y = -(1.75f64).sqrt();
+            assert!((x[0] + 0.5).abs() < 1e-6);
+            assert!((x[1] - y).abs() < 1e-6);
+            // stationarity: 1 + 2 lambda x2 = 0, 1 + 2 lambda x1 - mu = 0
+            let lambda = -1.0 / (2.0 * y);
+            let mu = 1.0 - lambda;
+            let (lambda_eq, lambda_ineq) = solver.multipliers();
+            assert!((lambda_eq[0] - lambda).abs() < 1e-6);
+            assert!((lambda_ineq[0] - mu).abs() < 1e-6);
+        }
+
+        #[test]
+        fn test_executor() {
+            let res = Executor::new(
+                Circle { bounded: false },
+                SQP::new(Array2::eye(2)),
+                array![-0.5, -1.5],
+            )
+            .max_iters(100)
+            .run()
+            .unwrap();
+            assert_eq!(
+                res.state.get_termination_reason(),
+                TerminationReason::TargetPrecisionReached
+            );
+            assert!((res.state.get_best_cost() + 2.0).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] John Duchi, Elad Hazan and Yoram Singer (2011). Adaptive Subgradient Methods for Online
+//! Learning and Stochastic Optimization. Journal of Machine Learning Research 12, 2121-2159.
+
+use super::{adaptive_step, check_positive, sample_gradient};
+use crate::minibatch::{ArgminMinibatchOpWrapper, MinibatchSampler};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// AdaGrad
+///
+/// Scales the learning rate of each parameter by the inverse square root of the sum of all past
+/// squared gradients of this parameter:
+///
+/// ```text
+/// G_{k+1} = G_k + g_k^2
+/// x_{k+1} = x_k - learning_rate * g_k / (sqrt(G_{k+1}) + epsilon)
+/// ```
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/stochastic.rs)
+///
+/// # References:
+///
+/// [0] John Duchi, Elad Hazan and Yoram Singer (2011). Adaptive Subgradient Methods for Online
+/// Learning and Stochastic Optimization. Journal of Machine Learning Research 12, 2121-2159.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct AdaGrad {
+    /// learning rate
+    learning_rate: f64,
+    /// small constant for numerical stability
+    epsilon: f64,
+    /// sum of squared gradients
+    sum_sq: Vec<f64>,
+    /// minibatch sampler
+    sampler: MinibatchSampler,
+}
+
+impl AdaGrad {
+    /// Constructor
+    pub fn new(learning_rate: f64) -> Result<Self, Error> {
+        check_positive("AdaGrad", "learning_rate", learning_rate)?;
+        Ok(AdaGrad {
+            learning_rate,
+            epsilon: 1e-8,
+            sum_sq: vec![],
+            sampler: MinibatchSampler::new(1)?,
+        })
+    }
+
+    /// Set epsilon (default: 1e-8)
+    pub fn epsilon(mut self, epsilon: f64) -> Result<Self, Error> {
+        check_positive("AdaGrad", "epsilon", epsilon)?;
+        self.epsilon = epsilon;
+        Ok(self)
+    }
+
+    /// Set number of samples per minibatch (default: 1)
+    pub fn batch_size(mut self, batch_size: usize) -> Result<Self, Error> {
+        self.sampler.set_batch_size(batch_size)?;
+        Ok(self)
+    }
+
+    /// Seed the random number generator used for shuffling the samples
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.sampler = self.sampler.seed(seed);
+        self
+    }
+}
+
+impl<O> Solver<O> for AdaGrad
+where
+    O: ArgminMinibatchOp<Output = f64>,
+    O::Param: FromIterator<f64>,
+    for<'a> &'a O::Param: IntoIterator<Item = &'a f64>,
+{
+    const NAME: &'static str = "AdaGrad";
+
+    fn init(
+        &mut self,
+        _op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        self.sum_sq = vec![0.0; state.get_param().into_iter().count()];
+        Ok(None)
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let (batch, grad) = sample_gradient(op, &mut self.sampler, &param)?;
+        let grad: Vec<f64> = grad.into_iter().cloned().collect();
+        for (s, g) in self.sum_sq.iter_mut().zip(grad.iter()) {
+            *s += g.powi(2);
+        }
+        let new_param = adaptive_step(
+            &param,
+            &grad,
+            &self.sum_sq,
+            self.learning_rate,
+            self.epsilon,
+        );
+        let cost = op.batch_apply(&new_param, &batch)?;
+        Ok(ArgminIterData::new()
+            .param(new_param)
+            .cost(cost)
+            .kv(make_kv!("epoch" => self.sampler.epoch();)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::stochastic::tests::check_solver;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(adagrad, AdaGrad);
+
+    #[test]
+    fn test_adagrad() {
+        let solver = AdaGrad::new(0.5).unwrap().batch_size(4).unwrap().seed(1);
+        check_solver(solver, 3000, 1e-4);
+    }
+}
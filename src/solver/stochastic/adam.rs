@@ -0,0 +1,165 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Diederik P. Kingma and Jimmy Ba (2015). Adam: A Method for Stochastic Optimization.
+//! ICLR 2015. arXiv:1412.6980.
+
+use super::{adaptive_step, check_decay, check_positive, sample_gradient};
+use crate::minibatch::{ArgminMinibatchOpWrapper, MinibatchSampler};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// Adam
+///
+/// Uses bias-corrected exponentially decaying averages of the gradients (first moment) and of the
+/// squared gradients (second moment):
+///
+/// ```text
+/// m_{k+1} = beta1 * m_k + (1 - beta1) * g_k
+/// v_{k+1} = beta2 * v_k + (1 - beta2) * g_k^2
+/// x_{k+1} = x_k - learning_rate * m_hat / (sqrt(v_hat) + epsilon)
+/// ```
+///
+/// with `m_hat = m_{k+1} / (1 - beta1^{k+1})` and `v_hat = v_{k+1} / (1 - beta2^{k+1})`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/stochastic.rs)
+///
+/// # References:
+///
+/// [0] Diederik P. Kingma and Jimmy Ba (2015). Adam: A Method for Stochastic Optimization.
+/// ICLR 2015. arXiv:1412.6980.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct Adam {
+    /// learning rate
+    learning_rate: f64,
+    /// decay rate of the first moment
+    beta1: f64,
+    /// decay rate of the second moment
+    beta2: f64,
+    /// small constant for numerical stability
+    epsilon: f64,
+    /// first moment
+    m: Vec<f64>,
+    /// second moment
+    v: Vec<f64>,
+    /// minibatch sampler
+    sampler: MinibatchSampler,
+}
+
+impl Adam {
+    /// Constructor
+    pub fn new(learning_rate: f64) -> Result<Self, Error> {
+        check_positive("Adam", "learning_rate", learning_rate)?;
+        Ok(Adam {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            m: vec![],
+            v: vec![],
+            sampler: MinibatchSampler::new(1)?,
+        })
+    }
+
+    /// Set decay rates of the first and second moment (default: 0.9 and 0.999)
+    pub fn betas(mut self, beta1: f64, beta2: f64) -> Result<Self, Error> {
+        check_decay("Adam", "beta1", beta1)?;
+        check_decay("Adam", "beta2", beta2)?;
+        self.beta1 = beta1;
+        self.beta2 = beta2;
+        Ok(self)
+    }
+
+    /// Set epsilon (default: 1e-8)
+    pub fn epsilon(mut self, epsilon: f64) -> Result<Self, Error> {
+        check_positive("Adam", "epsilon", epsilon)?;
+        self.epsilon = epsilon;
+        Ok(self)
+    }
+
+    /// Set number of samples per minibatch (default: 1)
+    pub fn batch_size(mut self, batch_size: usize) -> Result<Self, Error> {
+        self.sampler.set_batch_size(batch_size)?;
+        Ok(self)
+    }
+
+    /// Seed the random number generator used for shuffling the samples
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.sampler = self.sampler.seed(seed);
+        self
+    }
+}
+
+impl<O> Solver<O> for Adam
+where
+    O: ArgminMinibatchOp<Output = f64>,
+    O::Param: FromIterator<f64>,
+    for<'a> &'a O::Param: IntoIterator<Item = &'a f64>,
+{
+    const NAME: &'static str = "Adam";
+
+    fn init(
+        &mut self,
+        _op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let n = state.get_param().into_iter().count();
+        self.m = vec![0.0; n];
+        self.v = vec![0.0; n];
+        Ok(None)
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let (batch, grad) = sample_gradient(op, &mut self.sampler, &param)?;
+        for ((m, v), g) in self.m.iter_mut().zip(self.v.iter_mut()).zip(&grad) {
+            *m = self.beta1 * *m + (1.0 - self.beta1) * g;
+            *v = self.beta2 * *v + (1.0 - self.beta2) * g.powi(2);
+        }
+        let t = (state.get_iter() + 1) as i32;
+        let c1 = 1.0 - self.beta1.powi(t);
+        let c2 = 1.0 - self.beta2.powi(t);
+        let m_hat: Vec<f64> = self.m.iter().map(|m| m / c1).collect();
+        let v_hat: Vec<f64> = self.v.iter().map(|v| v / c2).collect();
+        let new_param = adaptive_step(&param, &m_hat, &v_hat, self.learning_rate, self.epsilon);
+        let cost = op.batch_apply(&new_param, &batch)?;
+        Ok(ArgminIterData::new()
+            .param(new_param)
+            .cost(cost)
+            .kv(make_kv!("epoch" => self.sampler.epoch();)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::stochastic::tests::check_solver;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(adam, Adam);
+
+    #[test]
+    fn test_adam() {
+        let solver = Adam::new(0.05).unwrap().batch_size(4).unwrap().seed(1);
+        check_solver(solver, 3000, 1e-3);
+    }
+
+    #[test]
+    fn test_betas() {
+        assert!(Adam::new(0.1).unwrap().betas(1.0, 0.9).is_err());
+        assert!(Adam::new(0.1).unwrap().betas(0.9, 0.99).is_ok());
+    }
+}
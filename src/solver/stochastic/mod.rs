@@ -0,0 +1,215 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Stochastic first order methods
+//!
+//! These solvers work on operators implementing `ArgminMinibatchOp`. In each iteration a
+//! minibatch is drawn by a `MinibatchSampler`, the gradient is evaluated on this minibatch and a
+//! step is taken. The reported cost is the cost of the same minibatch at the new parameter vector,
+//! which is therefore a noisy estimate of the full cost. The number of the current epoch is
+//! available in the key-value store as `epoch`.
+//!
+//! Each evaluation on a minibatch is counted as one cost function or gradient evaluation.
+//!
+//! # References:
+//!
+//! [0] Sebastian Ruder (2016). An overview of gradient descent optimization algorithms.
+//! arXiv:1609.04747.
+
+/// AdaGrad
+pub mod adagrad;
+/// Adam
+pub mod adam;
+/// RMSProp
+pub mod rmsprop;
+/// Stochastic gradient descent
+pub mod sgd;
+
+pub use self::adagrad::*;
+pub use self::adam::*;
+pub use self::rmsprop::*;
+pub use self::sgd::*;
+
+use crate::minibatch::{ArgminMinibatchOpWrapper, MinibatchSampler};
+use crate::prelude::*;
+use std::iter::FromIterator;
+
+/// Draw a minibatch and evaluate the gradient on it
+fn sample_gradient<O>(
+    op: &mut OpWrapper<O>,
+    sampler: &mut MinibatchSampler,
+    param: &O::Param,
+) -> Result<(Vec<usize>, O::Param), Error>
+where
+    O: ArgminMinibatchOp,
+{
+    let batch = sampler.next_batch(op.num_samples())?;
+    let grad = op.batch_gradient(param, &batch)?;
+    Ok((batch, grad))
+}
+
+/// Element-wise step `x_i - learning_rate * m_i / (sqrt(v_i) + epsilon)` used by the adaptive
+/// methods
+fn adaptive_step<P>(param: &P, m: &[f64], v: &[f64], learning_rate: f64, epsilon: f64) -> P
+where
+    P: FromIterator<f64>,
+    for<'a> &'a P: IntoIterator<Item = &'a f64>,
+{
+    param
+        .into_iter()
+        .zip(m.iter().zip(v.iter()))
+        .map(|(x, (mi, vi))| x - learning_rate * mi / (vi.sqrt() + epsilon))
+        .collect()
+}
+
+/// Checks that a parameter is finite and positive
+fn check_positive(name: &str, what: &str, value: f64) -> Result<(), Error> {
+    if value <= 0.0 || !value.is_finite() {
+        return Err(ArgminError::InvalidParameter {
+            text: format!("{}: {} must be finite and > 0.", name, what),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Checks that a decay rate lies in `[0, 1)`
+fn check_decay(name: &str, what: &str, value: f64) -> Result<(), Error> {
+    if !(0.0..1.0).contains(&value) {
+        return Err(ArgminError::InvalidParameter {
+            text: format!("{}: {} must be in [0, 1).", name, what),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serde1")]
+    use serde::{Deserialize, Serialize};
+
+    /// Linear least squares `1/N sum_i 0.5 * (a_i^T x - b_i)^2` with solution `[1, -2]`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    pub(crate) struct LeastSquares {
+        a: Vec<Vec<f64>>,
+        b: Vec<f64>,
+    }
+
+    impl LeastSquares {
+        pub(crate) fn new() -> Self {
+            let a: Vec<Vec<f64>> = (0..20)
+                .map(|i| {
+                    let t = f64::from(i) / 10.0 - 1.0;
+                    vec![1.0, t]
+                })
+                .collect();
+            let b = a.iter().map(|ai| ai[0] - 2.0 * ai[1]).collect();
+            LeastSquares { a, b }
+        }
+
+        fn residual(&self, p: &[f64], i: usize) -> f64 {
+            self.a[i][0] * p[0] + self.a[i][1] * p[1] - self.b[i]
+        }
+    }
+
+    impl ArgminOp for LeastSquares {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+        type Jacobian = ();
+    }
+
+    impl ArgminMinibatchOp for LeastSquares {
+        fn num_samples(&self) -> usize {
+            self.b.len()
+        }
+
+        fn batch_apply(&self, p: &Vec<f64>, indices: &[usize]) -> Result<f64, Error> {
+            let sum: f64 = indices
+                .iter()
+                .map(|&i| 0.5 * self.residual(p, i).powi(2))
+                .sum();
+            Ok(sum / indices.len() as f64)
+        }
+
+        fn batch_gradient(&self, p: &Vec<f64>, indices: &[usize]) -> Result<Vec<f64>, Error> {
+            let mut grad = vec![0.0; p.len()];
+            for &i in indices {
+                let r = self.residual(p, i);
+                grad = grad.scaled_add(&(r / indices.len() as f64), &self.a[i]);
+            }
+            Ok(grad)
+        }
+    }
+
+    /// Runs a solver on `LeastSquares` and checks the result
+    pub(crate) fn check_solver<S: Solver<LeastSquares>>(solver: S, iters: u64, tol: f64) {
+        let res = Executor::new(LeastSquares::new(), solver, vec![0.0, 0.0])
+            .max_iters(iters)
+            .run()
+            .unwrap();
+        let x = res.state.get_param();
+        assert!((x[0] - 1.0).abs() < tol, "{:?}", x);
+        assert!((x[1] + 2.0).abs() < tol, "{:?}", x);
+    }
+
+    /// Parameter vector after `iters` iterations of a solver on `LeastSquares`
+    fn run<S: Solver<LeastSquares>>(solver: S, iters: u64) -> Vec<f64> {
+        Executor::new(LeastSquares::new(), solver, vec![0.0, 0.0])
+            .max_iters(iters)
+            .run()
+            .unwrap()
+            .state
+            .get_param()
+    }
+
+    #[test]
+    fn test_seed_batch_size_order() {
+        // setting the batch size must not discard the seed
+        assert_eq!(
+            run(SGD::new(0.1).unwrap().seed(3).batch_size(4).unwrap(), 20),
+            run(SGD::new(0.1).unwrap().batch_size(4).unwrap().seed(3), 20)
+        );
+        assert_eq!(
+            run(
+                AdaGrad::new(0.5).unwrap().seed(3).batch_size(4).unwrap(),
+                20
+            ),
+            run(
+                AdaGrad::new(0.5).unwrap().batch_size(4).unwrap().seed(3),
+                20
+            )
+        );
+        assert_eq!(
+            run(
+                RMSProp::new(0.01).unwrap().seed(3).batch_size(4).unwrap(),
+                20
+            ),
+            run(
+                RMSProp::new(0.01).unwrap().batch_size(4).unwrap().seed(3),
+                20
+            )
+        );
+        assert_eq!(
+            run(Adam::new(0.05).unwrap().seed(3).batch_size(4).unwrap(), 20),
+            run(Adam::new(0.05).unwrap().batch_size(4).unwrap().seed(3), 20)
+        );
+    }
+
+    #[test]
+    fn test_counts() {
+        let res = Executor::new(LeastSquares::new(), SGD::new(0.1).unwrap(), vec![0.0, 0.0])
+            .max_iters(10)
+            .run()
+            .unwrap();
+        assert_eq!(res.state.get_grad_func_count(), 10);
+        assert_eq!(res.state.get_cost_func_count(), 10);
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Tijmen Tieleman and Geoffrey Hinton (2012). Lecture 6.5 - RMSProp: Divide the gradient by a
+//! running average of its recent magnitude. COURSERA: Neural Networks for Machine Learning.
+
+use super::{adaptive_step, check_decay, check_positive, sample_gradient};
+use crate::minibatch::{ArgminMinibatchOpWrapper, MinibatchSampler};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// RMSProp
+///
+/// Scales the learning rate of each parameter by the inverse square root of an exponentially
+/// decaying average of its squared gradients:
+///
+/// ```text
+/// E_{k+1} = rho * E_k + (1 - rho) * g_k^2
+/// x_{k+1} = x_k - learning_rate * g_k / (sqrt(E_{k+1}) + epsilon)
+/// ```
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/stochastic.rs)
+///
+/// # References:
+///
+/// [0] Tijmen Tieleman and Geoffrey Hinton (2012). Lecture 6.5 - RMSProp: Divide the gradient by a
+/// running average of its recent magnitude. COURSERA: Neural Networks for Machine Learning.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct RMSProp {
+    /// learning rate
+    learning_rate: f64,
+    /// decay rate of the average of squared gradients
+    rho: f64,
+    /// small constant for numerical stability
+    epsilon: f64,
+    /// average of squared gradients
+    mean_sq: Vec<f64>,
+    /// minibatch sampler
+    sampler: MinibatchSampler,
+}
+
+impl RMSProp {
+    /// Constructor
+    pub fn new(learning_rate: f64) -> Result<Self, Error> {
+        check_positive("RMSProp", "learning_rate", learning_rate)?;
+        Ok(RMSProp {
+            learning_rate,
+            rho: 0.9,
+            epsilon: 1e-8,
+            mean_sq: vec![],
+            sampler: MinibatchSampler::new(1)?,
+        })
+    }
+
+    /// Set decay rate of the average of squared gradients (default: 0.9)
+    pub fn rho(mut self, rho: f64) -> Result<Self, Error> {
+        check_decay("RMSProp", "rho", rho)?;
+        self.rho = rho;
+        Ok(self)
+    }
+
+    /// Set epsilon (default: 1e-8)
+    pub fn epsilon(mut self, epsilon: f64) -> Result<Self, Error> {
+        check_positive("RMSProp", "epsilon", epsilon)?;
+        self.epsilon = epsilon;
+        Ok(self)
+    }
+
+    /// Set number of samples per minibatch (default: 1)
+    pub fn batch_size(mut self, batch_size: usize) -> Result<Self, Error> {
+        self.sampler.set_batch_size(batch_size)?;
+        Ok(self)
+    }
+
+    /// Seed the random number generator used for shuffling the samples
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.sampler = self.sampler.seed(seed);
+        self
+    }
+}
+
+impl<O> Solver<O> for RMSProp
+where
+    O: ArgminMinibatchOp<Output = f64>,
+    O::Param: FromIterator<f64>,
+    for<'a> &'a O::Param: IntoIterator<Item = &'a f64>,
+{
+    const NAME: &'static str = "RMSProp";
+
+    fn init(
+        &mut self,
+        _op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        self.mean_sq = vec![0.0; state.get_param().into_iter().count()];
+        Ok(None)
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let (batch, grad) = sample_gradient(op, &mut self.sampler, &param)?;
+        let grad: Vec<f64> = grad.into_iter().cloned().collect();
+        for (e, g) in self.mean_sq.iter_mut().zip(grad.iter()) {
+            *e = self.rho * *e + (1.0 - self.rho) * g.powi(2);
+        }
+        let new_param = adaptive_step(
+            &param,
+            &grad,
+            &self.mean_sq,
+            self.learning_rate,
+            self.epsilon,
+        );
+        let cost = op.batch_apply(&new_param, &batch)?;
+        Ok(ArgminIterData::new()
+            .param(new_param)
+            .cost(cost)
+            .kv(make_kv!("epoch" => self.sampler.epoch();)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::stochastic::tests::check_solver;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(rmsprop, RMSProp);
+
+    #[test]
+    fn test_rmsprop() {
+        let solver = RMSProp::new(0.01).unwrap().batch_size(4).unwrap().seed(1);
+        check_solver(solver, 3000, 1e-2);
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Ilya Sutskever, James Martens, George Dahl and Geoffrey Hinton (2013). On the importance
+//! of initialization and momentum in deep learning. ICML 2013.
+
+use super::{check_decay, check_positive, sample_gradient};
+use crate::minibatch::{ArgminMinibatchOpWrapper, MinibatchSampler};
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Stochastic gradient descent with optional (Nesterov) momentum
+///
+/// With momentum `beta` the update reads
+///
+/// ```text
+/// v_{k+1} = beta * v_k + g_k
+/// x_{k+1} = x_k - learning_rate * v_{k+1}
+/// ```
+///
+/// where `g_k` is the gradient on the current minibatch. With Nesterov momentum the step is
+/// `learning_rate * (g_k + beta * v_{k+1})` instead. Without momentum (`beta = 0`, the default)
+/// this is plain stochastic gradient descent.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/stochastic.rs)
+///
+/// # References:
+///
+/// [0] Ilya Sutskever, James Martens, George Dahl and Geoffrey Hinton (2013). On the importance
+/// of initialization and momentum in deep learning. ICML 2013.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct SGD<P> {
+    /// learning rate
+    learning_rate: f64,
+    /// momentum
+    momentum: f64,
+    /// use Nesterov momentum
+    nesterov: bool,
+    /// velocity
+    velocity: Option<P>,
+    /// minibatch sampler
+    sampler: MinibatchSampler,
+}
+
+impl<P> SGD<P> {
+    /// Constructor
+    pub fn new(learning_rate: f64) -> Result<Self, Error> {
+        check_positive("SGD", "learning_rate", learning_rate)?;
+        Ok(SGD {
+            learning_rate,
+            momentum: 0.0,
+            nesterov: false,
+            velocity: None,
+            sampler: MinibatchSampler::new(1)?,
+        })
+    }
+
+    /// Set momentum (default: 0.0)
+    pub fn momentum(mut self, momentum: f64) -> Result<Self, Error> {
+        check_decay("SGD", "momentum", momentum)?;
+        self.momentum = momentum;
+        Ok(self)
+    }
+
+    /// Use Nesterov momentum (default: false)
+    pub fn nesterov(mut self, nesterov: bool) -> Self {
+        self.nesterov = nesterov;
+        self
+    }
+
+    /// Set number of samples per minibatch (default: 1)
+    pub fn batch_size(mut self, batch_size: usize) -> Result<Self, Error> {
+        self.sampler.set_batch_size(batch_size)?;
+        Ok(self)
+    }
+
+    /// Seed the random number generator used for shuffling the samples
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.sampler = self.sampler.seed(seed);
+        self
+    }
+}
+
+impl<O, P> Solver<O> for SGD<P>
+where
+    O: ArgminMinibatchOp<Param = P, Output = f64>,
+    P: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminScaledAdd<P, f64, P>
+        + ArgminScaledSub<P, f64, P>,
+{
+    const NAME: &'static str = "Stochastic gradient descent";
+
+    fn init(
+        &mut self,
+        _op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        self.velocity = None;
+        Ok(None)
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let (batch, grad) = sample_gradient(op, &mut self.sampler, &param)?;
+
+        let direction = if self.momentum > 0.0 {
+            let velocity = match self.velocity.take() {
+                Some(v) => grad.scaled_add(&self.momentum, &v),
+                None => grad.clone(),
+            };
+            let direction = if self.nesterov {
+                grad.scaled_add(&self.momentum, &velocity)
+            } else {
+                velocity.clone()
+            };
+            self.velocity = Some(velocity);
+            direction
+        } else {
+            grad
+        };
+
+        let new_param = param.scaled_sub(&self.learning_rate, &direction);
+        let cost = op.batch_apply(&new_param, &batch)?;
+        Ok(ArgminIterData::new()
+            .param(new_param)
+            .cost(cost)
+            .kv(make_kv!("epoch" => self.sampler.epoch();)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::stochastic::tests::check_solver;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(sgd, SGD<Vec<f64>>);
+
+    #[test]
+    fn test_sgd() {
+        let solver = SGD::new(0.1).unwrap().batch_size(4).unwrap().seed(1);
+        check_solver(solver, 3000, 1e-4);
+    }
+
+    #[test]
+    fn test_sgd_momentum() {
+        let solver = SGD::new(0.05)
+            .unwrap()
+            .momentum(0.9)
+            .unwrap()
+            .batch_size(4)
+            .unwrap()
+            .seed(1);
+        check_solver(solver.clone(), 1000, 1e-4);
+        check_solver(solver.nesterov(true), 1000, 1e-4);
+    }
+}
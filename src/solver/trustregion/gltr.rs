@@ -0,0 +1,414 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Nicholas I. M. Gould, Stefano Lucidi, Massimo Roma and Philippe L. Toint (1999).
+//! Solving the Trust-Region Subproblem using the Lanczos Method.
+//! SIAM Journal on Optimization 9(2), 504-525.
+//!
+//! [1] Andrew R. Conn, Nicholas I. M. Gould and Philippe L. Toint (2000). Trust-Region Methods.
+//! SIAM. ISBN 0-89871-460-5.
+
+use crate::hessian_vec_product::{DenseHessian, HessianVecProducts, MatrixFree};
+use crate::prelude::*;
+use crate::solver::trustregion::MoreSorensen;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// The Generalized Lanczos Trust Region (GLTR) method finds an approximate solution of the trust
+/// region subproblem by minimizing the quadratic model over a growing Krylov subspace spanned by
+/// the gradient and products with the Hessian.
+///
+/// The Lanczos process builds an orthonormal basis `Q_k` of the Krylov subspace, in which the
+/// Hessian becomes the tridiagonal matrix `T_k`. In every iteration the subproblem
+///
+/// ```text
+/// min ||g|| e_1^T h + 1/2 h^T T_k h  subject to  ||h|| <= radius
+/// ```
+///
+/// is solved (nearly) exactly with the Moré-Sorensen method and the step is `p = Q_k h`. In
+/// contrast to `Steihaug`, which stops as soon as it meets the trust region boundary or negative
+/// curvature, GLTR continues to improve the step along the boundary. While the iterates are in the
+/// interior of the trust region, they coincide with the ones of the conjugate gradient method.
+///
+/// Only products of the Hessian with vectors are required. By default, the Hessian of the state is
+/// used for these products (it is evaluated at the initial parameter vector of the solver if the
+/// state does not provide one). In matrix-free mode (`matrix_free()`), the Hessian is never used
+/// and the products are computed via `ArgminHessianVecProduct::hessian_vec_product` at the initial
+/// parameter vector of the solver, or approximated by finite differences of the gradient if it is
+/// not implemented (see `argmin::hessian_vec_product`). The Lanczos vectors are stored (and
+/// reorthogonalized) in order to assemble the step, therefore the memory requirements grow with
+/// the number of iterations.
+///
+/// The method terminates when the gradient of the model at the step is smaller than `epsilon`
+/// times the norm of the initial gradient. The multiplier `lambda` of the trust region constraint
+/// is reported in the key-value store. The reported cost is the value of the quadratic model
+/// `g^T p + 1/2 p^T H p` at the current step `p`.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/trustregion_nd.rs)
+///
+/// # References:
+///
+/// [0] Nicholas I. M. Gould, Stefano Lucidi, Massimo Roma and Philippe L. Toint (1999).
+/// Solving the Trust-Region Subproblem using the Lanczos Method.
+/// SIAM Journal on Optimization 9(2), 504-525.
+///
+/// [1] Andrew R. Conn, Nicholas I. M. Gould and Philippe L. Toint (2000). Trust-Region Methods.
+/// SIAM. ISBN 0-89871-460-5.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct GLTR<P, H = DenseHessian> {
+    /// Radius
+    radius: f64,
+    /// epsilon
+    epsilon: f64,
+    /// max iters
+    max_iters: u64,
+    /// point at which the Hessian is evaluated
+    x: P,
+    /// norm of the initial gradient
+    g_norm: f64,
+    /// Lanczos vectors
+    q: Vec<P>,
+    /// diagonal of the tridiagonal matrix
+    diag: Vec<f64>,
+    /// off-diagonal of the tridiagonal matrix
+    offdiag: Vec<f64>,
+    /// source of the Hessian-vector products
+    hessian_vec_products: std::marker::PhantomData<H>,
+}
+
+impl<P> GLTR<P, DenseHessian>
+where
+    P: Default + Clone + ArgminMul<f64, P> + ArgminDot<P, f64> + ArgminAdd<P, P> + ArgminSub<P, P>,
+{
+    /// Constructor
+    pub fn new() -> Self {
+        GLTR {
+            radius: std::f64::NAN,
+            epsilon: 10e-10,
+            max_iters: std::u64::MAX,
+            x: P::default(),
+            g_norm: std::f64::NAN,
+            q: vec![],
+            diag: vec![],
+            offdiag: vec![],
+            hessian_vec_products: std::marker::PhantomData,
+        }
+    }
+
+    /// Only use Hessian-vector products instead of the Hessian (see `argmin::hessian_vec_product`)
+    pub fn matrix_free(self) -> GLTR<P, MatrixFree> {
+        GLTR {
+            radius: self.radius,
+            epsilon: self.epsilon,
+            max_iters: self.max_iters,
+            x: self.x,
+            g_norm: self.g_norm,
+            q: self.q,
+            diag: self.diag,
+            offdiag: self.offdiag,
+            hessian_vec_products: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<P, H> GLTR<P, H>
+where
+    P: Default + Clone + ArgminMul<f64, P> + ArgminDot<P, f64> + ArgminAdd<P, P> + ArgminSub<P, P>,
+{
+    /// Set epsilon
+    pub fn epsilon(mut self, epsilon: f64) -> Result<Self, Error> {
+        if epsilon <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "GLTR: epsilon must be > 0.0.".to_string(),
+            }
+            .into());
+        }
+        self.epsilon = epsilon;
+        Ok(self)
+    }
+
+    /// set maximum number of iterations
+    pub fn max_iters(mut self, iters: u64) -> Self {
+        self.max_iters = iters;
+        self
+    }
+
+    /// Solves the subproblem in the current Krylov subspace. Returns the coordinates of the step
+    /// with respect to the Lanczos vectors, the value of the model and the multiplier.
+    fn solve_tridiagonal(&self) -> Result<(Vec<f64>, f64, f64), Error> {
+        let k = self.diag.len();
+        let t: Vec<Vec<f64>> = (0..k)
+            .map(|i| {
+                (0..k)
+                    .map(|j| {
+                        if i == j {
+                            self.diag[i]
+                        } else if i + 1 == j {
+                            self.offdiag[i]
+                        } else if j + 1 == i {
+                            self.offdiag[j]
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut g = vec![0.0; k];
+        g[0] = self.g_norm;
+
+        let mut subproblem = MoreSorensen::new();
+        subproblem.set_radius(self.radius);
+        let (h, lambda, _) = subproblem.solve(&g, &t)?;
+
+        let hth: f64 = t
+            .iter()
+            .zip(h.iter())
+            .map(|(row, hi)| hi * row.iter().zip(h.iter()).map(|(a, b)| a * b).sum::<f64>())
+            .sum();
+        let m = self.g_norm * h[0] + 0.5 * hth;
+        Ok((h, m, lambda))
+    }
+
+    /// Assembles `Q_k h`
+    fn step(&self, h: &[f64]) -> P {
+        self.q
+            .iter()
+            .zip(h.iter())
+            .skip(1)
+            .fold(self.q[0].mul(&h[0]), |p, (q, hi)| p.add(&q.mul(hi)))
+    }
+}
+
+impl<P, O, H> Solver<O> for GLTR<P, H>
+where
+    O: ArgminOp<Param = P, Output = f64>,
+    P: Clone
+        + Default
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminMul<f64, P>
+        + ArgminNorm<f64>
+        + ArgminDot<P, f64>
+        + ArgminAdd<P, P>
+        + ArgminSub<P, P>
+        + ArgminZeroLike,
+    H: HessianVecProducts<O> + Clone + SerializeAlias,
+{
+    const NAME: &'static str = "GLTR";
+
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let grad = match state.get_grad() {
+            Some(grad) => grad,
+            None => op.gradient(&state.get_param())?,
+        };
+
+        self.x = state.get_param();
+        self.g_norm = grad.norm();
+        self.diag = vec![];
+        self.offdiag = vec![];
+
+        Ok(if self.g_norm < self.epsilon {
+            Some(
+                ArgminIterData::new()
+                    .param(grad.zero_like())
+                    .cost(0.0)
+                    .termination_reason(TerminationReason::TargetPrecisionReached),
+            )
+        } else {
+            self.q = vec![grad.mul(&(1.0 / self.g_norm))];
+            None
+        })
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        // Lanczos step with full reorthogonalization
+        let k = self.diag.len();
+        let h = H::hessian(op, &self.x, state.get_hessian())?;
+        let mut w: P = H::product(op, &self.x, h.as_ref(), &self.q[k])?;
+        self.diag.push(self.q[k].dot(&w));
+        for q in self.q.iter() {
+            w = w.sub(&q.mul(&q.dot(&w)));
+        }
+        let gamma = w.norm();
+
+        let (coords, m, lambda) = self.solve_tridiagonal()?;
+        let p = self.step(&coords);
+
+        // norm of the gradient of the model at p and size of the tridiagonal matrix
+        let residual = gamma * coords[k].abs();
+        let t_norm = self
+            .diag
+            .iter()
+            .chain(self.offdiag.iter())
+            .fold(0.0, |acc: f64, x| acc.max(x.abs()));
+
+        let data = ArgminIterData::new()
+            .param(p)
+            .cost(m)
+            .kv(make_kv!("lambda" => lambda; "residual" => residual;));
+        let data = match h {
+            Some(h) => data.hessian(h),
+            None => data,
+        };
+
+        if residual <= self.epsilon * self.g_norm || gamma <= std::f64::EPSILON * t_norm {
+            return Ok(data.termination_reason(TerminationReason::TargetPrecisionReached));
+        }
+
+        self.offdiag.push(gamma);
+        self.q.push(w.mul(&(1.0 / gamma)));
+
+        Ok(data)
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        if state.get_iter() >= self.max_iters {
+            TerminationReason::MaxItersReached
+        } else {
+            TerminationReason::NotTerminated
+        }
+    }
+}
+
+impl<P: Clone + SerializeAlias, H: Clone + SerializeAlias> ArgminTrustRegion for GLTR<P, H> {
+    fn set_radius(&mut self, radius: f64) {
+        self.radius = radius;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_trait_impl;
+
+    test_trait_impl!(gltr, GLTR<MinimalNoOperator>);
+
+    /// Quadratic model `g^T p + 1/2 p^T H p`
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Model {
+        g: Vec<f64>,
+        h: Vec<Vec<f64>>,
+    }
+
+    impl ArgminOp for Model {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            let gp: f64 = self.g.dot(p);
+            let php: f64 = p.weighted_dot(&self.h, p);
+            Ok(gp + 0.5 * php)
+        }
+    }
+
+    impl ArgminHessianVecProduct for Model {
+        fn hessian_vec_product(&self, _p: &Vec<f64>, v: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(self.h.dot(v))
+        }
+    }
+
+    fn run(model: &Model, radius: f64) -> (Vec<f64>, u64) {
+        let mut solver = GLTR::new();
+        solver.set_radius(radius);
+        let res = Executor::new(model.clone(), solver, vec![0.0; model.g.len()])
+            .grad(model.g.clone())
+            .hessian(model.h.clone())
+            .max_iters(20)
+            .run()
+            .unwrap();
+        (res.state.get_param(), res.state.get_iter())
+    }
+
+    #[test]
+    fn test_epsilon() {
+        assert!(GLTR::<Vec<f64>>::new().epsilon(0.0).is_err());
+        assert!(GLTR::<Vec<f64>>::new().epsilon(1e-6).is_ok());
+    }
+
+    #[test]
+    fn test_interior() {
+        let model = Model {
+            g: vec![1.0, 1.0, 1.0],
+            h: vec![
+                vec![4.0, 1.0, 0.0],
+                vec![1.0, 3.0, 1.0],
+                vec![0.0, 1.0, 2.0],
+            ],
+        };
+        let (p, iters) = run(&model, 10.0);
+        // Newton step: H p = -g
+        let hp: Vec<f64> = model.h.dot(&p);
+        for (a, b) in hp.iter().zip(model.g.iter()) {
+            assert!((a + b).abs() < 1e-8);
+        }
+        assert!(iters <= 3);
+    }
+
+    #[test]
+    fn test_indefinite() {
+        // Steihaug stops at the boundary in the first iteration, GLTR continues along the boundary
+        // and agrees with the (nearly) exact solution
+        let model = Model {
+            g: vec![1.0, 1.0, 1.0],
+            h: vec![
+                vec![-2.0, 0.5, 0.0],
+                vec![0.5, 1.0, 0.3],
+                vec![0.0, 0.3, 3.0],
+            ],
+        };
+        let (p, _) = run(&model, 1.0);
+        let mut exact = MoreSorensen::new();
+        exact.set_radius(1.0);
+        let (p_exact, _, _) = exact.solve(&model.g, &model.h).unwrap();
+        assert!((p.norm() - 1.0).abs() < 1e-5);
+        for (a, b) in p.iter().zip(p_exact.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_matrix_free() {
+        // in matrix-free mode, the products are computed by the operator
+        let model = Model {
+            g: vec![1.0, 1.0, 1.0],
+            h: vec![
+                vec![-2.0, 0.5, 0.0],
+                vec![0.5, 1.0, 0.3],
+                vec![0.0, 0.3, 3.0],
+            ],
+        };
+        let mut solver = GLTR::new().matrix_free();
+        solver.set_radius(1.0);
+        let res = Executor::new(model.clone(), solver, vec![0.0; 3])
+            .grad(model.g.clone())
+            .max_iters(20)
+            .run()
+            .unwrap();
+        let p = res.state.get_param();
+        let (p_dense, _) = run(&model, 1.0);
+        for (a, b) in p.iter().zip(p_dense.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        let m = model.apply(&p).unwrap();
+        assert!((res.state.get_cost() - m).abs() < 1e-12);
+    }
+}
@@ -11,6 +11,10 @@
 pub mod cauchypoint;
 /// Dogleg method
 pub mod dogleg;
+/// Generalized Lanczos trust region method
+pub mod gltr;
+/// Moré-Sorensen method
+pub mod moresorensen;
 /// Steihaug method
 pub mod steihaug;
 /// Trust region solver
@@ -18,9 +22,34 @@ pub mod trustregion_method;
 
 pub use self::cauchypoint::*;
 pub use self::dogleg::*;
+pub use self::gltr::*;
+pub use self::moresorensen::*;
 pub use self::steihaug::*;
 pub use self::trustregion_method::*;
 
+use crate::hessian_vec_product::{DenseHessian, MatrixFree};
+use argmin_core::{ArgminTrustRegion, SerializeAlias};
+
+/// Trust region subproblem solvers usable by `TrustRegion` in mode `H`
+///
+/// Every subproblem solver can be used with the dense Hessian (`H = DenseHessian`). In
+/// matrix-free mode (`H = MatrixFree`), `TrustRegion` requires a subproblem solver which only
+/// needs Hessian-vector products and which reports the value of the quadratic model
+/// `g^T p + 1/2 p^T H p` at its solution `p` as cost, i.e. `Steihaug` or `GLTR` in matrix-free
+/// mode.
+pub trait ArgminTrustRegionSubproblem<H = DenseHessian>: ArgminTrustRegion {}
+
+impl<R: ArgminTrustRegion> ArgminTrustRegionSubproblem<DenseHessian> for R {}
+
+impl<P, M> ArgminTrustRegionSubproblem<MatrixFree> for Steihaug<P, M, MatrixFree>
+where
+    P: Clone + SerializeAlias,
+    M: Clone + SerializeAlias,
+{
+}
+
+impl<P: Clone + SerializeAlias> ArgminTrustRegionSubproblem<MatrixFree> for GLTR<P, MatrixFree> {}
+
 /// Computes reduction ratio
 pub fn reduction_ratio(fxk: f64, fxkpk: f64, mk0: f64, mkpk: f64) -> f64 {
     (fxk - fxkpk) / (mk0 - mkpk)
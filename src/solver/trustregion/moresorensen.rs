@@ -0,0 +1,407 @@
+// Copyright 2018-2020 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Jorge J. Moré and D. C. Sorensen (1983). Computing a Trust Region Step.
+//! SIAM Journal on Scientific and Statistical Computing 4(3), 553-572.
+//!
+//! [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::linalg;
+use crate::prelude::*;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// The Moré-Sorensen method computes a nearly exact solution of the trust region subproblem
+///
+/// ```text
+/// min g^T p + 1/2 p^T H p  subject to  ||p|| <= radius
+/// ```
+///
+/// by finding `lambda >= max(0, -lambda_min(H))` such that `(H + lambda I) p = -g` and
+/// `||p|| = radius` (unless the Newton step is inside the trust region). The secular equation
+/// `1/||p(lambda)|| = 1/radius` is solved with safeguarded Newton iterations, each requiring a
+/// Cholesky factorization of `H + lambda I`. In contrast to `Dogleg`, the Hessian may be
+/// indefinite. The smallest eigenvalue of `H` is computed once; in the hard case (the gradient is
+/// orthogonal to the corresponding eigenspace) the step is completed along the eigenvector to the
+/// trust region boundary.
+///
+/// The Hessian is assembled from `n` products with unit vectors and all computations are dense,
+/// therefore this method is suited for problems with a moderate number of parameters.
+///
+/// The multiplier `lambda` and whether the hard case occurred are reported in the key-value
+/// store.
+///
+/// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/trustregion_nd.rs)
+///
+/// # References:
+///
+/// [0] Jorge J. Moré and D. C. Sorensen (1983). Computing a Trust Region Step.
+/// SIAM Journal on Scientific and Statistical Computing 4(3), 553-572.
+///
+/// [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, PartialEq, PartialOrd)]
+pub struct MoreSorensen {
+    /// Radius
+    radius: f64,
+    /// relative tolerance of the norm of the step
+    tol: f64,
+    /// maximum number of Newton iterations
+    max_iters: u64,
+}
+
+impl Default for MoreSorensen {
+    fn default() -> Self {
+        MoreSorensen::new()
+    }
+}
+
+impl MoreSorensen {
+    /// Constructor
+    pub fn new() -> Self {
+        MoreSorensen {
+            radius: std::f64::NAN,
+            tol: 1e-6,
+            max_iters: 50,
+        }
+    }
+
+    /// Set relative tolerance `| ||p|| - radius | <= tol * radius` (default: 1e-6)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol <= 0.0 || tol >= 1.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "MoreSorensen: tol must be in (0, 1).".to_string(),
+            }
+            .into());
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Set maximum number of Newton iterations for the secular equation (default: 50)
+    pub fn max_iters(mut self, iters: u64) -> Self {
+        self.max_iters = iters;
+        self
+    }
+
+    /// Solves the subproblem for gradient `g` and (symmetric) Hessian `h`. Returns the step, the
+    /// multiplier `lambda` and whether the hard case occurred.
+    pub(crate) fn solve(&self, g: &[f64], h: &[Vec<f64>]) -> Result<(Vec<f64>, f64, bool), Error> {
+        let n = g.len();
+        let radius = self.radius;
+
+        // Newton step if it lies within the trust region
+        if let Some(l) = linalg::cholesky(h) {
+            let p = newton_step(&l, g);
+            if norm(&p) <= radius {
+                return Ok((p, 0.0, false));
+            }
+        }
+
+        let (eig, vecs) = linalg::symmetric_eigen(h);
+        let (k_min, lambda_min) =
+            eig.iter()
+                .cloned()
+                .enumerate()
+                .fold((0, std::f64::INFINITY), |acc, (k, e)| {
+                    if e < acc.1 {
+                        (k, e)
+                    } else {
+                        acc
+                    }
+                });
+        let v_min: Vec<f64> = vecs.iter().map(|row| row[k_min]).collect();
+        let h_norm = eig.iter().fold(0.0, |acc: f64, e| acc.max(e.abs()));
+        let g_norm = norm(g);
+        let eps = std::f64::EPSILON.sqrt() * h_norm.max(1.0);
+        let lower = (-lambda_min).max(0.0);
+
+        // hard case: g is (numerically) orthogonal to the eigenspace of lambda_min and the
+        // minimum norm solution for lambda = -lambda_min lies within the trust region
+        if lambda_min <= 0.0 {
+            let coeffs: Vec<f64> = (0..n)
+                .map(|j| (0..n).map(|i| vecs[i][j] * g[i]).sum())
+                .collect();
+            let g_min: f64 = (0..n)
+                .filter(|&j| eig[j] - lambda_min <= eps)
+                .map(|j| coeffs[j].powi(2))
+                .sum::<f64>()
+                .sqrt();
+            if g_min <= eps * g_norm.max(std::f64::MIN_POSITIVE) {
+                let mut p = vec![0.0; n];
+                for j in (0..n).filter(|&j| eig[j] - lambda_min > eps) {
+                    let c = -coeffs[j] / (eig[j] - lambda_min);
+                    for (pi, row) in p.iter_mut().zip(vecs.iter()) {
+                        *pi += c * row[j];
+                    }
+                }
+                if norm(&p) <= radius {
+                    return Ok((to_boundary(&p, &v_min, radius), lower, true));
+                }
+            }
+        }
+
+        // safeguarded Newton iterations on 1/||p(lambda)|| = 1/radius
+        let mut lo = lower;
+        let mut hi = g_norm / radius + h_norm;
+        let mut lambda = if lambda_min > 0.0 {
+            0.0
+        } else {
+            lo + eps.min(0.5 * (hi - lo))
+        };
+        let mut p = vec![0.0; n];
+        for _ in 0..self.max_iters {
+            let shifted: Vec<Vec<f64>> = h
+                .iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    let mut row = row.clone();
+                    row[i] += lambda;
+                    row
+                })
+                .collect();
+            let l = match linalg::cholesky(&shifted) {
+                Some(l) => l,
+                None => {
+                    lo = lambda;
+                    lambda = 0.5 * (lo + hi);
+                    continue;
+                }
+            };
+            p = newton_step(&l, g);
+            let p_norm = norm(&p);
+            if (p_norm - radius).abs() <= self.tol * radius {
+                return Ok((p, lambda, false));
+            }
+            if p_norm > radius {
+                lo = lambda;
+            } else {
+                hi = lambda;
+            }
+            let q = linalg::forward_substitution(&l, &p);
+            let next = lambda + (p_norm / norm(&q)).powi(2) * (p_norm - radius) / radius;
+            lambda = if next > lo && next < hi {
+                next
+            } else {
+                0.5 * (lo + hi)
+            };
+        }
+
+        // no convergence (nearly hard case): move to the boundary along the eigenvector
+        if norm(&p) > radius {
+            let scale = radius / norm(&p);
+            p.iter_mut().for_each(|pi| *pi *= scale);
+            Ok((p, lambda, false))
+        } else {
+            Ok((to_boundary(&p, &v_min, radius), lambda, true))
+        }
+    }
+}
+
+fn norm(x: &[f64]) -> f64 {
+    x.iter().map(|v| v.powi(2)).sum::<f64>().sqrt()
+}
+
+/// Solves `l l^T p = -g`
+fn newton_step(l: &[Vec<f64>], g: &[f64]) -> Vec<f64> {
+    let y = linalg::forward_substitution(l, g);
+    linalg::backward_substitution(l, &y)
+        .iter()
+        .map(|v| -v)
+        .collect()
+}
+
+/// `p + tau v` with `tau >= 0` such that `||p + tau v|| = radius` (requires `||p|| <= radius` and
+/// `||v|| = 1`)
+fn to_boundary(p: &[f64], v: &[f64], radius: f64) -> Vec<f64> {
+    let pv: f64 = p.iter().zip(v.iter()).map(|(a, b)| a * b).sum();
+    let pp: f64 = p.iter().map(|a| a.powi(2)).sum();
+    let tau = -pv + (pv.powi(2) + radius.powi(2) - pp).max(0.0).sqrt();
+    p.iter().zip(v.iter()).map(|(a, b)| a + tau * b).collect()
+}
+
+impl<O> Solver<O> for MoreSorensen
+where
+    O: ArgminOp<Output = f64>,
+    O::Param: FromIterator<f64>,
+    for<'a> &'a O::Param: IntoIterator<Item = &'a f64>,
+    O::Hessian: ArgminDot<O::Param, O::Param>,
+{
+    const NAME: &'static str = "Moré-Sorensen";
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let grad = match state.get_grad() {
+            Some(grad) => grad,
+            None => op.gradient(&param)?,
+        };
+        let hessian = match state.get_hessian() {
+            Some(hessian) => hessian,
+            None => op.hessian(&param)?,
+        };
+
+        let g: Vec<f64> = grad.into_iter().cloned().collect();
+        let n = g.len();
+        // columns of the Hessian, symmetrized
+        let cols: Vec<Vec<f64>> = (0..n)
+            .map(|j| {
+                let e: O::Param = (0..n).map(|i| if i == j { 1.0 } else { 0.0 }).collect();
+                hessian.dot(&e).into_iter().cloned().collect()
+            })
+            .collect();
+        let h: Vec<Vec<f64>> = (0..n)
+            .map(|i| (0..n).map(|j| 0.5 * (cols[j][i] + cols[i][j])).collect())
+            .collect();
+
+        let (p, lambda, hard_case) = self.solve(&g, &h)?;
+        Ok(ArgminIterData::new()
+            .param(p.into_iter().collect())
+            .kv(make_kv!("lambda" => lambda; "hard_case" => hard_case;)))
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        if state.get_iter() >= 1 {
+            TerminationReason::MaxItersReached
+        } else {
+            TerminationReason::NotTerminated
+        }
+    }
+}
+
+impl ArgminTrustRegion for MoreSorensen {
+    fn set_radius(&mut self, radius: f64) {
+        self.radius = radius;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::trustregion::TrustRegion;
+    use crate::test_trait_impl;
+    use crate::testfunctions::{rosenbrock_2d, rosenbrock_2d_derivative, rosenbrock_2d_hessian};
+    #[cfg(feature = "serde1")]
+    use serde::{Deserialize, Serialize};
+
+    test_trait_impl!(moresorensen, MoreSorensen);
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Rosenbrock {}
+
+    impl ArgminOp for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(rosenbrock_2d(p, 1.0, 100.0))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(rosenbrock_2d_derivative(p, 1.0, 100.0))
+        }
+
+        fn hessian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            let h = rosenbrock_2d_hessian(p, 1.0, 100.0);
+            Ok(vec![vec![h[0], h[1]], vec![h[2], h[3]]])
+        }
+    }
+
+    fn solve(h: Vec<Vec<f64>>, g: Vec<f64>, radius: f64) -> (Vec<f64>, f64, bool) {
+        let mut solver = MoreSorensen::new();
+        solver.set_radius(radius);
+        solver.solve(&g, &h).unwrap()
+    }
+
+    /// Checks `(H + lambda I) p = -g`
+    fn check_optimality(h: &[Vec<f64>], g: &[f64], p: &[f64], lambda: f64) {
+        for (i, row) in h.iter().enumerate() {
+            let hp: f64 = row.iter().zip(p.iter()).map(|(a, b)| a * b).sum();
+            assert!((hp + lambda * p[i] + g[i]).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_interior() {
+        let (p, lambda, _) = solve(vec![vec![2.0, 0.0], vec![0.0, 4.0]], vec![1.0, 1.0], 10.0);
+        assert!((p[0] + 0.5).abs() < 1e-12);
+        assert!((p[1] + 0.25).abs() < 1e-12);
+        assert_eq!(lambda, 0.0);
+    }
+
+    #[test]
+    fn test_boundary() {
+        let h = vec![vec![2.0, 0.5], vec![0.5, 4.0]];
+        let g = vec![1.0, 1.0];
+        let (p, lambda, hard_case) = solve(h.clone(), g.clone(), 0.1);
+        assert!((norm(&p) - 0.1).abs() < 1e-6);
+        assert!(lambda > 0.0);
+        assert!(!hard_case);
+        check_optimality(&h, &g, &p, lambda);
+    }
+
+    #[test]
+    fn test_indefinite() {
+        let h = vec![vec![-1.0, 0.3], vec![0.3, 2.0]];
+        let g = vec![1.0, 1.0];
+        let (p, lambda, hard_case) = solve(h.clone(), g.clone(), 1.0);
+        assert!((norm(&p) - 1.0).abs() < 1e-6);
+        assert!(lambda > 1.0);
+        assert!(!hard_case);
+        check_optimality(&h, &g, &p, lambda);
+    }
+
+    #[test]
+    fn test_hard_case() {
+        let h = vec![vec![-1.0, 0.0], vec![0.0, 2.0]];
+        let g = vec![0.0, 1.0];
+        let (p, lambda, hard_case) = solve(h.clone(), g.clone(), 1.0);
+        assert!(hard_case);
+        assert!((lambda - 1.0).abs() < 1e-12);
+        assert!((norm(&p) - 1.0).abs() < 1e-12);
+        assert!((p[1] + 1.0 / 3.0).abs() < 1e-12);
+        check_optimality(&h, &g, &p, lambda);
+    }
+
+    #[test]
+    fn test_tol() {
+        assert!(MoreSorensen::new().tol(0.0).is_err());
+        assert!(MoreSorensen::new().tol(1e-4).is_ok());
+    }
+
+    #[test]
+    fn test_rosenbrock_indefinite() {
+        // the Hessian at the initial parameter vector has a negative eigenvalue
+        let init_param = vec![0.0, 1.0];
+        let h = Rosenbrock {}.hessian(&init_param).unwrap();
+        assert!(h[0][0] * h[1][1] - h[0][1] * h[1][0] < 0.0);
+
+        let res = Executor::new(
+            Rosenbrock {},
+            TrustRegion::new(MoreSorensen::new()),
+            init_param,
+        )
+        .max_iters(100)
+        .run()
+        .unwrap();
+        let x = res.state.get_best_param();
+        assert!((x[0] - 1.0).abs() < 1e-6);
+        assert!((x[1] - 1.0).abs() < 1e-6);
+        assert!(res.state.get_best_cost() < 1e-12);
+    }
+}
@@ -10,6 +10,8 @@
 //! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
 //! Springer. ISBN 0-387-30303-0.
 
+use crate::hessian_vec_product::{DenseHessian, HessianVecProducts, MatrixFree};
+use crate::preconditioner::IdentityPreconditioner;
 use crate::prelude::*;
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
@@ -17,46 +19,130 @@ use serde::{Deserialize, Serialize};
 /// The Steihaug method is a conjugate gradients based approach for finding an approximate solution
 /// to the second order approximation of the cost function within the trust region.
 ///
+/// A preconditioner `M` (see `argmin::preconditioner`) can be provided via `preconditioner(...)`.
+/// In this case the trust region is measured in the norm `||p||_M = sqrt(p^T M p)`, which is
+/// computed via recurrences and therefore only requires applications of `M^{-1}`.
+///
+/// The Hessian is only needed in the form of products with vectors. By default, the Hessian of the
+/// state is used for these products (it is evaluated at the initial parameter vector of the solver
+/// if the state does not provide one). In matrix-free mode (`matrix_free()`), the Hessian is never
+/// used and the products are computed via `ArgminHessianVecProduct::hessian_vec_product` at the
+/// initial parameter vector of the solver, or approximated by finite differences of the gradient
+/// if it is not implemented (see `argmin::hessian_vec_product`).
+///
+/// The reported cost is the value of the quadratic model `g^T p + 1/2 p^T H p` at the current
+/// step `p`.
+///
 /// # References:
 ///
 /// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
 /// Springer. ISBN 0-387-30303-0.
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Copy, PartialEq, PartialOrd, Default)]
-pub struct Steihaug<P> {
+pub struct Steihaug<P, M = IdentityPreconditioner, H = DenseHessian> {
+    /// point at which the quadratic model is built
+    x: P,
     /// Radius
     radius: f64,
     /// epsilon
     epsilon: f64,
     /// p
     p: P,
+    /// H p
+    hp: P,
     /// residual
     r: P,
-    /// r^Tr
+    /// r^T M^{-1} r
     rtr: f64,
     /// initial residual
     r_0_norm: f64,
     /// direction
     d: P,
+    /// p^T M p
+    pmp: f64,
+    /// p^T M d
+    pmd: f64,
+    /// d^T M d
+    dmd: f64,
     /// max iters
     max_iters: u64,
+    /// preconditioner
+    precond: M,
+    /// source of the Hessian-vector products
+    hessian_vec_products: std::marker::PhantomData<H>,
 }
 
-impl<P> Steihaug<P>
+impl<P> Steihaug<P, IdentityPreconditioner>
 where
     P: Default + Clone + ArgminMul<f64, P> + ArgminDot<P, f64> + ArgminAdd<P, P>,
 {
     /// Constructor
     pub fn new() -> Self {
         Steihaug {
+            x: P::default(),
             radius: std::f64::NAN,
             epsilon: 10e-10,
             p: P::default(),
+            hp: P::default(),
             r: P::default(),
             rtr: std::f64::NAN,
             r_0_norm: std::f64::NAN,
             d: P::default(),
+            pmp: std::f64::NAN,
+            pmd: std::f64::NAN,
+            dmd: std::f64::NAN,
             max_iters: std::u64::MAX,
+            precond: IdentityPreconditioner::new(),
+            hessian_vec_products: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<P, M> Steihaug<P, M, DenseHessian> {
+    /// Only use Hessian-vector products instead of the Hessian (see `argmin::hessian_vec_product`)
+    pub fn matrix_free(self) -> Steihaug<P, M, MatrixFree> {
+        Steihaug {
+            x: self.x,
+            radius: self.radius,
+            epsilon: self.epsilon,
+            p: self.p,
+            hp: self.hp,
+            r: self.r,
+            rtr: self.rtr,
+            r_0_norm: self.r_0_norm,
+            d: self.d,
+            pmp: self.pmp,
+            pmd: self.pmd,
+            dmd: self.dmd,
+            max_iters: self.max_iters,
+            precond: self.precond,
+            hessian_vec_products: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<P, M, H> Steihaug<P, M, H>
+where
+    P: Default + Clone + ArgminMul<f64, P> + ArgminDot<P, f64> + ArgminAdd<P, P>,
+{
+    /// Set preconditioner
+    pub fn preconditioner<M2: Preconditioner<P>>(self, precond: M2) -> Steihaug<P, M2, H> {
+        Steihaug {
+            x: self.x,
+            radius: self.radius,
+            epsilon: self.epsilon,
+            p: self.p,
+            hp: self.hp,
+            r: self.r,
+            rtr: self.rtr,
+            r_0_norm: self.r_0_norm,
+            d: self.d,
+            pmp: self.pmp,
+            pmd: self.pmd,
+            dmd: self.dmd,
+            max_iters: self.max_iters,
+            precond,
+            hessian_vec_products: self.hessian_vec_products,
         }
     }
 
@@ -78,25 +164,25 @@ where
         self
     }
 
-    /// evaluate m(p) (without considering f_init because it is not available)
-    fn eval_m<H>(&self, p: &P, g: &P, h: &H) -> f64
-    where
-        P: ArgminWeightedDot<P, f64, H>,
-    {
-        // self.cur_grad().dot(&p) + 0.5 * p.weighted_dot(&self.cur_hessian(), &p)
-        g.dot(&p) + 0.5 * p.weighted_dot(&h, &p)
+    /// evaluate m(p + tau * d) (without considering f_init because it is not available)
+    ///
+    /// Uses `H p` and `d^T H d` of the current iteration, therefore no further Hessian-vector
+    /// products are needed.
+    fn eval_m(&self, tau: f64, g: &P, dhd: f64) -> f64 {
+        let php: f64 = self.p.dot(&self.hp);
+        let dhp: f64 = self.d.dot(&self.hp);
+        g.dot(&self.p) + tau * g.dot(&self.d) + 0.5 * (php + 2.0 * tau * dhp + tau.powi(2) * dhd)
     }
 
     /// calculate all possible step lengths
     #[allow(clippy::many_single_char_names)]
-    fn tau<F, H>(&self, filter_func: F, eval: bool, g: &P, h: &H) -> f64
+    fn tau<F>(&self, filter_func: F, eval: bool, g: &P, dhd: f64) -> f64
     where
         F: Fn(f64) -> bool,
-        H: ArgminDot<P, P>,
     {
-        let a = self.p.dot(&self.p);
-        let b = self.d.dot(&self.d);
-        let c = self.p.dot(&self.d);
+        let a = self.pmp;
+        let b = self.dmd;
+        let c = self.pmd;
         let delta = self.radius.powi(2);
         let t1 = (-a * b + b * delta + c.powi(2)).sqrt();
         let tau1 = -(t1 + c) / b;
@@ -115,10 +201,7 @@ where
                 .cloned()
                 .enumerate()
                 .filter(|(_, tau)| (!tau.is_nan() || !tau.is_infinite()) && filter_func(*tau))
-                .map(|(i, tau)| {
-                    let p = self.p.add(&self.d.mul(&tau));
-                    (i, self.eval_m(&p, g, h))
-                })
+                .map(|(i, tau)| (i, self.eval_m(tau, g, dhd)))
                 .filter(|(_, m)| !m.is_nan() || !m.is_infinite())
                 .collect::<Vec<(usize, f64)>>();
             v.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
@@ -138,7 +221,7 @@ where
     }
 }
 
-impl<P, O> Solver<O> for Steihaug<P>
+impl<P, O, M, H> Solver<O> for Steihaug<P, M, H>
 where
     O: ArgminOp<Param = P, Output = f64>,
     P: Clone
@@ -146,14 +229,14 @@ where
         + SerializeAlias
         + DeserializeOwnedAlias
         + ArgminMul<f64, P>
-        + ArgminWeightedDot<P, f64, O::Hessian>
         + ArgminNorm<f64>
         + ArgminDot<P, f64>
         + ArgminAdd<P, P>
         + ArgminSub<P, P>
         + ArgminZeroLike
         + ArgminMul<f64, P>,
-    O::Hessian: ArgminDot<P, P>,
+    M: Preconditioner<P> + SerializeAlias,
+    H: HessianVecProducts<O> + Clone + SerializeAlias,
 {
     const NAME: &'static str = "Steihaug";
 
@@ -162,19 +245,24 @@ where
         _op: &mut OpWrapper<O>,
         state: &IterState<O>,
     ) -> Result<Option<ArgminIterData<O>>, Error> {
-        // let param = state.get_param();
+        self.x = state.get_param();
         self.r = state.get_grad().unwrap();
-        // .unwrap_or_else(|| op.gradient(&param).unwrap());
 
+        let y = self.precond.precondition(&self.r)?;
         self.r_0_norm = self.r.norm();
-        self.rtr = self.r.dot(&self.r);
-        self.d = self.r.mul(&(-1.0));
+        self.rtr = self.r.dot(&y);
+        self.d = y.mul(&(-1.0));
         self.p = self.r.zero_like();
+        self.hp = self.r.zero_like();
+        self.pmp = 0.0;
+        self.pmd = 0.0;
+        self.dmd = self.rtr;
 
         Ok(if self.r_0_norm < self.epsilon {
             Some(
                 ArgminIterData::new()
                     .param(self.p.clone())
+                    .cost(0.0)
                     .termination_reason(TerminationReason::TargetPrecisionReached),
             )
         } else {
@@ -184,18 +272,20 @@ where
 
     fn next_iter(
         &mut self,
-        _op: &mut OpWrapper<O>,
+        op: &mut OpWrapper<O>,
         state: &IterState<O>,
     ) -> Result<ArgminIterData<O>, Error> {
         let grad = state.get_grad().unwrap();
-        let h = state.get_hessian().unwrap();
-        let dhd = self.d.weighted_dot(&h, &self.d);
+        let h = H::hessian(op, &self.x, state.get_hessian())?;
+        let hd = H::product(op, &self.x, h.as_ref(), &self.d)?;
+        let dhd = self.d.dot(&hd);
 
         // Current search direction d is a direction of zero curvature or negative curvature
         if dhd <= 0.0 {
-            let tau = self.tau(|_| true, true, &grad, &h);
+            let tau = self.tau(|_| true, true, &grad, dhd);
             return Ok(ArgminIterData::new()
                 .param(self.p.add(&self.d.mul(&tau)))
+                .cost(self.eval_m(tau, &grad, dhd))
                 .termination_reason(TerminationReason::TargetPrecisionReached));
         }
 
@@ -203,33 +293,45 @@ where
         let p_n = self.p.add(&self.d.mul(&alpha));
 
         // new p violates trust region bound
-        if p_n.norm() >= self.radius {
-            let tau = self.tau(|x| x >= 0.0, false, &grad, &h);
+        let pmp_n = self.pmp + 2.0 * alpha * self.pmd + alpha.powi(2) * self.dmd;
+        if pmp_n.sqrt() >= self.radius {
+            let tau = self.tau(|x| x >= 0.0, false, &grad, dhd);
             return Ok(ArgminIterData::new()
                 .param(self.p.add(&self.d.mul(&tau)))
+                .cost(self.eval_m(tau, &grad, dhd))
                 .termination_reason(TerminationReason::TargetPrecisionReached));
         }
 
-        let r_n = self.r.add(&h.dot(&self.d).mul(&alpha));
+        let r_n = self.r.add(&hd.mul(&alpha));
 
         if r_n.norm() < self.epsilon * self.r_0_norm {
             return Ok(ArgminIterData::new()
                 .param(p_n)
+                .cost(self.eval_m(alpha, &grad, dhd))
                 .termination_reason(TerminationReason::TargetPrecisionReached));
         }
 
-        let rjtrj = r_n.dot(&r_n);
+        let y_n = self.precond.precondition(&r_n)?;
+        let rjtrj = r_n.dot(&y_n);
         let beta = rjtrj / self.rtr;
-        self.d = r_n.mul(&-1.0).add(&self.d.mul(&beta));
+        self.d = y_n.mul(&-1.0).add(&self.d.mul(&beta));
+        self.pmd = beta * (self.pmd + alpha * self.dmd);
+        self.dmd = rjtrj + beta.powi(2) * self.dmd;
+        self.pmp = pmp_n;
         self.r = r_n;
         self.p = p_n;
+        self.hp = self.hp.add(&hd.mul(&alpha));
         self.rtr = rjtrj;
 
-        Ok(ArgminIterData::new()
+        let cost = grad.dot(&self.p) + 0.5 * self.p.dot(&self.hp);
+        let data = ArgminIterData::new()
             .param(self.p.clone())
-            .cost(self.rtr)
-            .grad(grad)
-            .hessian(h))
+            .cost(cost)
+            .grad(grad);
+        Ok(match h {
+            Some(h) => data.hessian(h),
+            None => data,
+        })
     }
 
     fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
@@ -241,7 +343,12 @@ where
     }
 }
 
-impl<P: Clone + SerializeAlias> ArgminTrustRegion for Steihaug<P> {
+impl<P, M, H> ArgminTrustRegion for Steihaug<P, M, H>
+where
+    P: Clone + SerializeAlias,
+    M: Clone + SerializeAlias,
+    H: Clone + SerializeAlias,
+{
     fn set_radius(&mut self, radius: f64) {
         self.radius = radius;
     }
@@ -250,7 +357,72 @@ impl<P: Clone + SerializeAlias> ArgminTrustRegion for Steihaug<P> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::preconditioner::JacobiPreconditioner;
     use crate::test_trait_impl;
 
     test_trait_impl!(steihaug, Steihaug<MinimalNoOperator>);
+
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    #[derive(Clone, Default)]
+    struct Model {}
+
+    impl ArgminOp for Model {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+        type Jacobian = ();
+    }
+
+    impl ArgminHessianVecProduct for Model {
+        fn hessian_vec_product(&self, _p: &Vec<f64>, v: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![100.0 * v[0], v[1]])
+        }
+    }
+
+    #[test]
+    fn test_preconditioned_norm() {
+        // with M = H, the first direction is the Newton direction and the step ends on the
+        // boundary of the trust region in the norm induced by M
+        let precond = JacobiPreconditioner::new(&vec![100.0, 1.0]).unwrap();
+        let mut solver = Steihaug::new().preconditioner(precond);
+        solver.set_radius(0.5);
+        let res = Executor::new(Model {}, solver, vec![0.0, 0.0])
+            .grad(vec![1.0, 1.0])
+            .hessian(vec![vec![100.0, 0.0], vec![0.0, 1.0]])
+            .max_iters(10)
+            .run()
+            .unwrap();
+        let p = res.state.get_param();
+        let tau = 0.5 / 1.01f64.sqrt();
+        assert!((p[0] + 0.01 * tau).abs() < 1e-12);
+        assert!((p[1] + tau).abs() < 1e-12);
+        assert!(((100.0 * p[0].powi(2) + p[1].powi(2)).sqrt() - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_matrix_free() {
+        // in matrix-free mode, the products are computed by the operator and the Hessian of the
+        // state is not used
+        let mut solver = Steihaug::new().matrix_free();
+        solver.set_radius(10.0);
+        let res = Executor::new(Model {}, solver, vec![0.0, 0.0])
+            .grad(vec![1.0, 1.0])
+            .hessian(vec![vec![1.0, 0.0], vec![0.0, 1.0]])
+            .max_iters(10)
+            .run()
+            .unwrap();
+        let p = res.state.get_param();
+        assert!((p[0] + 0.01).abs() < 1e-12);
+        assert!((p[1] + 1.0).abs() < 1e-12);
+
+        let mut solver = Steihaug::new();
+        solver.set_radius(10.0);
+        let dense = Executor::new(Model {}, solver, vec![0.0, 0.0])
+            .grad(vec![1.0, 1.0])
+            .hessian(vec![vec![100.0, 0.0], vec![0.0, 1.0]])
+            .max_iters(10)
+            .run()
+            .unwrap();
+        assert_eq!(dense.state.get_param(), p);
+    }
 }
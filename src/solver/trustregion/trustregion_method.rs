@@ -10,8 +10,9 @@
 //! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
 //! Springer. ISBN 0-387-30303-0.
 
+use crate::hessian_vec_product::{DenseHessian, HessianVecProducts, MatrixFree};
 use crate::prelude::*;
-use crate::solver::trustregion::reduction_ratio;
+use crate::solver::trustregion::{reduction_ratio, ArgminTrustRegionSubproblem};
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -26,10 +27,23 @@ use std::fmt::Debug;
 /// * [Cauchy point](../cauchypoint/struct.CauchyPoint.html)
 /// * [Dogleg method](../dogleg/struct.Dogleg.html)
 /// * [Steihaug method](../steihaug/struct.Steihaug.html)
+/// * [Moré-Sorensen method](../moresorensen/struct.MoreSorensen.html)
+/// * [GLTR method](../gltr/struct.GLTR.html)
 ///
 /// This subproblem can be set via `set_subproblem(...)`. If this is not provided, it will default
 /// to the Steihaug method.
 ///
+/// In matrix-free mode (`matrix_free()`), `ArgminOp::hessian` is never called. This requires a
+/// subproblem solver which only needs Hessian-vector products, i.e. the Steihaug or GLTR method in
+/// matrix-free mode (see `argmin::hessian_vec_product`):
+///
+/// ```text
+/// TrustRegion::new(Steihaug::new().matrix_free()).matrix_free()
+/// ```
+///
+/// The value of the quadratic model at the step is then taken from the cost reported by the
+/// subproblem solver.
+///
 /// [Example](https://github.com/argmin-rs/argmin/blob/master/examples/trustregion_nd.rs)
 ///
 /// # References:
@@ -38,7 +52,7 @@ use std::fmt::Debug;
 /// Springer. ISBN 0-387-30303-0.
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
 #[derive(Clone)]
-pub struct TrustRegion<R> {
+pub struct TrustRegion<R, H = DenseHessian> {
     /// Radius
     radius: f64,
     /// Maximum Radius
@@ -51,9 +65,11 @@ pub struct TrustRegion<R> {
     fxk: f64,
     /// mk(0)
     mk0: f64,
+    /// source of the Hessian-vector products
+    hessian_vec_products: std::marker::PhantomData<H>,
 }
 
-impl<R> TrustRegion<R> {
+impl<R> TrustRegion<R, DenseHessian> {
     /// Constructor
     pub fn new(subproblem: R) -> Self {
         TrustRegion {
@@ -63,9 +79,30 @@ impl<R> TrustRegion<R> {
             subproblem,
             fxk: std::f64::NAN,
             mk0: std::f64::NAN,
+            hessian_vec_products: std::marker::PhantomData,
+        }
+    }
+
+    /// Only use Hessian-vector products instead of the Hessian
+    ///
+    /// Requires a subproblem solver in matrix-free mode.
+    pub fn matrix_free(self) -> TrustRegion<R, MatrixFree>
+    where
+        R: ArgminTrustRegionSubproblem<MatrixFree>,
+    {
+        TrustRegion {
+            radius: self.radius,
+            max_radius: self.max_radius,
+            eta: self.eta,
+            subproblem: self.subproblem,
+            fxk: self.fxk,
+            mk0: self.mk0,
+            hessian_vec_products: std::marker::PhantomData,
         }
     }
+}
 
+impl<R, H> TrustRegion<R, H> {
     /// set radius
     pub fn radius(mut self, radius: f64) -> Self {
         self.radius = radius;
@@ -80,7 +117,7 @@ impl<R> TrustRegion<R> {
 
     /// Set eta
     pub fn eta(mut self, eta: f64) -> Result<Self, Error> {
-        if eta >= 0.25 || eta < 0.0 {
+        if !(0.0..0.25).contains(&eta) {
             return Err(ArgminError::InvalidParameter {
                 text: "TrustRegion: eta must be in [0, 1/4).".to_string(),
             }
@@ -91,7 +128,7 @@ impl<R> TrustRegion<R> {
     }
 }
 
-impl<O, R> Solver<O> for TrustRegion<R>
+impl<O, R, H> Solver<O> for TrustRegion<R, H>
 where
     O: ArgminOp<Output = f64>,
     O::Param: Default
@@ -99,15 +136,15 @@ where
         + Debug
         + SerializeAlias
         + ArgminMul<f64, O::Param>
-        + ArgminWeightedDot<O::Param, f64, O::Hessian>
         + ArgminNorm<f64>
         + ArgminDot<O::Param, f64>
         + ArgminAdd<O::Param, O::Param>
         + ArgminSub<O::Param, O::Param>
         + ArgminZeroLike
         + ArgminMul<f64, O::Param>,
-    O::Hessian: Default + Clone + Debug + SerializeAlias + ArgminDot<O::Param, O::Param>,
-    R: ArgminTrustRegion + Solver<OpWrapper<O>>,
+    O::Hessian: Default + Clone + Debug + SerializeAlias,
+    R: ArgminTrustRegionSubproblem<H> + Solver<OpWrapper<O>>,
+    H: HessianVecProducts<O> + Clone + SerializeAlias,
 {
     const NAME: &'static str = "Trust region";
 
@@ -118,16 +155,16 @@ where
     ) -> Result<Option<ArgminIterData<O>>, Error> {
         let param = state.get_param();
         let grad = op.gradient(&param)?;
-        let hessian = op.hessian(&param)?;
         self.fxk = op.apply(&param)?;
         self.mk0 = self.fxk;
-        Ok(Some(
-            ArgminIterData::new()
-                .param(param)
-                .cost(self.fxk)
-                .grad(grad)
-                .hessian(hessian),
-        ))
+        let data = ArgminIterData::new()
+            .param(param.clone())
+            .cost(self.fxk)
+            .grad(grad);
+        Ok(Some(match H::hessian(op, &param, None)? {
+            Some(hessian) => data.hessian(hessian),
+            None => data,
+        }))
     }
 
     fn next_iter(
@@ -139,30 +176,43 @@ where
         let grad = state
             .get_grad()
             .unwrap_or_else(|| op.gradient(&param).unwrap());
-        let hessian = state
-            .get_hessian()
-            .unwrap_or_else(|| op.hessian(&param).unwrap());
+        let hessian = H::hessian(op, &param, state.get_hessian())?;
 
         self.subproblem.set_radius(self.radius);
 
-        let ArgminResult {
-            operator: sub_op,
-            state: IterState { param: pk, .. },
-        } = Executor::new(
-            OpWrapper::new_from_op(&op),
+        let executor = Executor::new(
+            OpWrapper::new_from_op(op),
             self.subproblem.clone(),
             param.clone(),
         )
         .grad(grad.clone())
-        .hessian(hessian.clone())
-        .ctrlc(false)
-        .run()?;
+        .ctrlc(false);
+        let executor = match hessian {
+            Some(ref hessian) => executor.hessian(hessian.clone()),
+            None => executor,
+        };
+        let ArgminResult {
+            operator: sub_op,
+            state:
+                IterState {
+                    param: pk,
+                    cost: sub_cost,
+                    ..
+                },
+        } = executor.run()?;
 
         op.consume_op(sub_op);
 
         let new_param = pk.add(&param);
         let fxkpk = op.apply(&new_param)?;
-        let mkpk = self.fxk + pk.dot(&grad) + 0.5 * pk.weighted_dot(&hessian, &pk);
+        let mkpk = match hessian {
+            Some(ref hessian) => {
+                let hpk = H::product(op, &param, Some(hessian), &pk)?;
+                self.fxk + pk.dot(&grad) + 0.5 * pk.dot(&hpk)
+            }
+            // the subproblem solver reports the value of the model (without f(x_k)) as cost
+            None => self.fxk + sub_cost,
+        };
 
         let rho = reduction_ratio(self.fxk, fxkpk, self.mk0, mkpk);
 
@@ -181,12 +231,14 @@ where
             self.fxk = fxkpk;
             self.mk0 = fxkpk;
             let grad = op.gradient(&new_param)?;
-            let hessian = op.hessian(&new_param)?;
-            ArgminIterData::new()
-                .param(new_param)
+            let data = ArgminIterData::new()
+                .param(new_param.clone())
                 .cost(fxkpk)
-                .grad(grad)
-                .hessian(hessian)
+                .grad(grad);
+            match H::hessian(op, &new_param, None)? {
+                Some(hessian) => data.hessian(hessian),
+                None => data,
+            }
         } else {
             ArgminIterData::new().param(param).cost(self.fxk)
         }
@@ -208,4 +260,15 @@ mod tests {
     type Operator = MinimalNoOperator;
 
     test_trait_impl!(trustregion, TrustRegion<Steihaug<Operator>>);
+
+    #[test]
+    fn test_matrix_free() {
+        use crate::solver::trustregion::GLTR;
+
+        // `matrix_free` is only available for subproblem solvers in matrix-free mode
+        let _: TrustRegion<Steihaug<Vec<f64>, _, MatrixFree>, MatrixFree> =
+            TrustRegion::new(Steihaug::new().matrix_free()).matrix_free();
+        let _: TrustRegion<GLTR<Vec<f64>, MatrixFree>, MatrixFree> =
+            TrustRegion::new(GLTR::new().matrix_free()).matrix_free();
+    }
 }
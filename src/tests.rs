@@ -16,6 +16,7 @@ use crate::solver::gradientdescent::SteepestDescent;
 use crate::solver::linesearch::{HagerZhangLineSearch, MoreThuenteLineSearch};
 use crate::solver::newton::NewtonCG;
 use crate::solver::quasinewton::{BFGS, DFP, LBFGS};
+use crate::solver::trustregion::{MoreSorensen, Steihaug, TrustRegion, GLTR};
 
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
@@ -90,6 +91,10 @@ impl ArgminOp for MaxEntropy {
     }
 }
 
+// The matrix-free tests approximate the Hessian-vector products by finite differences of the
+// gradient.
+impl ArgminHessianVecProduct for MaxEntropy {}
+
 macro_rules! entropy_max_tests {
     ($($name:ident: $solver:expr,)*) => {
     $(
@@ -117,7 +122,11 @@ entropy_max_tests! {
      test_max_entropy_bfgs: BFGS::new(Array2::eye(3), MoreThuenteLineSearch::new()),
      test_max_entropy_dfp: DFP::new(Array2::eye(3), MoreThuenteLineSearch::new()),
      test_max_entropy_newton_cg: NewtonCG::new(MoreThuenteLineSearch::new()),
+     test_max_entropy_newton_cg_matrix_free: NewtonCG::new(MoreThuenteLineSearch::new()).matrix_free(),
      test_max_entropy_steepest_descent: SteepestDescent::new(MoreThuenteLineSearch::new()),
+     test_max_entropy_trustregion_moresorensen: TrustRegion::new(MoreSorensen::new()),
+     test_max_entropy_trustregion_matrix_free: TrustRegion::new(Steihaug::new().matrix_free()).matrix_free(),
+     test_max_entropy_trustregion_gltr_matrix_free: TrustRegion::new(GLTR::new().matrix_free()).matrix_free(),
 }
 
 #[test]